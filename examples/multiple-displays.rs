@@ -60,14 +60,9 @@ fn main() -> Result<(), core::convert::Infallible> {
     let mut tft: SimulatorDisplay<Rgb565> = SimulatorDisplay::new(Size::new(320, 240));
     tft.clear(Rgb565::new(5, 10, 5)).unwrap();
 
-    Text::with_text_style(
-        &format!("Draw here"),
-        tft.bounding_box().center(),
-        TFT_TEXT,
-        CENTERED,
-    )
-    .draw(&mut tft)
-    .unwrap();
+    Text::with_text_style("Draw here", tft.bounding_box().center(), TFT_TEXT, CENTERED)
+        .draw(&mut tft)
+        .unwrap();
 
     // The simulated displays can now be added to common simulator window.
 
@@ -81,16 +76,13 @@ fn main() -> Result<(), core::convert::Infallible> {
         .build();
     let oled_size = oled_displays[0].output_size(&oled_settings);
 
-    for (oled, anchor) in oled_displays.iter().zip(
-        [
-            AnchorPoint::TopLeft,
-            AnchorPoint::TopCenter,
-            AnchorPoint::TopRight,
-        ]
-        .into_iter(),
-    ) {
+    for (oled, anchor) in oled_displays.iter().zip([
+        AnchorPoint::TopLeft,
+        AnchorPoint::TopCenter,
+        AnchorPoint::TopRight,
+    ]) {
         let offset = display_offset(window_size, oled_size, anchor);
-        window.add_display(&oled, offset, &oled_settings);
+        window.add_display(oled, offset, &oled_settings);
     }
 
     let tft_settings = OutputSettings::default();
@@ -79,14 +79,11 @@ fn main() -> Result<(), core::convert::Infallible> {
                     gate.store(true, Ordering::SeqCst);
                     display.clear(BinaryColor::On).unwrap();
                 }
-                SimulatorEvent::KeyUp { keycode, .. } => match keycode {
-                    Keycode::Space => {
-                        gate.store(false, Ordering::SeqCst);
-                        display.clear(BinaryColor::Off).unwrap();
-                        text.draw(&mut display).unwrap();
-                    }
-                    _ => {}
-                },
+                SimulatorEvent::KeyUp { keycode, .. } if keycode == Keycode::Space => {
+                    gate.store(false, Ordering::SeqCst);
+                    display.clear(BinaryColor::Off).unwrap();
+                    text.draw(&mut display).unwrap();
+                }
                 _ => {}
             }
         }
@@ -0,0 +1,158 @@
+//! Converts image files into Rust or C source code.
+//!
+//! The generated source contains the raw image data in the format expected by the `ImageRaw` type
+//! of embedded-graphics. Run `eg-simulator-export --help` for a list of all options.
+
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::PathBuf,
+    process,
+};
+
+use embedded_graphics::{
+    pixelcolor::{raw::ToBytes, BinaryColor, Gray2, Gray4, Gray8, Rgb555, Rgb565, Rgb666, Rgb888},
+    prelude::*,
+};
+use embedded_graphics_simulator::{
+    Dithering, ImportSettings, ImportSettingsBuilder, SimulatorDisplay, SourceExport,
+    SourceLanguage,
+};
+
+const USAGE: &str = "\
+Converts an image file into Rust or C source code.
+
+Usage: eg-simulator-export [OPTIONS] <IMAGE>
+
+PNG files are always supported. BMP, PBM, PGM, PPM, QOI and TGA files are supported if the
+corresponding features are enabled.
+
+Options:
+  --color <COLOR>   Color type: binary, gray2, gray4, gray8, rgb555, rgb565, rgb666 or rgb888
+                    [default: rgb888]
+  --name <NAME>     Name of the generated constants [default: file name of the image]
+  --dither <METHOD> Dithering method: threshold, ordered or floyd-steinberg
+                    [default: threshold]
+  --resize <WxH>    Resize the image to fit into the given size
+  --c               Generate a C header instead of Rust source code
+  --le              Use little endian byte order
+  -o <FILE>         Write the source code to a file instead of stdout
+  -h, --help        Print help";
+
+#[derive(Debug)]
+struct Args {
+    input: PathBuf,
+    output: Option<PathBuf>,
+    color: String,
+    settings: ImportSettings,
+    export: SourceExport,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut input = None;
+    let mut output = None;
+    let mut color = String::from("rgb888");
+    let mut name = None;
+    let mut language = SourceLanguage::Rust;
+    let mut little_endian = false;
+    let mut settings = ImportSettingsBuilder::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |option: &str| {
+            args.next()
+                .ok_or_else(|| format!("missing value for option \"{option}\""))
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                process::exit(0);
+            }
+            "--color" => color = value(&arg)?,
+            "--name" => name = Some(value(&arg)?),
+            "--dither" => {
+                settings = settings.dithering(match value(&arg)?.as_str() {
+                    "threshold" => Dithering::Threshold,
+                    "ordered" => Dithering::Ordered,
+                    "floyd-steinberg" => Dithering::FloydSteinberg,
+                    method => return Err(format!("unknown dithering method \"{method}\"")),
+                })
+            }
+            "--resize" => {
+                let size = value(&arg)?;
+                let (width, height) = size
+                    .split_once('x')
+                    .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                    .ok_or_else(|| format!("invalid size \"{size}\""))?;
+                settings = settings.resize_to_fit(Size::new(width, height));
+            }
+            "--c" => language = SourceLanguage::C,
+            "--le" => little_endian = true,
+            "-o" => output = Some(PathBuf::from(value(&arg)?)),
+            _ if arg.starts_with('-') => return Err(format!("unknown option \"{arg}\"")),
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument \"{arg}\"")),
+        }
+    }
+
+    let input = input.ok_or("missing image file")?;
+    let name = name.unwrap_or_else(|| {
+        input
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| String::from("image"))
+    });
+
+    Ok(Args {
+        input,
+        output,
+        color,
+        settings: settings.build(),
+        export: SourceExport::new(&name)
+            .language(language)
+            .little_endian(little_endian),
+    })
+}
+
+fn export<C>(args: &Args) -> Result<String, String>
+where
+    C: PixelColor + From<Rgb888> + Into<Rgb888> + ToBytes,
+    <C as ToBytes>::Bytes: AsRef<[u8]>,
+{
+    let display = SimulatorDisplay::<C>::load_with_settings(&args.input, &args.settings)
+        .map_err(|e| format!("couldn't load \"{}\": {e}", args.input.display()))?;
+
+    Ok(args.export.to_source(&display))
+}
+
+fn run() -> Result<(), String> {
+    let args = parse_args()?;
+
+    let source = match args.color.as_str() {
+        "binary" => export::<BinaryColor>(&args),
+        "gray2" => export::<Gray2>(&args),
+        "gray4" => export::<Gray4>(&args),
+        "gray8" => export::<Gray8>(&args),
+        "rgb555" => export::<Rgb555>(&args),
+        "rgb565" => export::<Rgb565>(&args),
+        "rgb666" => export::<Rgb666>(&args),
+        "rgb888" => export::<Rgb888>(&args),
+        color => Err(format!("unknown color type \"{color}\"")),
+    }?;
+
+    match args.output {
+        Some(path) => fs::write(&path, source)
+            .map_err(|e| format!("couldn't write \"{}\": {e}", path.display())),
+        None => io::stdout()
+            .write_all(source.as_bytes())
+            .map_err(|e| e.to_string()),
+    }
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("error: {e}\n\n{USAGE}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,454 @@
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    convert::Infallible,
+    error::Error,
+    fmt,
+    rc::Rc,
+};
+
+use embedded_graphics::prelude::*;
+use embedded_hal::{digital, i2c, spi};
+
+use crate::{
+    controller::{Controller, ControllerError},
+    display::SimulatorDisplay,
+};
+
+/// Error reported by the simulated bus devices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BusError {
+    /// The controller reported an error while decoding the bus traffic.
+    Controller(ControllerError),
+    /// An I2C transaction used a different address than the simulated device.
+    AddressNotAcknowledged(u8),
+    /// An I2C write contained an invalid control byte.
+    InvalidControlByte(u8),
+}
+
+impl fmt::Display for BusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BusError::Controller(error) => error.fmt(f),
+            BusError::AddressNotAcknowledged(address) => {
+                write!(f, "I2C address 0x{address:02X} not acknowledged")
+            }
+            BusError::InvalidControlByte(byte) => write!(f, "invalid control byte 0x{byte:02X}"),
+        }
+    }
+}
+
+impl Error for BusError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BusError::Controller(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ControllerError> for BusError {
+    fn from(error: ControllerError) -> Self {
+        BusError::Controller(error)
+    }
+}
+
+impl spi::Error for BusError {
+    fn kind(&self) -> spi::ErrorKind {
+        spi::ErrorKind::Other
+    }
+}
+
+impl i2c::Error for BusError {
+    fn kind(&self) -> i2c::ErrorKind {
+        match self {
+            BusError::AddressNotAcknowledged(_) => {
+                i2c::ErrorKind::NoAcknowledge(i2c::NoAcknowledgeSource::Address)
+            }
+            _ => i2c::ErrorKind::Other,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BusState<T> {
+    controller: T,
+    data: bool,
+}
+
+impl<T: Controller> BusState<T> {
+    fn write(&mut self, data: bool, bytes: &[u8]) -> Result<(), BusError> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+
+        if data {
+            self.controller.write_data(bytes)?;
+        } else {
+            self.controller.write_command(bytes)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Simulated display bus.
+///
+/// A display bus connects an emulated display controller to `embedded-hal` bus devices, which
+/// can be passed to existing display driver crates. The bus traffic is decoded into command and
+/// data writes to the controller and errors reported by the controller, e.g. writes outside the
+/// address window, are returned by the bus devices.
+///
+/// The following devices are available:
+///
+/// * [`SimulatorSpiDevice`], which uses a separate data/command pin ([`SimulatorDcPin`]) to
+///   distinguish between command and data bytes,
+/// * [`SimulatorI2c`], which uses control bytes to distinguish between command and data bytes.
+///
+/// The bus is a cheaply cloneable handle to the shared controller. It can be used to render the
+/// panel content into a [`SimulatorDisplay`] after the driver has sent its data.
+///
+/// This type is only available if the `embedded-hal` feature is enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_simulator::{ControllerBus, MipiDcs, MipiDcsModel, SimulatorDisplay};
+/// use embedded_hal::{digital::OutputPin, spi::SpiDevice};
+///
+/// let bus = ControllerBus::new(MipiDcs::new(MipiDcsModel::Ili9341, Size::new(240, 320)));
+///
+/// // The SPI device and DC pin are usually passed to a display driver.
+/// let mut spi = bus.spi_device();
+/// let mut dc = bus.dc_pin();
+///
+/// dc.set_low().unwrap();
+/// spi.write(&[0x11]).unwrap();
+/// spi.write(&[0x29]).unwrap();
+///
+/// let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(240, 320));
+/// bus.update(&mut display);
+/// ```
+#[derive(Debug)]
+pub struct ControllerBus<T> {
+    state: Rc<RefCell<BusState<T>>>,
+}
+
+impl<T> ControllerBus<T> {
+    /// Creates a new bus for the given controller.
+    pub fn new(controller: T) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(BusState {
+                controller,
+                data: false,
+            })),
+        }
+    }
+
+    /// Returns a SPI device that is connected to this bus.
+    ///
+    /// Bytes written to the SPI device are sent to the controller as commands or data, depending
+    /// on the state of the data/command pin.
+    pub fn spi_device(&self) -> SimulatorSpiDevice<T> {
+        SimulatorSpiDevice {
+            state: Rc::clone(&self.state),
+        }
+    }
+
+    /// Returns the data/command pin of this bus.
+    ///
+    /// A low level selects command bytes and a high level selects data bytes.
+    pub fn dc_pin(&self) -> SimulatorDcPin<T> {
+        SimulatorDcPin {
+            state: Rc::clone(&self.state),
+        }
+    }
+
+    /// Returns an I2C bus with a device with the given 7 bit address connected to this bus.
+    pub fn i2c(&self, address: u8) -> SimulatorI2c<T> {
+        SimulatorI2c {
+            state: Rc::clone(&self.state),
+            address,
+        }
+    }
+
+    /// Returns a reference to the controller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the controller is currently mutably borrowed.
+    pub fn controller(&self) -> Ref<'_, T> {
+        Ref::map(self.state.borrow(), |state| &state.controller)
+    }
+
+    /// Returns a mutable reference to the controller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the controller is currently borrowed.
+    pub fn controller_mut(&self) -> RefMut<'_, T> {
+        RefMut::map(self.state.borrow_mut(), |state| &mut state.controller)
+    }
+}
+
+impl<T: Controller> ControllerBus<T> {
+    /// Updates a simulator display with the panel content.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display size doesn't match the panel size.
+    pub fn update<C>(&self, display: &mut SimulatorDisplay<C>)
+    where
+        C: PixelColor + From<T::Color>,
+    {
+        self.controller().update(display);
+    }
+}
+
+impl<T> Clone for ControllerBus<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Rc::clone(&self.state),
+        }
+    }
+}
+
+/// Simulated SPI device.
+///
+/// Created by [`ControllerBus::spi_device`]. Read operations return zeros, because the emulated
+/// controllers don't support reading.
+#[derive(Debug)]
+pub struct SimulatorSpiDevice<T> {
+    state: Rc<RefCell<BusState<T>>>,
+}
+
+impl<T> spi::ErrorType for SimulatorSpiDevice<T> {
+    type Error = BusError;
+}
+
+impl<T: Controller> spi::SpiDevice for SimulatorSpiDevice<T> {
+    fn transaction(&mut self, operations: &mut [spi::Operation<'_, u8>]) -> Result<(), BusError> {
+        let mut state = self.state.borrow_mut();
+        let data = state.data;
+        let mut result = Ok(());
+
+        for operation in operations {
+            let written = match operation {
+                spi::Operation::Read(read) => {
+                    read.fill(0);
+                    Ok(())
+                }
+                spi::Operation::Write(write) => state.write(data, write),
+                spi::Operation::Transfer(read, write) => {
+                    read.fill(0);
+                    state.write(data, write)
+                }
+                spi::Operation::TransferInPlace(buffer) => {
+                    let written = state.write(data, buffer);
+                    buffer.fill(0);
+                    written
+                }
+                spi::Operation::DelayNs(_) => Ok(()),
+            };
+            result = result.and(written);
+        }
+
+        result
+    }
+}
+
+/// Simulated data/command pin.
+///
+/// Created by [`ControllerBus::dc_pin`].
+#[derive(Debug)]
+pub struct SimulatorDcPin<T> {
+    state: Rc<RefCell<BusState<T>>>,
+}
+
+impl<T> digital::ErrorType for SimulatorDcPin<T> {
+    type Error = Infallible;
+}
+
+impl<T> digital::OutputPin for SimulatorDcPin<T> {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.state.borrow_mut().data = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.state.borrow_mut().data = true;
+        Ok(())
+    }
+}
+
+/// Simulated I2C bus.
+///
+/// Created by [`ControllerBus::i2c`]. Each write starts with a control byte, like in the I2C
+/// interface of SSD1306 controllers. Bit 6 (D/C#) of the control byte selects command bytes (`0`)
+/// or data bytes (`1`). If bit 7 (Co) is set, only a single byte follows before the next control
+/// byte. Otherwise all remaining bytes of the write are sent with the same D/C# setting.
+///
+/// Read operations return zeros, because the emulated controllers don't support reading.
+#[derive(Debug)]
+pub struct SimulatorI2c<T> {
+    state: Rc<RefCell<BusState<T>>>,
+    address: u8,
+}
+
+impl<T> i2c::ErrorType for SimulatorI2c<T> {
+    type Error = BusError;
+}
+
+impl<T: Controller> i2c::I2c for SimulatorI2c<T> {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [i2c::Operation<'_>],
+    ) -> Result<(), BusError> {
+        if address != self.address {
+            return Err(BusError::AddressNotAcknowledged(address));
+        }
+
+        let mut state = self.state.borrow_mut();
+        let mut result = Ok(());
+
+        // Adjacent write operations are part of the same write and are decoded as one stream.
+        let mut control = None;
+        for operation in operations {
+            let mut bytes = match operation {
+                i2c::Operation::Read(read) => {
+                    read.fill(0);
+                    control = None;
+                    continue;
+                }
+                i2c::Operation::Write(write) => &write[..],
+            };
+
+            while !bytes.is_empty() {
+                match control {
+                    None => {
+                        let byte = bytes[0];
+                        if byte & 0x3F != 0 {
+                            return result.and(Err(BusError::InvalidControlByte(byte)));
+                        }
+                        control = Some(byte);
+                        bytes = &bytes[1..];
+                    }
+                    Some(byte) => {
+                        let continuation = byte & 0x80 != 0;
+                        let length = if continuation { 1 } else { bytes.len() };
+
+                        result = result.and(state.write(byte & 0x40 != 0, &bytes[..length]));
+                        bytes = &bytes[length..];
+
+                        if continuation {
+                            control = None;
+                        }
+                    }
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::pixelcolor::{BinaryColor, Rgb565};
+    use embedded_hal::{digital::OutputPin, i2c::I2c, spi::SpiDevice};
+
+    use crate::{MipiDcs, MipiDcsModel, Ssd1306};
+
+    #[test]
+    fn spi_mipi_dcs() {
+        let bus = ControllerBus::new(MipiDcs::new(MipiDcsModel::St7789, Size::new(4, 4)));
+        let (mut spi, mut dc) = (bus.spi_device(), bus.dc_pin());
+
+        for (command, parameters) in [
+            (0x11, &[][..]),
+            (0x3A, &[0x55]),
+            (0x29, &[]),
+            (0x2A, &[0, 1, 0, 1]),
+            (0x2B, &[0, 2, 0, 2]),
+            (0x2C, &[0x07, 0xE0]),
+        ] {
+            dc.set_low().unwrap();
+            spi.write(&[command]).unwrap();
+            dc.set_high().unwrap();
+            spi.write(parameters).unwrap();
+        }
+
+        let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(4, 4));
+        bus.update(&mut display);
+        assert_eq!(display.get_pixel(Point::new(1, 2)), Rgb565::GREEN);
+        assert!(bus.controller().is_display_on());
+
+        // The window only has room for a single pixel.
+        assert_eq!(
+            spi.write(&[0x07, 0xE0]),
+            Err(BusError::Controller(ControllerError::WindowOverflow))
+        );
+    }
+
+    #[test]
+    fn i2c_ssd1306() {
+        let bus = ControllerBus::new(Ssd1306::new(Size::new(128, 64)));
+        let mut i2c = bus.i2c(0x3C);
+
+        // command stream
+        i2c.write(0x3C, &[0x00, 0xAF, 0x20, 0x00]).unwrap();
+        // single command followed by a data stream
+        i2c.write(0x3C, &[0x80, 0xA7, 0x40, 0x01, 0x01]).unwrap();
+
+        let controller = bus.controller();
+        assert!(controller.is_display_on());
+        assert!(controller.is_inverted());
+        assert_eq!(&controller.ram()[0..3], &[0x01, 0x01, 0x00]);
+        drop(controller);
+
+        let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(128, 64));
+        bus.update(&mut display);
+        assert_eq!(display.get_pixel(Point::new(0, 0)), BinaryColor::Off);
+        assert_eq!(display.get_pixel(Point::new(0, 1)), BinaryColor::On);
+    }
+
+    #[test]
+    fn i2c_split_writes() {
+        let bus = ControllerBus::new(Ssd1306::new(Size::new(128, 64)));
+        let mut i2c = bus.i2c(0x3C);
+
+        i2c.transaction(
+            0x3C,
+            &mut [
+                i2c::Operation::Write(&[0x00]),
+                i2c::Operation::Write(&[0x81, 0x20]),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(bus.controller().contrast(), 0x20);
+    }
+
+    #[test]
+    fn i2c_errors() {
+        let bus = ControllerBus::new(Ssd1306::new(Size::new(128, 64)));
+        let mut i2c = bus.i2c(0x3D);
+
+        assert_eq!(
+            i2c.write(0x3C, &[0x00, 0xAF]),
+            Err(BusError::AddressNotAcknowledged(0x3C))
+        );
+        assert_eq!(
+            i2c.write(0x3D, &[0x01, 0xAF]),
+            Err(BusError::InvalidControlByte(0x01))
+        );
+        assert_eq!(
+            i2c.write(0x3D, &[0x00, 0xFF, 0xAF]),
+            Err(BusError::Controller(ControllerError::UnknownCommand(0xFF)))
+        );
+        assert!(bus.controller().is_display_on());
+    }
+}
@@ -0,0 +1,899 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process,
+};
+#[cfg(feature = "json")]
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    diff::{dimmed_grayscale, DisplayDiff},
+    display::SimulatorDisplay,
+    metrics::{delta_e, ImageMetrics},
+    output_image::OutputImage,
+    theme::BinaryColorTheme,
+    OutputSettings,
+};
+
+/// Tolerance for reference image comparisons.
+///
+/// By default two images are only considered equal if all pixels match exactly. The tolerance can
+/// be used to ignore small differences, e.g. caused by anti-aliasing.
+///
+/// The tolerance used by the `EG_SIMULATOR_CHECK` and `EG_SIMULATOR_CHECK_RAW` environment
+/// variables can be configured by using the `EG_SIMULATOR_CHECK_THRESHOLD` and
+/// `EG_SIMULATOR_CHECK_MAX_DIFF_PIXELS` environment variables, which correspond to
+/// [`channel_threshold`](Self::channel_threshold) and
+/// [`max_differing_pixels`](Self::max_differing_pixels).
+///
+/// The perceptual criteria [`max_delta_e`](Self::max_delta_e), [`min_ssim`](Self::min_ssim) and
+/// [`max_mean_absolute_error`](Self::max_mean_absolute_error) can be used to accept images that
+/// are visually the same. They are configured by the `EG_SIMULATOR_CHECK_MAX_DELTA_E`,
+/// `EG_SIMULATOR_CHECK_MIN_SSIM` and `EG_SIMULATOR_CHECK_MAX_MAE` environment variables. See
+/// [`ImageMetrics`] for a description of the metrics.
+///
+/// Images only match if all configured criteria are met.
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics_simulator::Tolerance;
+///
+/// // Ignore color differences that aren't noticeable.
+/// let tolerance = Tolerance::exact().max_delta_e(2.3);
+///
+/// // Only compare the structural similarity.
+/// let tolerance = Tolerance::exact()
+///     .max_differing_pixels(usize::MAX)
+///     .min_ssim(0.98);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Tolerance {
+    channel_threshold: u8,
+    max_differing_pixels: usize,
+    max_delta_e: f64,
+    min_ssim: Option<f64>,
+    max_mean_absolute_error: Option<f64>,
+}
+
+impl Tolerance {
+    /// Creates a tolerance that only accepts exact matches.
+    pub const fn exact() -> Self {
+        Self {
+            channel_threshold: 0,
+            max_differing_pixels: 0,
+            max_delta_e: 0.0,
+            min_ssim: None,
+            max_mean_absolute_error: None,
+        }
+    }
+
+    /// Sets the per channel threshold.
+    ///
+    /// A pixel is only counted as different if the absolute difference of at least one of its
+    /// red, green or blue channels is larger than the threshold.
+    pub const fn channel_threshold(mut self, channel_threshold: u8) -> Self {
+        self.channel_threshold = channel_threshold;
+
+        self
+    }
+
+    /// Sets the maximum number of differing pixels.
+    ///
+    /// Images are considered equal if the number of differing pixels doesn't exceed this value.
+    pub const fn max_differing_pixels(mut self, max_differing_pixels: usize) -> Self {
+        self.max_differing_pixels = max_differing_pixels;
+
+        self
+    }
+
+    /// Sets the maximum color difference of a single pixel.
+    ///
+    /// A pixel is only counted as different if its CIE76 delta E is larger than this value, in
+    /// addition to the [`channel_threshold`](Self::channel_threshold) being exceeded. A value of
+    /// about `2.3` corresponds to a just noticeable difference.
+    pub const fn max_delta_e(mut self, max_delta_e: f64) -> Self {
+        self.max_delta_e = max_delta_e;
+
+        self
+    }
+
+    /// Sets the minimum structural similarity index (SSIM).
+    ///
+    /// Images with a lower SSIM are considered different.
+    pub const fn min_ssim(mut self, min_ssim: f64) -> Self {
+        self.min_ssim = Some(min_ssim);
+
+        self
+    }
+
+    /// Sets the maximum mean absolute error.
+    ///
+    /// Images with a higher mean absolute channel error are considered different.
+    pub const fn max_mean_absolute_error(mut self, max_mean_absolute_error: f64) -> Self {
+        self.max_mean_absolute_error = Some(max_mean_absolute_error);
+
+        self
+    }
+
+    /// Creates a tolerance based on the `EG_SIMULATOR_CHECK_*` environment variables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value of one of the variables is invalid.
+    pub(crate) fn from_env() -> Self {
+        let mut tolerance = Self::exact();
+
+        if let Ok(value) = env::var("EG_SIMULATOR_CHECK_THRESHOLD") {
+            tolerance.channel_threshold = value
+                .parse()
+                .expect("EG_SIMULATOR_CHECK_THRESHOLD must be a number between 0 and 255");
+        }
+
+        if let Ok(value) = env::var("EG_SIMULATOR_CHECK_MAX_DIFF_PIXELS") {
+            tolerance.max_differing_pixels = value
+                .parse()
+                .expect("EG_SIMULATOR_CHECK_MAX_DIFF_PIXELS must be a pixel count");
+        }
+
+        if let Ok(value) = env::var("EG_SIMULATOR_CHECK_MAX_DELTA_E") {
+            tolerance.max_delta_e = value
+                .parse()
+                .expect("EG_SIMULATOR_CHECK_MAX_DELTA_E must be a number");
+        }
+
+        if let Ok(value) = env::var("EG_SIMULATOR_CHECK_MIN_SSIM") {
+            tolerance.min_ssim = Some(
+                value
+                    .parse()
+                    .expect("EG_SIMULATOR_CHECK_MIN_SSIM must be a number"),
+            );
+        }
+
+        if let Ok(value) = env::var("EG_SIMULATOR_CHECK_MAX_MAE") {
+            tolerance.max_mean_absolute_error = Some(
+                value
+                    .parse()
+                    .expect("EG_SIMULATOR_CHECK_MAX_MAE must be a number"),
+            );
+        }
+
+        tolerance
+    }
+
+    fn pixels_differ(&self, a: Rgb888, b: Rgb888) -> bool {
+        (a.r().abs_diff(b.r()) > self.channel_threshold
+            || a.g().abs_diff(b.g()) > self.channel_threshold
+            || a.b().abs_diff(b.b()) > self.channel_threshold)
+            && (self.max_delta_e <= 0.0 || delta_e(a, b) > self.max_delta_e)
+    }
+
+    /// Compares two images of the same size.
+    ///
+    /// Returns `None` if the images match within this tolerance.
+    pub(crate) fn compare(
+        &self,
+        expected: &SimulatorDisplay<Rgb888>,
+        actual: &SimulatorDisplay<Rgb888>,
+    ) -> Option<Mismatch> {
+        // Differences that are within the tolerance are ignored.
+        let diff = actual
+            .diff(expected)
+            .expect("images must have the same size")
+            .and_then(|diff| diff.retain(|change| self.pixels_differ(change.old, change.new)));
+        let differing_pixels = diff.as_ref().map_or(0, DisplayDiff::changed_pixels);
+
+        let mut failed_criteria = Vec::new();
+        if self.min_ssim.is_some() || self.max_mean_absolute_error.is_some() {
+            let metrics = ImageMetrics::new(expected, actual);
+
+            if let Some(min_ssim) = self.min_ssim {
+                if metrics.ssim() < min_ssim {
+                    failed_criteria.push(format!("SSIM {:.4} is below {min_ssim}", metrics.ssim()));
+                }
+            }
+
+            if let Some(max_mae) = self.max_mean_absolute_error {
+                if metrics.mean_absolute_error() > max_mae {
+                    failed_criteria.push(format!(
+                        "mean absolute error {:.4} exceeds {max_mae}",
+                        metrics.mean_absolute_error()
+                    ));
+                }
+            }
+        }
+
+        if differing_pixels <= self.max_differing_pixels && failed_criteria.is_empty() {
+            return None;
+        }
+
+        Some(Mismatch {
+            diff,
+            failed_criteria,
+        })
+    }
+}
+
+/// Differences between a reference image and the actual display content.
+pub(crate) struct Mismatch {
+    /// Differing pixels, which is `None` if only perceptual criteria failed.
+    pub diff: Option<DisplayDiff<Rgb888>>,
+    /// Descriptions of the failed perceptual criteria.
+    pub failed_criteria: Vec<String>,
+}
+
+impl Mismatch {
+    /// Returns the number of differing pixels.
+    pub fn differing_pixels(&self) -> usize {
+        self.diff.as_ref().map_or(0, DisplayDiff::changed_pixels)
+    }
+
+    /// Returns the bounding box of the differing pixels.
+    pub fn bounding_box(&self) -> Option<Rectangle> {
+        self.diff.as_ref().map(DisplayDiff::bounding_box)
+    }
+
+    /// Returns a human readable summary of the differences.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+
+        if let Some(diff) = &self.diff {
+            let area = diff.bounding_box();
+            parts.push(format!(
+                "{} pixels differ in area ({}, {}) {}x{}",
+                diff.changed_pixels(),
+                area.top_left.x,
+                area.top_left.y,
+                area.size.width,
+                area.size.height,
+            ));
+        }
+        parts.extend(self.failed_criteria.iter().cloned());
+
+        parts.join(", ")
+    }
+
+    /// Renders the differences into an image.
+    ///
+    /// All matching pixels are shown as a dimmed grayscale version of the actual image and
+    /// differing pixels are highlighted in red.
+    pub fn to_diff_image(&self, actual: &SimulatorDisplay<Rgb888>) -> SimulatorDisplay<Rgb888> {
+        let mut image = dimmed_grayscale(actual, BinaryColorTheme::Default);
+
+        for change in self.diff.iter().flat_map(DisplayDiff::changes) {
+            Pixel(change.point, Rgb888::RED).draw(&mut image).unwrap();
+        }
+
+        image
+    }
+
+    /// Renders an image that shows the expected image, the actual image and the differences side
+    /// by side.
+    ///
+    /// The differences are rendered by using [`to_diff_image`](Self::to_diff_image).
+    pub fn to_artifact_image(
+        &self,
+        expected: &SimulatorDisplay<Rgb888>,
+        actual: &SimulatorDisplay<Rgb888>,
+    ) -> OutputImage<Rgb888> {
+        const GAP: u32 = 4;
+
+        let size = expected.size();
+        let diff = self.to_diff_image(actual);
+
+        let pitch = (size.width + GAP) as i32;
+        let mut image = OutputImage::new(Size::new(size.width * 3 + GAP * 2, size.height));
+        image.clear(Rgb888::new(128, 128, 128)).unwrap();
+
+        let settings = OutputSettings::default();
+        image.draw_display(expected, Point::zero(), &settings);
+        image.draw_display(actual, Point::new(pitch, 0), &settings);
+        image.draw_display(&diff, Point::new(pitch * 2, 0), &settings);
+
+        image
+    }
+}
+
+/// Returns the path of the diff artifact for a reference image.
+///
+/// The path can be set by using the `EG_SIMULATOR_CHECK_DIFF` environment variable. By default
+/// the artifact is written next to the reference image, e.g. `reference.diff.png` for
+/// `reference.png`.
+pub(crate) fn diff_artifact_path(reference: &Path) -> PathBuf {
+    env::var_os("EG_SIMULATOR_CHECK_DIFF")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| reference.with_extension("diff.png"))
+}
+
+/// Status of a reference image check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "snake_case"))]
+pub enum CheckStatus {
+    /// The display content matches the reference image.
+    Passed,
+    /// The display content doesn't match the reference image.
+    Mismatch,
+    /// The display and the reference image don't have the same size.
+    SizeMismatch,
+    /// The reference image is missing or couldn't be loaded.
+    ReferenceMissing,
+}
+
+impl CheckStatus {
+    /// Returns the exit code that is used for this status.
+    ///
+    /// | Status             | Exit code |
+    /// |--------------------|-----------|
+    /// | `Passed`           | 0         |
+    /// | `Mismatch`         | 10        |
+    /// | `SizeMismatch`     | 11        |
+    /// | `ReferenceMissing` | 12        |
+    pub const fn exit_code(self) -> i32 {
+        match self {
+            CheckStatus::Passed => 0,
+            CheckStatus::Mismatch => 10,
+            CheckStatus::SizeMismatch => 11,
+            CheckStatus::ReferenceMissing => 12,
+        }
+    }
+}
+
+/// Result of a reference image check.
+///
+/// If the `json` feature is enabled, a check report is written in JSON format to the path in the
+/// `EG_SIMULATOR_CHECK_REPORT` environment variable after a `EG_SIMULATOR_CHECK` or
+/// `EG_SIMULATOR_CHECK_RAW` check was performed:
+///
+/// ```json
+/// {
+///   "status": "mismatch",
+///   "expected_path": "screenshot.png",
+///   "expected_size": { "width": 128, "height": 64 },
+///   "actual_size": { "width": 128, "height": 64 },
+///   "actual_path": "screenshot.actual.png",
+///   "differing_pixels": 12,
+///   "bounding_box": { "x": 10, "y": 4, "width": 3, "height": 4 },
+///   "diff_path": "screenshot.diff.png",
+///   "message": "display content doesn't match PNG file: ...",
+///   "tolerance": {
+///     "channel_threshold": 0,
+///     "max_differing_pixels": 0,
+///     "max_delta_e": 0.0,
+///     "min_ssim": null,
+///     "max_mean_absolute_error": null
+///   }
+/// }
+/// ```
+///
+/// `expected_size` is `null` if the reference image couldn't be loaded, `actual_path` is `null` if
+/// the check passed, and `differing_pixels`, `bounding_box` and `diff_path` are `null` unless the
+/// status is `mismatch`. `tolerance` contains the tolerance that was used for the comparison.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct CheckReport {
+    status: CheckStatus,
+    expected_path: PathBuf,
+    expected_size: Option<SizeRecord>,
+    actual_size: SizeRecord,
+    actual_path: Option<PathBuf>,
+    differing_pixels: Option<usize>,
+    bounding_box: Option<RectangleRecord>,
+    diff_path: Option<PathBuf>,
+    pub(crate) message: String,
+    #[cfg_attr(feature = "json", serde(default))]
+    tolerance: Tolerance,
+}
+
+impl CheckReport {
+    /// Loads a check report from a JSON file.
+    ///
+    /// This method requires the `json` feature.
+    #[cfg(feature = "json")]
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    /// Saves the check report to a JSON file.
+    ///
+    /// This method requires the `json` feature.
+    #[cfg(feature = "json")]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+
+        writer.flush()
+    }
+
+    /// Returns the check status.
+    pub fn status(&self) -> CheckStatus {
+        self.status
+    }
+
+    /// Returns the path of the reference image.
+    pub fn expected_path(&self) -> &Path {
+        &self.expected_path
+    }
+
+    /// Returns the size of the reference image.
+    ///
+    /// Returns `None` if the reference image couldn't be loaded.
+    pub fn expected_size(&self) -> Option<Size> {
+        self.expected_size.map(Size::from)
+    }
+
+    /// Returns the size of the display content.
+    pub fn actual_size(&self) -> Size {
+        self.actual_size.into()
+    }
+
+    /// Returns the number of differing pixels.
+    pub fn differing_pixels(&self) -> Option<usize> {
+        self.differing_pixels
+    }
+
+    /// Returns the bounding box of all differing pixels.
+    pub fn bounding_box(&self) -> Option<Rectangle> {
+        self.bounding_box.map(Rectangle::from)
+    }
+
+    /// Returns the path of the diff artifact.
+    pub fn diff_path(&self) -> Option<&Path> {
+        self.diff_path.as_deref()
+    }
+
+    /// Returns the path of the saved display content.
+    ///
+    /// The display content is only saved if the check didn't pass.
+    pub fn actual_path(&self) -> Option<&Path> {
+        self.actual_path.as_deref()
+    }
+
+    /// Returns a human readable description of the result.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the tolerance that was used to compare the display content.
+    pub fn tolerance(&self) -> Tolerance {
+        self.tolerance
+    }
+
+    /// Saves the actual display content to `path` and records the path in the report.
+    fn save_actual(&mut self, actual: &SimulatorDisplay<Rgb888>, path: &Path) {
+        let image = actual.to_rgb_output_image(&OutputSettings::default());
+
+        match image.save_png(path) {
+            Ok(()) => self.actual_path = Some(path.to_path_buf()),
+            Err(e) => {
+                self.message += &format!("; failed to write actual image {}: {e}", path.display())
+            }
+        }
+    }
+
+    /// Writes the report and terminates the process with the exit code of the check status.
+    ///
+    /// The report is only written if the `json` feature is enabled and the
+    /// `EG_SIMULATOR_CHECK_REPORT` environment variable is set. The message is printed to stderr
+    /// unless the check passed.
+    pub(crate) fn exit(&self) -> ! {
+        #[cfg(feature = "json")]
+        if let Some(path) = env::var_os("EG_SIMULATOR_CHECK_REPORT") {
+            if let Err(e) = self.save(&path) {
+                eprintln!(
+                    "failed to write check report {}: {e}",
+                    Path::new(&path).display()
+                );
+            }
+        }
+
+        if self.status != CheckStatus::Passed {
+            eprintln!("{}", self.message);
+        }
+
+        process::exit(self.status.exit_code())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+struct SizeRecord {
+    width: u32,
+    height: u32,
+}
+
+impl From<Size> for SizeRecord {
+    fn from(size: Size) -> Self {
+        Self {
+            width: size.width,
+            height: size.height,
+        }
+    }
+}
+
+impl From<SizeRecord> for Size {
+    fn from(size: SizeRecord) -> Self {
+        Size::new(size.width, size.height)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+struct RectangleRecord {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl From<Rectangle> for RectangleRecord {
+    fn from(rectangle: Rectangle) -> Self {
+        Self {
+            x: rectangle.top_left.x,
+            y: rectangle.top_left.y,
+            width: rectangle.size.width,
+            height: rectangle.size.height,
+        }
+    }
+}
+
+impl From<RectangleRecord> for Rectangle {
+    fn from(rectangle: RectangleRecord) -> Self {
+        Rectangle::new(
+            Point::new(rectangle.x, rectangle.y),
+            Size::new(rectangle.width, rectangle.height),
+        )
+    }
+}
+
+/// Compares the display content with a reference image by using the settings from the
+/// `EG_SIMULATOR_CHECK_*` environment variables.
+pub(crate) fn check_reference_from_env(
+    expected: image::ImageResult<SimulatorDisplay<Rgb888>>,
+    actual: &SimulatorDisplay<Rgb888>,
+    reference_path: &Path,
+) -> CheckReport {
+    check_reference(
+        expected,
+        actual,
+        reference_path,
+        &reference_path.with_extension("actual.png"),
+        &diff_artifact_path(reference_path),
+        &Tolerance::from_env(),
+    )
+}
+
+/// Compares the display content with a reference image.
+///
+/// If the check doesn't pass, the display content is written to `actual_path` and, if the
+/// content doesn't match, a diff artifact is written to `diff_path`.
+pub(crate) fn check_reference(
+    expected: image::ImageResult<SimulatorDisplay<Rgb888>>,
+    actual: &SimulatorDisplay<Rgb888>,
+    reference_path: &Path,
+    actual_path: &Path,
+    diff_path: &Path,
+    tolerance: &Tolerance,
+) -> CheckReport {
+    let mut report = CheckReport {
+        status: CheckStatus::Passed,
+        expected_path: reference_path.to_path_buf(),
+        expected_size: None,
+        actual_size: actual.size().into(),
+        actual_path: None,
+        differing_pixels: None,
+        bounding_box: None,
+        diff_path: None,
+        message: String::from("display content matches PNG file"),
+        tolerance: *tolerance,
+    };
+
+    let expected = match expected {
+        Ok(expected) => expected,
+        Err(e) => {
+            report.status = CheckStatus::ReferenceMissing;
+            report.message = format!(
+                "failed to load reference PNG file {}: {e}",
+                reference_path.display()
+            );
+            report.save_actual(actual, actual_path);
+            return report;
+        }
+    };
+
+    report.expected_size = Some(expected.size().into());
+
+    if expected.size() != actual.size() {
+        report.status = CheckStatus::SizeMismatch;
+        report.message = format!(
+            "display dimensions don't match PNG dimensions (display: {}x{}, PNG: {}x{})",
+            actual.size().width,
+            actual.size().height,
+            expected.size().width,
+            expected.size().height
+        );
+        report.save_actual(actual, actual_path);
+        return report;
+    }
+
+    if let Some(mismatch) = tolerance.compare(&expected, actual) {
+        let artifact = mismatch.to_artifact_image(&expected, actual);
+        let artifact_status = match artifact.save_png(diff_path) {
+            Ok(()) => {
+                report.diff_path = Some(diff_path.to_path_buf());
+                format!("diff image written to {}", diff_path.display())
+            }
+            Err(e) => format!("failed to write diff image {}: {e}", diff_path.display()),
+        };
+
+        report.status = CheckStatus::Mismatch;
+        report.differing_pixels = Some(mismatch.differing_pixels());
+        report.bounding_box = mismatch.bounding_box().map(Into::into);
+        report.message = format!(
+            "display content doesn't match PNG file: {}; {}",
+            mismatch.summary(),
+            artifact_status,
+        );
+        report.save_actual(actual, actual_path);
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_utils::TempDir;
+
+    fn display(pixels: &[(i32, i32, Rgb888)]) -> SimulatorDisplay<Rgb888> {
+        let mut display = SimulatorDisplay::with_default_color(Size::new(8, 4), Rgb888::BLACK);
+        for (x, y, color) in pixels {
+            Pixel(Point::new(*x, *y), *color)
+                .draw(&mut display)
+                .unwrap();
+        }
+
+        display
+    }
+
+    #[test]
+    fn exact() {
+        let expected = display(&[(1, 1, Rgb888::WHITE)]);
+
+        assert!(Tolerance::exact().compare(&expected, &expected).is_none());
+
+        let actual = display(&[(1, 1, Rgb888::new(255, 254, 255))]);
+        let mismatch = Tolerance::exact().compare(&expected, &actual).unwrap();
+        assert_eq!(mismatch.differing_pixels(), 1);
+        assert_eq!(
+            mismatch.bounding_box(),
+            Some(Rectangle::new(Point::new(1, 1), Size::new(1, 1)))
+        );
+    }
+
+    #[test]
+    fn channel_threshold() {
+        let expected = display(&[(1, 1, Rgb888::new(100, 100, 100))]);
+        let actual = display(&[(1, 1, Rgb888::new(102, 98, 100))]);
+
+        let tolerance = Tolerance::exact().channel_threshold(2);
+        assert!(tolerance.compare(&expected, &actual).is_none());
+
+        let tolerance = Tolerance::exact().channel_threshold(1);
+        assert!(tolerance.compare(&expected, &actual).is_some());
+    }
+
+    #[test]
+    fn max_differing_pixels() {
+        let expected = display(&[]);
+        let actual = display(&[(1, 0, Rgb888::RED), (6, 3, Rgb888::GREEN)]);
+
+        let tolerance = Tolerance::exact().max_differing_pixels(2);
+        assert!(tolerance.compare(&expected, &actual).is_none());
+
+        let tolerance = Tolerance::exact().max_differing_pixels(1);
+        let mismatch = tolerance.compare(&expected, &actual).unwrap();
+        assert_eq!(mismatch.differing_pixels(), 2);
+        assert_eq!(
+            mismatch.bounding_box(),
+            Some(Rectangle::with_corners(Point::new(1, 0), Point::new(6, 3)))
+        );
+    }
+
+    #[test]
+    fn max_delta_e() {
+        let expected = display(&[(1, 1, Rgb888::new(100, 100, 100)), (2, 1, Rgb888::BLACK)]);
+        let actual = display(&[(1, 1, Rgb888::new(101, 100, 99)), (2, 1, Rgb888::WHITE)]);
+
+        let tolerance = Tolerance::exact().max_delta_e(2.3);
+        let mismatch = tolerance.compare(&expected, &actual).unwrap();
+        assert_eq!(mismatch.differing_pixels(), 1);
+        assert_eq!(mismatch.summary(), "1 pixels differ in area (2, 1) 1x1");
+    }
+
+    #[test]
+    fn perceptual_criteria() {
+        let expected = display(&[(1, 1, Rgb888::WHITE)]);
+        let actual = display(&[(1, 1, Rgb888::new(250, 250, 250))]);
+
+        let tolerance = Tolerance::exact()
+            .max_differing_pixels(usize::MAX)
+            .min_ssim(0.99)
+            .max_mean_absolute_error(1.0);
+        assert!(tolerance.compare(&expected, &actual).is_none());
+
+        let actual = display(&[(1, 1, Rgb888::BLACK), (5, 2, Rgb888::WHITE)]);
+        let mismatch = tolerance.compare(&expected, &actual).unwrap();
+        assert!(
+            mismatch
+                .summary()
+                .starts_with("2 pixels differ in area (1, 1) 5x2, SSIM "),
+            "{}",
+            mismatch.summary()
+        );
+        assert!(
+            mismatch
+                .summary()
+                .contains(", mean absolute error 15.9375 exceeds 1"),
+            "{}",
+            mismatch.summary()
+        );
+    }
+
+    #[test]
+    fn artifact_image() {
+        let expected = display(&[]);
+        let actual = display(&[(2, 1, Rgb888::WHITE)]);
+
+        let mismatch = Tolerance::exact().compare(&expected, &actual).unwrap();
+        let image = mismatch.to_artifact_image(&expected, &actual);
+        assert_eq!(image.size(), Size::new(8 * 3 + 8, 4));
+
+        let image = image.to_rgb_display();
+        assert_eq!(image.get_pixel(Point::new(2, 1)), Rgb888::BLACK);
+        assert_eq!(
+            image.get_pixel(Point::new(8, 0)),
+            Rgb888::new(128, 128, 128)
+        );
+        assert_eq!(image.get_pixel(Point::new(12 + 2, 1)), Rgb888::WHITE);
+        assert_eq!(image.get_pixel(Point::new(24 + 2, 1)), Rgb888::RED);
+        assert_eq!(image.get_pixel(Point::new(24 + 3, 1)), Rgb888::BLACK);
+    }
+
+    fn check(
+        expected: image::ImageResult<SimulatorDisplay<Rgb888>>,
+        actual: &SimulatorDisplay<Rgb888>,
+        reference: &Path,
+    ) -> CheckReport {
+        check_reference(
+            expected,
+            actual,
+            reference,
+            &reference.with_extension("actual.png"),
+            &reference.with_extension("diff.png"),
+            &Tolerance::exact(),
+        )
+    }
+
+    #[test]
+    fn check_passed() {
+        let expected = display(&[(1, 1, Rgb888::WHITE)]);
+        let dir = TempDir::new("check-passed");
+        let reference = dir.join("passed.png");
+
+        let report = check(Ok(expected.clone()), &expected, &reference);
+        assert_eq!(report.status(), CheckStatus::Passed);
+        assert_eq!(report.expected_size(), Some(Size::new(8, 4)));
+        assert_eq!(report.differing_pixels(), None);
+        assert_eq!(report.actual_path(), None);
+    }
+
+    #[test]
+    fn check_mismatch() {
+        let expected = display(&[]);
+        let actual = display(&[(2, 1, Rgb888::WHITE), (3, 2, Rgb888::RED)]);
+        let dir = TempDir::new("check-mismatch");
+        let reference = dir.join("mismatch.png");
+
+        let report = check(Ok(expected), &actual, &reference);
+        assert_eq!(report.status(), CheckStatus::Mismatch);
+        assert_eq!(report.status().exit_code(), 10);
+        assert_eq!(report.differing_pixels(), Some(2));
+        assert_eq!(
+            report.bounding_box(),
+            Some(Rectangle::new(Point::new(2, 1), Size::new(2, 2)))
+        );
+
+        let diff_path = report.diff_path().unwrap();
+        assert_eq!(diff_path, reference.with_extension("diff.png"));
+        assert!(diff_path.exists());
+
+        let actual_path = report.actual_path().unwrap();
+        assert_eq!(SimulatorDisplay::load_png(actual_path).unwrap(), actual);
+
+        #[cfg(feature = "json")]
+        {
+            let json = dir.join("mismatch.json");
+            report.save(&json).unwrap();
+            assert_eq!(CheckReport::load(&json).unwrap(), report);
+        }
+    }
+
+    #[test]
+    fn check_tolerance() {
+        let dir = TempDir::new("check-tolerance");
+        let reference = dir.join("tolerance.png");
+        let tolerance = Tolerance::exact().channel_threshold(8);
+
+        let expected = display(&[(1, 1, Rgb888::WHITE)]);
+        let actual = display(&[(1, 1, Rgb888::new(250, 250, 250)), (2, 2, Rgb888::RED)]);
+
+        let report = check_reference(
+            Ok(expected),
+            &actual,
+            &reference,
+            &dir.join("tolerance.actual.png"),
+            &dir.join("tolerance.diff.png"),
+            &tolerance,
+        );
+        assert_eq!(report.status(), CheckStatus::Mismatch);
+        assert_eq!(report.differing_pixels(), Some(1));
+        assert_eq!(report.tolerance(), tolerance);
+
+        #[cfg(feature = "json")]
+        {
+            let json = dir.join("tolerance.json");
+            report.save(&json).unwrap();
+            assert_eq!(CheckReport::load(&json).unwrap().tolerance(), tolerance);
+        }
+    }
+
+    #[test]
+    fn check_size_mismatch() {
+        let expected = SimulatorDisplay::with_default_color(Size::new(4, 4), Rgb888::BLACK);
+        let actual = display(&[]);
+        let dir = TempDir::new("check-size");
+        let reference = dir.join("size.png");
+
+        let report = check(Ok(expected), &actual, &reference);
+        assert_eq!(report.status(), CheckStatus::SizeMismatch);
+        assert_eq!(report.status().exit_code(), 11);
+        assert_eq!(
+            report.message(),
+            "display dimensions don't match PNG dimensions (display: 8x4, PNG: 4x4)"
+        );
+    }
+
+    #[test]
+    fn check_reference_missing() {
+        let dir = TempDir::new("check-missing");
+        let reference = dir.join("missing.png");
+        let expected = SimulatorDisplay::load_png(&reference);
+
+        let report = check(expected, &display(&[]), &reference);
+        assert_eq!(report.status(), CheckStatus::ReferenceMissing);
+        assert_eq!(report.status().exit_code(), 12);
+        assert_eq!(report.expected_size(), None);
+        assert_eq!(report.actual_size(), Size::new(8, 4));
+
+        #[cfg(feature = "json")]
+        {
+            let json = serde_json::to_value(&report).unwrap();
+            assert_eq!(json["status"], "reference_missing");
+            assert_eq!(json["expected_size"], serde_json::Value::Null);
+            assert_eq!(json["actual_size"]["width"], 8);
+        }
+    }
+}
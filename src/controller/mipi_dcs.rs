@@ -0,0 +1,882 @@
+use embedded_graphics::{
+    pixelcolor::{Rgb666, RgbColor},
+    prelude::*,
+};
+
+use crate::{
+    controller::{Controller, ControllerError},
+    display::SimulatorDisplay,
+};
+
+/// MADCTL row address order bit.
+const MADCTL_MY: u8 = 0x80;
+/// MADCTL column address order bit.
+const MADCTL_MX: u8 = 0x40;
+/// MADCTL row/column exchange bit.
+const MADCTL_MV: u8 = 0x20;
+/// MADCTL BGR color order bit.
+const MADCTL_BGR: u8 = 0x08;
+
+/// Controller model of a [`MipiDcs`] controller.
+///
+/// The model determines the size of the frame memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MipiDcsModel {
+    /// Sitronix ST7735 with a 132x162 pixel frame memory.
+    St7735,
+    /// Sitronix ST7789 with a 240x320 pixel frame memory.
+    St7789,
+    /// Ilitek ILI9341 with a 240x320 pixel frame memory.
+    Ili9341,
+}
+
+impl MipiDcsModel {
+    /// Returns the size of the frame memory.
+    pub const fn memory_size(self) -> Size {
+        match self {
+            MipiDcsModel::St7735 => Size::new(132, 162),
+            MipiDcsModel::St7789 | MipiDcsModel::Ili9341 => Size::new(240, 320),
+        }
+    }
+}
+
+/// Pixel format of the MCU interface, which is set by the COLMOD command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PixelFormat {
+    /// 12 bits per pixel, 2 pixels in 3 bytes.
+    Rgb444,
+    /// 16 bits per pixel, 1 pixel in 2 bytes.
+    Rgb565,
+    /// 18 bits per pixel, 1 pixel in 3 bytes.
+    Rgb666,
+}
+
+/// Parameters that are expected after a command byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Parameters {
+    /// Fixed number of parameter bytes.
+    Fixed(usize),
+    /// Any number of parameter bytes, which are ignored.
+    Any,
+    /// Pixel data, which is written to the frame memory.
+    MemoryWrite,
+}
+
+/// Emulated MIPI DCS TFT controller.
+///
+/// The emulated controller accepts the command and data byte stream that is sent to ST7735,
+/// ST7789 and ILI9341 controllers and other controllers that implement the MIPI Display Command
+/// Set. Commands are written by using [`write_command`](Self::write_command) and command
+/// parameters and pixel data by using [`write_data`](Self::write_data), which corresponds to the
+/// state of the D/C signal on a real display interface. The content of the frame memory is
+/// rendered into a [`SimulatorDisplay`] by using [`update`](Self::update).
+///
+/// The following features of the controller are emulated:
+///
+/// * column and row address windows (`CASET`, `RASET`) and memory writes (`RAMWR`, `RAMWRC`),
+///   including the wrap around of the write pointer at the end of the window,
+/// * memory data access control (`MADCTL`), with the row/column exchange and mirror bits and the
+///   RGB/BGR color order,
+/// * 12, 16 and 18 bit pixel formats (`COLMOD`),
+/// * display inversion (`INVON`, `INVOFF`), idle mode, sleep mode and display on/off,
+/// * software reset.
+///
+/// The `MY` and `MX` bits of `MADCTL` mirror the row and column addresses and the `MV` bit
+/// exchanges rows and columns after mirroring. Setting `MV` together with either `MX` or `MY`
+/// rotates the image by 90 degrees.
+///
+/// Vertical scrolling, partial mode, tearing effect, gamma, brightness and vendor specific
+/// commands in the range from `0xB0` to `0xFF` are accepted but have no effect. Read commands
+/// are also accepted, but the emulated controller doesn't return any data.
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+/// use embedded_graphics_simulator::{MipiDcs, MipiDcsModel, SimulatorDisplay};
+///
+/// let mut controller = MipiDcs::new(MipiDcsModel::St7789, Size::new(240, 320));
+///
+/// // Sleep out, 16 bit pixel format, display on
+/// controller.write_command(&[0x11]).unwrap();
+/// controller.write_command(&[0x3A]).unwrap();
+/// controller.write_data(&[0x55]).unwrap();
+/// controller.write_command(&[0x29]).unwrap();
+///
+/// // Fill a 2x1 pixel window with red.
+/// controller.write_command(&[0x2A]).unwrap();
+/// controller.write_data(&[0, 10, 0, 11]).unwrap();
+/// controller.write_command(&[0x2B]).unwrap();
+/// controller.write_data(&[0, 20, 0, 20]).unwrap();
+/// controller.write_command(&[0x2C]).unwrap();
+/// controller.write_data(&[0xF8, 0x00, 0xF8, 0x00]).unwrap();
+///
+/// let display: SimulatorDisplay<Rgb565> = controller.to_display();
+///
+/// assert_eq!(display.get_pixel(Point::new(11, 20)), Rgb565::RED);
+/// assert_eq!(display.get_pixel(Point::new(12, 20)), Rgb565::BLACK);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MipiDcs {
+    model: MipiDcsModel,
+    size: Size,
+    offset: Point,
+    bgr_panel: bool,
+    inverted_panel: bool,
+
+    memory: Box<[Rgb666]>,
+    command: Option<u8>,
+    parameters: Vec<u8>,
+    pixel_data: Vec<u8>,
+
+    column: u16,
+    row: u16,
+    column_window: (u16, u16),
+    row_window: (u16, u16),
+    wrapped: bool,
+
+    madctl: u8,
+    pixel_format: PixelFormat,
+    sleeping: bool,
+    display_on: bool,
+    inverted: bool,
+    idle: bool,
+}
+
+impl MipiDcs {
+    /// Creates a new emulated controller.
+    ///
+    /// The panel is connected to the frame memory starting at the top left corner. Panels that
+    /// are smaller than the frame memory and connected at a different position can be configured
+    /// by using [`offset`](Self::offset).
+    ///
+    /// The controller starts in the same state as a real controller after a reset. It is in
+    /// sleep mode, the display is turned off and the frame memory is cleared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the panel is larger than the frame memory of the controller.
+    pub fn new(model: MipiDcsModel, size: Size) -> Self {
+        let memory_size = model.memory_size();
+        assert!(
+            size.width <= memory_size.width && size.height <= memory_size.height,
+            "{model:?} panels can't be larger than {}x{} pixels",
+            memory_size.width,
+            memory_size.height
+        );
+
+        let mut controller = Self {
+            model,
+            size,
+            offset: Point::zero(),
+            bgr_panel: false,
+            inverted_panel: false,
+
+            memory: vec![Rgb666::BLACK; (memory_size.width * memory_size.height) as usize]
+                .into_boxed_slice(),
+            command: None,
+            parameters: Vec::new(),
+            pixel_data: Vec::new(),
+
+            column: 0,
+            row: 0,
+            column_window: (0, 0),
+            row_window: (0, 0),
+            wrapped: false,
+
+            madctl: 0,
+            pixel_format: PixelFormat::Rgb666,
+            sleeping: true,
+            display_on: false,
+            inverted: false,
+            idle: false,
+        };
+        controller.reset();
+
+        controller
+    }
+
+    /// Sets the position of the panel in the frame memory.
+    ///
+    /// Some panels, e.g. most 128x160 ST7735 panels, are connected to the frame memory with an
+    /// offset, which needs to be taken into account by the display driver.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the panel doesn't fit into the frame memory at the given offset.
+    pub fn offset(mut self, offset: Point) -> Self {
+        let memory_size = self.model.memory_size();
+        assert!(
+            offset.x >= 0
+                && offset.y >= 0
+                && offset.x as u32 + self.size.width <= memory_size.width
+                && offset.y as u32 + self.size.height <= memory_size.height,
+            "panel must fit into the frame memory"
+        );
+
+        self.offset = offset;
+        self
+    }
+
+    /// Sets the subpixel order of the panel.
+    ///
+    /// If the panel uses BGR subpixel order, the `BGR` bit in `MADCTL` needs to be set by the
+    /// display driver to display the correct colors. Otherwise the red and blue channels are
+    /// swapped.
+    pub fn bgr_panel(mut self, bgr_panel: bool) -> Self {
+        self.bgr_panel = bgr_panel;
+        self
+    }
+
+    /// Sets if the panel is inverted.
+    ///
+    /// Some panels, e.g. most IPS panels with a ST7789 controller, need to be used with display
+    /// inversion turned on (`INVON`) to display the correct colors.
+    pub fn inverted_panel(mut self, inverted_panel: bool) -> Self {
+        self.inverted_panel = inverted_panel;
+        self
+    }
+
+    /// Returns the panel size.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Returns the controller model.
+    pub fn model(&self) -> MipiDcsModel {
+        self.model
+    }
+
+    /// Resets all registers to their default values.
+    ///
+    /// The frame memory isn't cleared by a reset.
+    fn reset(&mut self) {
+        self.madctl = 0;
+        self.pixel_format = PixelFormat::Rgb666;
+        self.sleeping = true;
+        self.display_on = false;
+        self.inverted = false;
+        self.idle = false;
+
+        let (width, height) = self.address_space();
+        self.column_window = (0, width - 1);
+        self.row_window = (0, height - 1);
+        self.column = 0;
+        self.row = 0;
+    }
+
+    /// Returns the width and height of the address space, taking the `MV` bit into account.
+    fn address_space(&self) -> (u16, u16) {
+        let memory_size = self.model.memory_size();
+        let (width, height) = (memory_size.width as u16, memory_size.height as u16);
+
+        if self.madctl & MADCTL_MV != 0 {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Processes command bytes.
+    ///
+    /// Each byte is interpreted as a separate command. Command parameters must be written by
+    /// using [`write_data`](Self::write_data) after the command.
+    ///
+    /// An error is returned for unknown commands and if a command is sent before all parameters
+    /// of the previous command were written. Unknown commands are ignored and incomplete
+    /// commands aren't executed.
+    pub fn write_command(&mut self, bytes: &[u8]) -> Result<(), ControllerError> {
+        let mut result = Ok(());
+
+        for &byte in bytes {
+            if let Some(command) = self.command.take() {
+                if let Some(Parameters::Fixed(_)) = parameters(command) {
+                    result = result.and(Err(ControllerError::MissingParameters(command)));
+                }
+            }
+            self.parameters.clear();
+            self.pixel_data.clear();
+
+            match parameters(byte) {
+                Some(Parameters::Fixed(0)) => {
+                    if let Err(e) = self.execute(byte, &[]) {
+                        result = result.and(Err(e));
+                    }
+                }
+                Some(Parameters::MemoryWrite) => {
+                    if byte == 0x2C {
+                        self.column = self.column_window.0;
+                        self.row = self.row_window.0;
+                        self.wrapped = false;
+                    }
+                    self.command = Some(byte);
+                }
+                Some(_) => self.command = Some(byte),
+                None => result = result.and(Err(ControllerError::UnknownCommand(byte))),
+            }
+        }
+
+        result
+    }
+
+    /// Processes data bytes.
+    ///
+    /// Data bytes are either parameters of the previous command or pixel data after a memory
+    /// write command. The layout of the pixel data depends on the pixel format, which is set by
+    /// the `COLMOD` command.
+    ///
+    /// An error is returned if data is written without a preceding command that expects data,
+    /// if a command receives too many parameters or if more pixels are written than fit into the
+    /// address window. The write pointer wraps around to the start of the window in the last
+    /// case, like in a real controller.
+    pub fn write_data(&mut self, bytes: &[u8]) -> Result<(), ControllerError> {
+        let Some(command) = self.command else {
+            return Err(ControllerError::UnexpectedData);
+        };
+
+        match parameters(command) {
+            Some(Parameters::Fixed(count)) => {
+                let (bytes, rest) = bytes.split_at(bytes.len().min(count - self.parameters.len()));
+                self.parameters.extend_from_slice(bytes);
+
+                let mut result = Ok(());
+                if self.parameters.len() == count {
+                    self.command = None;
+                    let parameters = std::mem::take(&mut self.parameters);
+                    result = self.execute(command, &parameters);
+                }
+                if !rest.is_empty() {
+                    result = result.and(Err(ControllerError::UnexpectedData));
+                }
+
+                result
+            }
+            Some(Parameters::MemoryWrite) => {
+                let mut result = Ok(());
+                for &byte in bytes {
+                    self.pixel_data.push(byte);
+                    if let Err(e) = self.decode_pixel_data() {
+                        result = Err(e);
+                    }
+                }
+
+                result
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Decodes the buffered pixel data if enough bytes were received.
+    fn decode_pixel_data(&mut self) -> Result<(), ControllerError> {
+        let expand4 = |value: u8| (value & 0x0F) << 2 | (value & 0x0F) >> 2;
+        let expand5 = |value: u8| (value & 0x1F) << 1 | (value & 0x1F) >> 4;
+
+        let result = match (self.pixel_format, self.pixel_data.as_slice()) {
+            (PixelFormat::Rgb444, &[a, b, c]) => {
+                let first =
+                    self.write_pixel(Rgb666::new(expand4(a >> 4), expand4(a), expand4(b >> 4)));
+                let second = self.write_pixel(Rgb666::new(expand4(b), expand4(c >> 4), expand4(c)));
+                first.and(second)
+            }
+            (PixelFormat::Rgb565, &[high, low]) => self.write_pixel(Rgb666::new(
+                expand5(high >> 3),
+                (high & 0x07) << 3 | low >> 5,
+                expand5(low),
+            )),
+            (PixelFormat::Rgb666, &[r, g, b]) => {
+                self.write_pixel(Rgb666::new(r >> 2, g >> 2, b >> 2))
+            }
+            _ => return Ok(()),
+        };
+
+        self.pixel_data.clear();
+
+        result
+    }
+
+    /// Writes a pixel to the frame memory and advances the write pointer.
+    fn write_pixel(&mut self, color: Rgb666) -> Result<(), ControllerError> {
+        let result = if self.wrapped {
+            Err(ControllerError::WindowOverflow)
+        } else {
+            Ok(())
+        };
+
+        let (width, height) = self.address_space();
+
+        if self.column < width && self.row < height {
+            let mut x = self.column;
+            let mut y = self.row;
+            if self.madctl & MADCTL_MX != 0 {
+                x = width - 1 - x;
+            }
+            if self.madctl & MADCTL_MY != 0 {
+                y = height - 1 - y;
+            }
+            if self.madctl & MADCTL_MV != 0 {
+                (x, y) = (y, x);
+            }
+
+            let memory_width = self.model.memory_size().width as usize;
+            self.memory[y as usize * memory_width + x as usize] = color;
+        }
+
+        let (column_start, column_end) = self.column_window;
+        let (row_start, row_end) = self.row_window;
+
+        if self.column >= column_end {
+            self.column = column_start;
+            if self.row >= row_end {
+                self.row = row_start;
+                self.wrapped = true;
+            } else {
+                self.row += 1;
+            }
+        } else {
+            self.column += 1;
+        }
+
+        result
+    }
+
+    fn execute(&mut self, command: u8, parameters: &[u8]) -> Result<(), ControllerError> {
+        let invalid = |parameter: u16| ControllerError::InvalidParameter { command, parameter };
+
+        match (command, parameters) {
+            (0x01, []) => self.reset(),
+            (0x10 | 0x11, []) => self.sleeping = command == 0x10,
+            (0x20 | 0x21, []) => self.inverted = command == 0x21,
+            (0x28 | 0x29, []) => self.display_on = command == 0x29,
+            (0x2A | 0x2B, &[start_high, start_low, end_high, end_low]) => {
+                let start = u16::from_be_bytes([start_high, start_low]);
+                let end = u16::from_be_bytes([end_high, end_low]);
+
+                let (width, height) = self.address_space();
+                let limit = if command == 0x2A { width } else { height };
+                if end >= limit {
+                    return Err(invalid(end));
+                }
+                if start > end {
+                    return Err(invalid(start));
+                }
+
+                if command == 0x2A {
+                    self.column_window = (start, end);
+                } else {
+                    self.row_window = (start, end);
+                }
+            }
+            (0x36, &[madctl]) => {
+                self.madctl = madctl;
+
+                // Clamp the windows to the new address space.
+                let (width, height) = self.address_space();
+                self.column_window.1 = self.column_window.1.min(width - 1);
+                self.column_window.0 = self.column_window.0.min(self.column_window.1);
+                self.row_window.1 = self.row_window.1.min(height - 1);
+                self.row_window.0 = self.row_window.0.min(self.row_window.1);
+            }
+            (0x38 | 0x39, []) => self.idle = command == 0x39,
+            (0x3A, &[colmod]) => {
+                self.pixel_format = match colmod & 0x07 {
+                    0x03 => PixelFormat::Rgb444,
+                    0x05 => PixelFormat::Rgb565,
+                    0x06 => PixelFormat::Rgb666,
+                    _ => return Err(invalid(colmod.into())),
+                }
+            }
+            // NOP, read, partial mode, tearing effect, scrolling, gamma and brightness commands.
+            (0x00 | 0x04 | 0x09 | 0x0A..=0x0F | 0x12 | 0x13 | 0x26 | 0x2E | 0x30, _)
+            | (0x33 | 0x34 | 0x35 | 0x37 | 0x3E | 0x44 | 0x45 | 0x51 | 0x53 | 0x55 | 0x5E, _) => {}
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if the controller is in sleep mode.
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    /// Returns `true` if the display is turned on.
+    pub fn is_display_on(&self) -> bool {
+        self.display_on
+    }
+
+    /// Returns `true` if display inversion is turned on.
+    pub fn is_inverted(&self) -> bool {
+        self.inverted
+    }
+
+    /// Returns the value of the memory data access control register (`MADCTL`).
+    pub fn madctl(&self) -> u8 {
+        self.madctl
+    }
+
+    /// Returns the frame memory.
+    ///
+    /// The frame memory is stored row by row, without the `MADCTL` transformations applied.
+    pub fn memory(&self) -> &[Rgb666] {
+        &self.memory
+    }
+
+    /// Returns the panel pixel at the given position, taking the controller settings into
+    /// account.
+    fn panel_pixel(&self, point: Point) -> Rgb666 {
+        if self.sleeping || !self.display_on {
+            return Rgb666::BLACK;
+        }
+
+        let point = point + self.offset;
+        let memory_width = self.model.memory_size().width as usize;
+        let color = self.memory[point.y as usize * memory_width + point.x as usize];
+
+        let (mut r, g, mut b) = (color.r(), color.g(), color.b());
+        if (self.madctl & MADCTL_BGR != 0) != self.bgr_panel {
+            (r, b) = (b, r);
+        }
+
+        let max = Rgb666::MAX_R;
+        let mut channels = [r, g, b];
+        for channel in &mut channels {
+            if self.idle {
+                *channel = if *channel & 0x20 != 0 { max } else { 0 };
+            }
+            if self.inverted != self.inverted_panel {
+                *channel = max - *channel;
+            }
+        }
+
+        Rgb666::new(channels[0], channels[1], channels[2])
+    }
+
+    /// Updates a simulator display with the panel content.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display size doesn't match the panel size.
+    pub fn update<C>(&self, display: &mut SimulatorDisplay<C>)
+    where
+        C: PixelColor + From<Rgb666>,
+    {
+        Controller::update(self, display)
+    }
+
+    /// Returns a new simulator display with the panel content.
+    pub fn to_display<C>(&self) -> SimulatorDisplay<C>
+    where
+        C: PixelColor + From<Rgb666>,
+    {
+        let mut display = SimulatorDisplay::with_default_color(self.size, Rgb666::BLACK.into());
+        self.update(&mut display);
+
+        display
+    }
+}
+
+impl Controller for MipiDcs {
+    type Color = Rgb666;
+
+    fn write_command(&mut self, bytes: &[u8]) -> Result<(), ControllerError> {
+        MipiDcs::write_command(self, bytes)
+    }
+
+    fn write_data(&mut self, bytes: &[u8]) -> Result<(), ControllerError> {
+        MipiDcs::write_data(self, bytes)
+    }
+
+    fn size(&self) -> Size {
+        self.size
+    }
+
+    fn pixel(&self, point: Point) -> Rgb666 {
+        self.panel_pixel(point)
+    }
+}
+
+/// Returns the parameters of a command or `None` if the command is unknown.
+fn parameters(command: u8) -> Option<Parameters> {
+    Some(match command {
+        0x00 | 0x01 | 0x04 | 0x09 | 0x0A..=0x0F | 0x10..=0x13 | 0x20 | 0x21 | 0x28 | 0x29 => {
+            Parameters::Fixed(0)
+        }
+        0x2E | 0x34 | 0x38 | 0x39 | 0x3E | 0x45 => Parameters::Fixed(0),
+        0x26 | 0x35 | 0x36 | 0x3A | 0x51 | 0x53 | 0x55 | 0x5E => Parameters::Fixed(1),
+        0x37 | 0x44 => Parameters::Fixed(2),
+        0x2A | 0x2B | 0x30 => Parameters::Fixed(4),
+        0x33 => Parameters::Fixed(6),
+        0x2C | 0x3C => Parameters::MemoryWrite,
+        0xB0..=0xFF => Parameters::Any,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    /// Sends a command with parameters.
+    fn send(controller: &mut MipiDcs, command: u8, data: &[u8]) {
+        controller.write_command(&[command]).unwrap();
+        if !data.is_empty() {
+            controller.write_data(data).unwrap();
+        }
+    }
+
+    /// Sets the address window and starts a memory write.
+    fn window(controller: &mut MipiDcs, x: (u16, u16), y: (u16, u16)) {
+        let [xs_high, xs_low] = x.0.to_be_bytes();
+        let [xe_high, xe_low] = x.1.to_be_bytes();
+        let [ys_high, ys_low] = y.0.to_be_bytes();
+        let [ye_high, ye_low] = y.1.to_be_bytes();
+
+        send(controller, 0x2A, &[xs_high, xs_low, xe_high, xe_low]);
+        send(controller, 0x2B, &[ys_high, ys_low, ye_high, ye_low]);
+        send(controller, 0x2C, &[]);
+    }
+
+    /// Creates a 16 bit controller that is turned on.
+    fn controller(model: MipiDcsModel, size: Size) -> MipiDcs {
+        let mut controller = MipiDcs::new(model, size);
+        send(&mut controller, 0x11, &[]);
+        send(&mut controller, 0x3A, &[0x55]);
+        send(&mut controller, 0x29, &[]);
+
+        controller
+    }
+
+    fn colored_pixels(controller: &MipiDcs) -> Vec<(Point, Rgb565)> {
+        let display = controller.to_display::<Rgb565>();
+        display
+            .bounding_box()
+            .points()
+            .map(|p| (p, display.get_pixel(p)))
+            .filter(|(_, c)| *c != Rgb565::BLACK)
+            .collect()
+    }
+
+    const RED: [u8; 2] = [0xF8, 0x00];
+    const GREEN: [u8; 2] = [0x07, 0xE0];
+    const BLUE: [u8; 2] = [0x00, 0x1F];
+
+    #[test]
+    fn window_wraps_around() {
+        let mut controller = controller(MipiDcsModel::Ili9341, Size::new(240, 320));
+        window(&mut controller, (10, 11), (20, 21));
+
+        controller.write_data(&[RED, GREEN, BLUE].concat()).unwrap();
+
+        // RAMWRC continues at the current position.
+        send(&mut controller, 0x3C, &GREEN);
+
+        // The fifth pixel wraps around to the start of the window.
+        assert_eq!(
+            controller.write_data(&BLUE),
+            Err(ControllerError::WindowOverflow)
+        );
+
+        assert_eq!(
+            colored_pixels(&controller),
+            [
+                (Point::new(10, 20), Rgb565::BLUE),
+                (Point::new(11, 20), Rgb565::GREEN),
+                (Point::new(10, 21), Rgb565::BLUE),
+                (Point::new(11, 21), Rgb565::GREEN),
+            ]
+        );
+
+        // RAMWR restarts at the start of the window.
+        send(&mut controller, 0x2C, &RED);
+        assert_eq!(
+            controller
+                .to_display::<Rgb565>()
+                .get_pixel(Point::new(10, 20)),
+            Rgb565::RED
+        );
+    }
+
+    #[test]
+    fn madctl_rotation() {
+        // MX | MV
+        let mut controller = controller(MipiDcsModel::St7789, Size::new(240, 320));
+        send(&mut controller, 0x36, &[0x60]);
+        window(&mut controller, (0, 319), (0, 239));
+        controller.write_data(&[RED, GREEN].concat()).unwrap();
+
+        assert_eq!(
+            colored_pixels(&controller),
+            [
+                (Point::new(0, 318), Rgb565::GREEN),
+                (Point::new(0, 319), Rgb565::RED),
+            ]
+        );
+
+        // MY | MV
+        let mut controller = self::controller(MipiDcsModel::St7789, Size::new(240, 320));
+        send(&mut controller, 0x36, &[0xA0]);
+        window(&mut controller, (0, 319), (0, 239));
+        controller.write_data(&[RED, GREEN].concat()).unwrap();
+
+        assert_eq!(
+            colored_pixels(&controller),
+            [
+                (Point::new(239, 0), Rgb565::RED),
+                (Point::new(239, 1), Rgb565::GREEN),
+            ]
+        );
+    }
+
+    #[test]
+    fn madctl_mirror() {
+        let mut controller = controller(MipiDcsModel::Ili9341, Size::new(240, 320));
+        send(&mut controller, 0x36, &[0xC0]);
+        window(&mut controller, (0, 1), (0, 0));
+        controller.write_data(&[RED, GREEN].concat()).unwrap();
+
+        assert_eq!(
+            colored_pixels(&controller),
+            [
+                (Point::new(238, 319), Rgb565::GREEN),
+                (Point::new(239, 319), Rgb565::RED),
+            ]
+        );
+    }
+
+    #[test]
+    fn bgr() {
+        let mut controller = controller(MipiDcsModel::Ili9341, Size::new(240, 320)).bgr_panel(true);
+        window(&mut controller, (0, 0), (0, 0));
+        controller.write_data(&RED).unwrap();
+
+        // The red and blue channels are swapped if the BGR bit doesn't match the panel.
+        assert_eq!(colored_pixels(&controller), [(Point::zero(), Rgb565::BLUE)]);
+
+        send(&mut controller, 0x36, &[0x08]);
+        assert_eq!(colored_pixels(&controller), [(Point::zero(), Rgb565::RED)]);
+    }
+
+    #[test]
+    fn pixel_formats() {
+        let mut controller = controller(MipiDcsModel::St7789, Size::new(4, 1));
+        window(&mut controller, (0, 3), (0, 0));
+
+        // 16 bit pixel split across writes
+        controller.write_data(&[0x84]).unwrap();
+        controller.write_data(&[0x10]).unwrap();
+
+        // 18 bit
+        send(&mut controller, 0x3A, &[0x66]);
+        send(&mut controller, 0x3C, &[0xFC, 0x80, 0x00]);
+
+        // 12 bit, two pixels in three bytes
+        send(&mut controller, 0x3A, &[0x03]);
+        send(&mut controller, 0x3C, &[0xF0, 0x00, 0x0F]);
+
+        let display = controller.to_display::<Rgb666>();
+        assert_eq!(display.get_pixel(Point::new(0, 0)), Rgb666::new(33, 32, 33));
+        assert_eq!(display.get_pixel(Point::new(1, 0)), Rgb666::new(63, 32, 0));
+        assert_eq!(display.get_pixel(Point::new(2, 0)), Rgb666::new(63, 0, 0));
+        assert_eq!(display.get_pixel(Point::new(3, 0)), Rgb666::new(0, 0, 63));
+    }
+
+    #[test]
+    fn panel_offset() {
+        let mut controller =
+            controller(MipiDcsModel::St7735, Size::new(128, 160)).offset(Point::new(2, 1));
+        window(&mut controller, (2, 2), (1, 1));
+        controller.write_data(&GREEN).unwrap();
+
+        assert_eq!(
+            colored_pixels(&controller),
+            [(Point::zero(), Rgb565::GREEN)]
+        );
+    }
+
+    #[test]
+    fn display_modes() {
+        let mut controller = MipiDcs::new(MipiDcsModel::St7789, Size::new(2, 1));
+        send(&mut controller, 0x3A, &[0x55]);
+        window(&mut controller, (0, 0), (0, 0));
+        controller.write_data(&RED).unwrap();
+
+        // The display is in sleep mode and turned off after a reset.
+        assert!(controller.is_sleeping());
+        assert!(colored_pixels(&controller).is_empty());
+
+        send(&mut controller, 0x11, &[]);
+        send(&mut controller, 0x29, &[]);
+        assert_eq!(colored_pixels(&controller), [(Point::zero(), Rgb565::RED)]);
+
+        send(&mut controller, 0x21, &[]);
+        assert_eq!(
+            colored_pixels(&controller),
+            [
+                (Point::new(0, 0), Rgb565::CYAN),
+                (Point::new(1, 0), Rgb565::WHITE)
+            ]
+        );
+
+        // Inverted panels need INVON to display the correct colors.
+        let controller = controller.inverted_panel(true);
+        assert_eq!(colored_pixels(&controller), [(Point::zero(), Rgb565::RED)]);
+
+        // The frame memory isn't cleared by a software reset.
+        let mut controller = controller.inverted_panel(false);
+        send(&mut controller, 0x01, &[]);
+        assert!(!controller.is_display_on());
+        assert_eq!(controller.memory()[0], Rgb666::RED);
+    }
+
+    #[test]
+    fn errors() {
+        let mut controller = MipiDcs::new(MipiDcsModel::St7735, Size::new(128, 160));
+
+        assert_eq!(
+            controller.write_command(&[0x80, 0x29]),
+            Err(ControllerError::UnknownCommand(0x80))
+        );
+        assert!(controller.is_display_on());
+
+        assert_eq!(
+            controller.write_data(&[0x00]),
+            Err(ControllerError::UnexpectedData)
+        );
+
+        controller.write_command(&[0x2A]).unwrap();
+        assert_eq!(
+            controller.write_data(&[0, 0, 0, 132]),
+            Err(ControllerError::InvalidParameter {
+                command: 0x2A,
+                parameter: 132
+            })
+        );
+
+        controller.write_command(&[0x2B]).unwrap();
+        controller.write_data(&[0, 1]).unwrap();
+        assert_eq!(
+            controller.write_command(&[0x29]),
+            Err(ControllerError::MissingParameters(0x2B))
+        );
+
+        controller.write_command(&[0x3A]).unwrap();
+        assert_eq!(
+            controller.write_data(&[0x05, 0x00]),
+            Err(ControllerError::UnexpectedData)
+        );
+        controller.write_command(&[0x3A]).unwrap();
+        assert_eq!(
+            controller.write_data(&[0x07]),
+            Err(ControllerError::InvalidParameter {
+                command: 0x3A,
+                parameter: 0x07
+            })
+        );
+
+        // Vendor specific commands accept any parameters.
+        controller.write_command(&[0xB1]).unwrap();
+        controller.write_data(&[0x01, 0x2C, 0x2D]).unwrap();
+    }
+}
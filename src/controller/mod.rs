@@ -0,0 +1,108 @@
+use std::{error::Error, fmt};
+
+use embedded_graphics::prelude::*;
+
+use crate::display::SimulatorDisplay;
+
+mod mipi_dcs;
+mod ssd1306;
+
+pub use mipi_dcs::{MipiDcs, MipiDcsModel};
+pub use ssd1306::{AddressingMode, Ssd1306};
+
+/// Emulated display controller.
+///
+/// This trait is implemented by all emulated controllers and is used by the bus and interface
+/// types to send commands and data to a controller. It can also be implemented for custom
+/// controller models.
+pub trait Controller {
+    /// Native color type of the panel.
+    type Color: PixelColor;
+
+    /// Processes command bytes.
+    fn write_command(&mut self, bytes: &[u8]) -> Result<(), ControllerError>;
+
+    /// Processes data bytes.
+    fn write_data(&mut self, bytes: &[u8]) -> Result<(), ControllerError>;
+
+    /// Returns the panel size.
+    fn size(&self) -> Size;
+
+    /// Returns the color of a panel pixel.
+    ///
+    /// The position is always inside the panel area.
+    fn pixel(&self, point: Point) -> Self::Color;
+
+    /// Updates a simulator display with the panel content.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display size doesn't match the panel size.
+    fn update<C>(&self, display: &mut SimulatorDisplay<C>)
+    where
+        C: PixelColor + From<Self::Color>,
+    {
+        assert_eq!(
+            display.size(),
+            self.size(),
+            "display size must match the panel size"
+        );
+
+        display
+            .bounding_box()
+            .points()
+            .map(|p| Pixel(p, self.pixel(p).into()))
+            .draw(display)
+            .unwrap();
+    }
+}
+
+/// Error reported by an emulated display controller.
+///
+/// Real controllers silently ignore invalid input. The emulated controllers report it instead,
+/// to make bugs in driver code visible. The controller state stays consistent after an error
+/// and subsequent commands are processed normally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ControllerError {
+    /// The command isn't supported by the controller.
+    UnknownCommand(u8),
+    /// A command parameter is out of range.
+    InvalidParameter {
+        /// Command byte.
+        command: u8,
+        /// Invalid parameter value.
+        ///
+        /// Multi byte parameters, like the coordinates in the column and row address commands
+        /// of MIPI DCS controllers, are combined into a single value.
+        parameter: u16,
+    },
+    /// Data was written while no data was expected.
+    UnexpectedData,
+    /// A command was sent before all parameters of the previous command were received.
+    MissingParameters(u8),
+    /// More data was written than fits into the address window.
+    ///
+    /// The write pointer wraps around to the start of the window, like in a real controller.
+    WindowOverflow,
+}
+
+impl fmt::Display for ControllerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControllerError::UnknownCommand(command) => {
+                write!(f, "unknown command 0x{command:02X}")
+            }
+            ControllerError::InvalidParameter { command, parameter } => write!(
+                f,
+                "invalid parameter 0x{parameter:02X} for command 0x{command:02X}"
+            ),
+            ControllerError::UnexpectedData => write!(f, "unexpected data write"),
+            ControllerError::MissingParameters(command) => {
+                write!(f, "missing parameters for command 0x{command:02X}")
+            }
+            ControllerError::WindowOverflow => write!(f, "write outside the address window"),
+        }
+    }
+}
+
+impl Error for ControllerError {}
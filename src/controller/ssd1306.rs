@@ -0,0 +1,609 @@
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+
+use crate::{
+    controller::{Controller, ControllerError},
+    display::SimulatorDisplay,
+};
+
+const COLUMNS: usize = 128;
+const PAGES: usize = 8;
+const ROWS: usize = PAGES * 8;
+
+/// Memory addressing mode of a [`Ssd1306`] controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressingMode {
+    /// Horizontal addressing mode.
+    ///
+    /// The column address is incremented after each data byte. At the end of the column window
+    /// the column address wraps around and the page address is incremented.
+    Horizontal,
+    /// Vertical addressing mode.
+    ///
+    /// The page address is incremented after each data byte. At the end of the page window the
+    /// page address wraps around and the column address is incremented.
+    Vertical,
+    /// Page addressing mode.
+    ///
+    /// The column address is incremented after each data byte and wraps around to the start
+    /// column at the end of the page, without changing the page address.
+    Page,
+}
+
+/// Emulated SSD1306 OLED controller.
+///
+/// The emulated controller accepts the same command and data byte stream that is sent to a real
+/// SSD1306 and can be used to test display drivers without hardware. The content of the display
+/// RAM is rendered into a [`SimulatorDisplay`] by using [`update`](Self::update).
+///
+/// The following features of the controller are emulated:
+///
+/// * horizontal, vertical and page addressing modes, including the column and page windows,
+/// * display start line, display offset and multiplex ratio,
+/// * segment and COM output remapping,
+/// * normal and inverted display, entire display on and display on/off,
+/// * contrast.
+///
+/// Scrolling, timing and power configuration commands are accepted but have no effect. The
+/// COM pins hardware configuration is also ignored and the panel rows are always mapped to the COM
+/// outputs sequentially.
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+/// use embedded_graphics_simulator::{SimulatorDisplay, Ssd1306};
+///
+/// let mut controller = Ssd1306::new(Size::new(128, 64));
+///
+/// // Display on, horizontal addressing mode, column and page window
+/// controller.write_command(&[0xAF, 0x20, 0x00]).unwrap();
+/// controller.write_command(&[0x21, 0, 127, 0x22, 0, 7]).unwrap();
+///
+/// // Draw a vertical line with a height of 8 pixels in the first column.
+/// controller.write_data(&[0xFF]).unwrap();
+///
+/// let mut display = SimulatorDisplay::new(Size::new(128, 64));
+/// controller.update(&mut display);
+///
+/// assert_eq!(display.get_pixel(Point::new(0, 7)), BinaryColor::On);
+/// assert_eq!(display.get_pixel(Point::new(1, 0)), BinaryColor::Off);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ssd1306 {
+    size: Size,
+    ram: Box<[u8]>,
+    command: Vec<u8>,
+
+    addressing_mode: AddressingMode,
+    column: u8,
+    page: u8,
+    column_window: (u8, u8),
+    page_window: (u8, u8),
+    /// Column start address in page addressing mode.
+    page_start_column: u8,
+    wrapped: bool,
+
+    display_on: bool,
+    inverted: bool,
+    entire_display_on: bool,
+    contrast: u8,
+    start_line: u8,
+    display_offset: u8,
+    multiplex_ratio: u8,
+    segment_remap: bool,
+    com_remap: bool,
+}
+
+impl Ssd1306 {
+    /// Creates a new emulated controller.
+    ///
+    /// The controller starts in the same state as a real SSD1306 after a reset. The display is
+    /// turned off and the display RAM is cleared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the panel is larger than 128x64 pixels.
+    pub fn new(size: Size) -> Self {
+        assert!(
+            size.width as usize <= COLUMNS && size.height as usize <= ROWS,
+            "SSD1306 panels can't be larger than 128x64 pixels"
+        );
+
+        Self {
+            size,
+            ram: vec![0; COLUMNS * PAGES].into_boxed_slice(),
+            command: Vec::new(),
+
+            addressing_mode: AddressingMode::Page,
+            column: 0,
+            page: 0,
+            column_window: (0, COLUMNS as u8 - 1),
+            page_window: (0, PAGES as u8 - 1),
+            page_start_column: 0,
+            wrapped: false,
+
+            display_on: false,
+            inverted: false,
+            entire_display_on: false,
+            contrast: 0x7F,
+            start_line: 0,
+            display_offset: 0,
+            multiplex_ratio: ROWS as u8,
+            segment_remap: false,
+            com_remap: false,
+        }
+    }
+
+    /// Returns the panel size.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Processes command bytes.
+    ///
+    /// Command parameters can be passed in the same call as the command or in subsequent calls.
+    /// An error is returned for unknown commands and invalid parameters, which are ignored by
+    /// the emulated controller.
+    pub fn write_command(&mut self, bytes: &[u8]) -> Result<(), ControllerError> {
+        let mut result = Ok(());
+
+        for &byte in bytes {
+            self.command.push(byte);
+
+            match parameter_count(self.command[0]) {
+                Some(count) if self.command.len() > count => {
+                    let command = std::mem::take(&mut self.command);
+                    if let Err(e) = self.execute(command[0], &command[1..]) {
+                        result = result.and(Err(e));
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    self.command.clear();
+                    result = result.and(Err(ControllerError::UnknownCommand(byte)));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Writes data bytes to the display RAM.
+    ///
+    /// Each byte contains 8 vertically stacked pixels of one page, with the least significant
+    /// bit at the top.
+    ///
+    /// An error is returned if more data is written than fits into the address window, or into
+    /// the remaining columns of the page in page addressing mode. The RAM address pointer wraps
+    /// around in this case, like in a real controller.
+    pub fn write_data(&mut self, bytes: &[u8]) -> Result<(), ControllerError> {
+        if !self.command.is_empty() {
+            self.command.clear();
+            return Err(ControllerError::UnexpectedData);
+        }
+
+        let mut result = Ok(());
+        for &byte in bytes {
+            if self.wrapped {
+                result = Err(ControllerError::WindowOverflow);
+            }
+
+            self.ram[self.page as usize * COLUMNS + self.column as usize] = byte;
+            self.advance();
+        }
+
+        result
+    }
+
+    /// Advances the RAM address pointer after a data byte was written.
+    fn advance(&mut self) {
+        let (column_start, column_end) = self.column_window;
+        let (page_start, page_end) = self.page_window;
+
+        match self.addressing_mode {
+            AddressingMode::Horizontal => {
+                if self.column >= column_end {
+                    self.column = column_start;
+                    if self.page >= page_end {
+                        self.page = page_start;
+                        self.wrapped = true;
+                    } else {
+                        self.page += 1;
+                    }
+                } else {
+                    self.column += 1;
+                }
+            }
+            AddressingMode::Vertical => {
+                if self.page >= page_end {
+                    self.page = page_start;
+                    if self.column >= column_end {
+                        self.column = column_start;
+                        self.wrapped = true;
+                    } else {
+                        self.column += 1;
+                    }
+                } else {
+                    self.page += 1;
+                }
+            }
+            AddressingMode::Page => {
+                if self.column as usize >= COLUMNS - 1 {
+                    self.column = self.page_start_column;
+                    self.wrapped = true;
+                } else {
+                    self.column += 1;
+                }
+            }
+        }
+    }
+
+    fn execute(&mut self, command: u8, parameters: &[u8]) -> Result<(), ControllerError> {
+        let invalid = |parameter: u8| ControllerError::InvalidParameter {
+            command,
+            parameter: parameter.into(),
+        };
+
+        // Commands that change the RAM address pointer start a new write sequence.
+        if matches!(command, 0x00..=0x17 | 0x20..=0x22 | 0xB0..=0xB7) {
+            self.wrapped = false;
+        }
+
+        match (command, parameters) {
+            (0x00..=0x0F, []) => {
+                self.page_start_column = self.page_start_column & 0xF0 | command & 0x0F;
+                self.column = self.page_start_column;
+            }
+            (0x10..=0x17, []) => {
+                self.page_start_column = self.page_start_column & 0x0F | (command & 0x07) << 4;
+                self.column = self.page_start_column;
+            }
+            (0x20, &[mode]) => {
+                self.addressing_mode = match mode {
+                    0x00 => AddressingMode::Horizontal,
+                    0x01 => AddressingMode::Vertical,
+                    0x02 => AddressingMode::Page,
+                    _ => return Err(invalid(mode)),
+                }
+            }
+            (0x21, &[start, end]) => {
+                for value in [start, end] {
+                    if value as usize >= COLUMNS {
+                        return Err(invalid(value));
+                    }
+                }
+                self.column_window = (start, end);
+                self.column = start;
+            }
+            (0x22, &[start, end]) => {
+                for value in [start, end] {
+                    if value as usize >= PAGES {
+                        return Err(invalid(value));
+                    }
+                }
+                self.page_window = (start, end);
+                self.page = start;
+            }
+            (0x40..=0x7F, []) => self.start_line = command & 0x3F,
+            (0x81, &[contrast]) => self.contrast = contrast,
+            (0xA0 | 0xA1, []) => self.segment_remap = command == 0xA1,
+            (0xA4 | 0xA5, []) => self.entire_display_on = command == 0xA5,
+            (0xA6 | 0xA7, []) => self.inverted = command == 0xA7,
+            (0xA8, &[ratio]) => {
+                if !(15..ROWS as u8).contains(&ratio) {
+                    return Err(invalid(ratio));
+                }
+                self.multiplex_ratio = ratio + 1;
+            }
+            (0xAE | 0xAF, []) => self.display_on = command == 0xAF,
+            (0xB0..=0xB7, []) => self.page = command & 0x07,
+            (0xC0 | 0xC8, []) => self.com_remap = command == 0xC8,
+            (0xD3, &[offset]) => {
+                if offset as usize >= ROWS {
+                    return Err(invalid(offset));
+                }
+                self.display_offset = offset;
+            }
+            // Scrolling, timing, power and COM pins configuration commands and NOP.
+            (0x26 | 0x27 | 0x29 | 0x2A | 0x2E | 0x2F | 0xA3, _)
+            | (0x8D | 0xD5 | 0xD9 | 0xDA | 0xDB, _)
+            | (0xE3, []) => {}
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if the display is turned on.
+    pub fn is_display_on(&self) -> bool {
+        self.display_on
+    }
+
+    /// Returns `true` if the display is inverted.
+    pub fn is_inverted(&self) -> bool {
+        self.inverted
+    }
+
+    /// Returns the contrast setting.
+    pub fn contrast(&self) -> u8 {
+        self.contrast
+    }
+
+    /// Returns the memory addressing mode.
+    pub fn addressing_mode(&self) -> AddressingMode {
+        self.addressing_mode
+    }
+
+    /// Returns the display RAM.
+    ///
+    /// The RAM is organized in 8 pages with 128 bytes each.
+    pub fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    /// Returns the panel pixel at the given position, taking the controller settings into
+    /// account.
+    fn panel_pixel(&self, point: Point) -> BinaryColor {
+        if !self.display_on {
+            return BinaryColor::Off;
+        }
+
+        let (x, y) = (point.x as usize, point.y as usize);
+        let multiplex_ratio = self.multiplex_ratio as usize;
+        if y >= multiplex_ratio {
+            return BinaryColor::Off;
+        }
+
+        let on = if self.entire_display_on {
+            true
+        } else {
+            let row = if self.com_remap {
+                multiplex_ratio - 1 - y
+            } else {
+                y
+            };
+            let row = (row + self.display_offset as usize + self.start_line as usize) % ROWS;
+            let column = if self.segment_remap {
+                COLUMNS - 1 - x
+            } else {
+                x
+            };
+
+            self.ram[row / 8 * COLUMNS + column] & (1 << (row % 8)) != 0
+        };
+
+        BinaryColor::from(on != self.inverted)
+    }
+
+    /// Updates a simulator display with the panel content.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display size doesn't match the panel size.
+    pub fn update(&self, display: &mut SimulatorDisplay<BinaryColor>) {
+        Controller::update(self, display)
+    }
+
+    /// Returns a new simulator display with the panel content.
+    pub fn to_display(&self) -> SimulatorDisplay<BinaryColor> {
+        let mut display = SimulatorDisplay::new(self.size);
+        self.update(&mut display);
+
+        display
+    }
+}
+
+impl Controller for Ssd1306 {
+    type Color = BinaryColor;
+
+    fn write_command(&mut self, bytes: &[u8]) -> Result<(), ControllerError> {
+        Ssd1306::write_command(self, bytes)
+    }
+
+    fn write_data(&mut self, bytes: &[u8]) -> Result<(), ControllerError> {
+        Ssd1306::write_data(self, bytes)
+    }
+
+    fn size(&self) -> Size {
+        self.size
+    }
+
+    fn pixel(&self, point: Point) -> BinaryColor {
+        self.panel_pixel(point)
+    }
+}
+
+/// Returns the number of parameters of a command or `None` if the command is unknown.
+fn parameter_count(command: u8) -> Option<usize> {
+    Some(match command {
+        0x00..=0x17 | 0x2E | 0x2F | 0x40..=0x7F => 0,
+        0xA0 | 0xA1 | 0xA4..=0xA7 | 0xAE | 0xAF | 0xB0..=0xB7 | 0xC0 | 0xC8 | 0xE3 => 0,
+        0x20 | 0x81 | 0x8D | 0xA8 | 0xD3 | 0xD5 | 0xD9 | 0xDA | 0xDB => 1,
+        0x21 | 0x22 | 0xA3 => 2,
+        0x29 | 0x2A => 5,
+        0x26 | 0x27 => 6,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Initialization sequence used by the `ssd1306` driver crate for 128x64 panels.
+    const INIT: &[u8] = &[
+        0xAE, 0xD5, 0x80, 0xA8, 0x3F, 0xD3, 0x00, 0x40, 0x8D, 0x14, 0x20, 0x00, 0xA1, 0xC8, 0xDA,
+        0x12, 0x81, 0xCF, 0xD9, 0xF1, 0xDB, 0x40, 0xA4, 0xA6, 0x2E, 0xAF,
+    ];
+
+    fn on_pixels(controller: &Ssd1306) -> Vec<Point> {
+        let display = controller.to_display();
+        display
+            .bounding_box()
+            .points()
+            .filter(|p| display.get_pixel(*p).is_on())
+            .collect()
+    }
+
+    #[test]
+    fn init_sequence() {
+        let mut controller = Ssd1306::new(Size::new(128, 64));
+        controller.write_command(INIT).unwrap();
+
+        assert!(controller.is_display_on());
+        assert_eq!(controller.contrast(), 0xCF);
+        assert_eq!(controller.addressing_mode(), AddressingMode::Horizontal);
+
+        // With segment and COM remapping the first RAM byte is displayed in the top right
+        // corner, upside down.
+        controller
+            .write_command(&[0x21, 0, 127, 0x22, 0, 7])
+            .unwrap();
+        controller.write_data(&[0x01]).unwrap();
+        assert_eq!(on_pixels(&controller), [Point::new(127, 63)]);
+    }
+
+    #[test]
+    fn horizontal_addressing_window() {
+        let mut controller = Ssd1306::new(Size::new(128, 64));
+        controller
+            .write_command(&[0xAF, 0x20, 0x00, 0x21, 10, 11, 0x22, 2, 3])
+            .unwrap();
+
+        // The fifth byte wraps around to the start of the window and overwrites the first byte.
+        controller.write_data(&[0x01, 0x02, 0x04, 0x08]).unwrap();
+        assert_eq!(
+            controller.write_data(&[0x80]),
+            Err(ControllerError::WindowOverflow)
+        );
+        assert_eq!(
+            on_pixels(&controller),
+            [
+                Point::new(11, 17),
+                Point::new(10, 23),
+                Point::new(10, 26),
+                Point::new(11, 27),
+            ]
+        );
+    }
+
+    #[test]
+    fn vertical_addressing() {
+        let mut controller = Ssd1306::new(Size::new(128, 64));
+        controller
+            .write_command(&[0xAF, 0x20, 0x01, 0x21, 0, 127, 0x22, 6, 7])
+            .unwrap();
+
+        controller.write_data(&[0x01, 0x01, 0x02]).unwrap();
+        assert_eq!(
+            on_pixels(&controller),
+            [Point::new(0, 48), Point::new(1, 49), Point::new(0, 56)]
+        );
+    }
+
+    #[test]
+    fn page_addressing() {
+        let mut controller = Ssd1306::new(Size::new(128, 64));
+        assert_eq!(controller.addressing_mode(), AddressingMode::Page);
+
+        // page 1, column 0x7E
+        controller.write_command(&[0xAF, 0xB1, 0x0E, 0x17]).unwrap();
+        controller.write_data(&[0x01, 0x01]).unwrap();
+        assert_eq!(
+            controller.write_data(&[0x02]),
+            Err(ControllerError::WindowOverflow)
+        );
+
+        // The column address wraps around to the start column without changing the page.
+        assert_eq!(
+            on_pixels(&controller),
+            [Point::new(127, 8), Point::new(126, 9)]
+        );
+    }
+
+    #[test]
+    fn start_line_and_offset() {
+        let mut controller = Ssd1306::new(Size::new(128, 64));
+        controller.write_command(&[0xAF, 0x42]).unwrap();
+        controller.write_data(&[0x04]).unwrap();
+        assert_eq!(on_pixels(&controller), [Point::new(0, 0)]);
+
+        controller.write_command(&[0x40, 0xD3, 62]).unwrap();
+        assert_eq!(on_pixels(&controller), [Point::new(0, 4)]);
+    }
+
+    #[test]
+    fn small_panel() {
+        let mut controller = Ssd1306::new(Size::new(128, 32));
+        controller
+            .write_command(&[0xA8, 0x1F, 0xC8, 0xAF, 0x20, 0x00])
+            .unwrap();
+        controller.write_data(&[0x01]).unwrap();
+
+        assert_eq!(on_pixels(&controller), [Point::new(0, 31)]);
+    }
+
+    #[test]
+    fn display_modes() {
+        let mut controller = Ssd1306::new(Size::new(16, 8));
+        controller.write_data(&[0x01]).unwrap();
+
+        // The display is turned off after a reset.
+        assert!(on_pixels(&controller).is_empty());
+
+        controller.write_command(&[0xAF, 0xA7]).unwrap();
+        assert!(controller.is_inverted());
+        assert_eq!(on_pixels(&controller).len(), 16 * 8 - 1);
+
+        controller.write_command(&[0xA6, 0xA5]).unwrap();
+        assert_eq!(on_pixels(&controller).len(), 16 * 8);
+
+        controller.write_command(&[0xA4, 0xAE]).unwrap();
+        assert!(on_pixels(&controller).is_empty());
+    }
+
+    #[test]
+    fn split_commands() {
+        let mut controller = Ssd1306::new(Size::new(128, 64));
+        controller.write_command(&[0x81]).unwrap();
+        controller.write_command(&[0x10, 0x21]).unwrap();
+        controller.write_command(&[5]).unwrap();
+        controller.write_command(&[6]).unwrap();
+
+        assert_eq!(controller.contrast(), 0x10);
+        assert_eq!(controller.column_window, (5, 6));
+    }
+
+    #[test]
+    fn errors() {
+        let mut controller = Ssd1306::new(Size::new(128, 64));
+
+        assert_eq!(
+            controller.write_command(&[0xFF, 0xAF]),
+            Err(ControllerError::UnknownCommand(0xFF))
+        );
+        assert!(controller.is_display_on());
+
+        assert_eq!(
+            controller.write_command(&[0x22, 0, 8]),
+            Err(ControllerError::InvalidParameter {
+                command: 0x22,
+                parameter: 8
+            })
+        );
+        assert_eq!(
+            controller.write_command(&[0x20, 3]),
+            Err(ControllerError::InvalidParameter {
+                command: 0x20,
+                parameter: 3
+            })
+        );
+
+        controller.write_command(&[0x81]).unwrap();
+        assert_eq!(
+            controller.write_data(&[0xFF]),
+            Err(ControllerError::UnexpectedData)
+        );
+        assert!(controller.ram().iter().all(|byte| *byte == 0));
+    }
+}
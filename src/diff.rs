@@ -0,0 +1,326 @@
+use std::{error::Error, fmt};
+
+use embedded_graphics::{
+    pixelcolor::{BinaryColor, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::{
+    display::SimulatorDisplay, output_image::OutputImage, output_settings::OutputSettings,
+    theme::BinaryColorTheme,
+};
+
+/// Differences between two displays.
+///
+/// A display diff is returned by [`SimulatorDisplay::diff`]. It contains the old and new colors
+/// of all pixels that differ between the two displays and can be rendered into an overlay image
+/// by using [`to_overlay_image`](Self::to_overlay_image).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayDiff<C> {
+    display: SimulatorDisplay<C>,
+    changes: Vec<PixelChange<C>>,
+    bounding_box: Rectangle,
+}
+
+impl<C: PixelColor> DisplayDiff<C> {
+    /// Compares two displays.
+    ///
+    /// Returns `None` if both displays are equal.
+    pub(crate) fn new(old: &SimulatorDisplay<C>, new: &SimulatorDisplay<C>) -> Option<Self> {
+        let changes = new
+            .bounding_box()
+            .points()
+            .filter_map(|point| {
+                let old = old.get_pixel(point);
+                let new = new.get_pixel(point);
+
+                (old != new).then_some(PixelChange { point, old, new })
+            })
+            .collect::<Vec<_>>();
+
+        Self::from_changes(new.clone(), changes)
+    }
+
+    /// Creates a diff from a list of changes.
+    ///
+    /// Returns `None` if the list is empty.
+    fn from_changes(display: SimulatorDisplay<C>, changes: Vec<PixelChange<C>>) -> Option<Self> {
+        if changes.is_empty() {
+            return None;
+        }
+
+        let (min, max) = changes.iter().fold(
+            (
+                Point::new(i32::MAX, i32::MAX),
+                Point::new(i32::MIN, i32::MIN),
+            ),
+            |(min, max), change| {
+                (
+                    min.component_min(change.point),
+                    max.component_max(change.point),
+                )
+            },
+        );
+
+        Some(Self {
+            display,
+            changes,
+            bounding_box: Rectangle::with_corners(min, max),
+        })
+    }
+
+    /// Only keeps the changes for which `f` returns `true`.
+    ///
+    /// Returns `None` if no changes are left.
+    pub(crate) fn retain<F>(mut self, f: F) -> Option<Self>
+    where
+        F: FnMut(&PixelChange<C>) -> bool,
+    {
+        self.changes.retain(f);
+
+        Self::from_changes(self.display, self.changes)
+    }
+
+    /// Returns the number of changed pixels.
+    pub fn changed_pixels(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Returns the bounding box of all changed pixels.
+    pub fn bounding_box(&self) -> Rectangle {
+        self.bounding_box
+    }
+
+    /// Returns the changed pixels.
+    ///
+    /// The changes are ordered by their position, row by row.
+    pub fn changes(&self) -> &[PixelChange<C>] {
+        &self.changes
+    }
+
+    /// Returns a mask of the changed pixels.
+    ///
+    /// All pixels that are different are set to `BinaryColor::On` and all equal pixels to
+    /// `BinaryColor::Off`.
+    pub fn to_mask(&self) -> SimulatorDisplay<BinaryColor> {
+        let mut mask = SimulatorDisplay::new(self.display.size());
+        for change in &self.changes {
+            Pixel(change.point, BinaryColor::On)
+                .draw(&mut mask)
+                .unwrap();
+        }
+
+        mask
+    }
+}
+
+impl<C> DisplayDiff<C>
+where
+    C: PixelColor + Into<Rgb888>,
+{
+    /// Renders the differences into an overlay image.
+    ///
+    /// The overlay is based on a dimmed grayscale version of the new display content. Changed
+    /// pixels are tinted based on their [`ChangeKind`]: added pixels are green, removed pixels
+    /// are red and changed pixels are yellow. The `background` color is used to classify the
+    /// changes.
+    ///
+    /// The theme of the output settings is applied to the display content before it is
+    /// converted to grayscale.
+    pub fn to_overlay_image(
+        &self,
+        background: C,
+        output_settings: &OutputSettings,
+    ) -> OutputImage<Rgb888> {
+        let mut overlay = dimmed_grayscale(&self.display, output_settings.theme);
+
+        for change in &self.changes {
+            let color = match change.kind(background) {
+                ChangeKind::Added => Rgb888::GREEN,
+                ChangeKind::Removed => Rgb888::RED,
+                ChangeKind::Changed => Rgb888::YELLOW,
+            };
+
+            Pixel(change.point, color).draw(&mut overlay).unwrap();
+        }
+
+        // The theme was already applied to the base image and must not change the tints.
+        let output_settings = OutputSettings {
+            theme: BinaryColorTheme::Default,
+            ..*output_settings
+        };
+
+        let mut image = OutputImage::new(overlay.output_size(&output_settings));
+        image.draw_display(&overlay, Point::zero(), &output_settings);
+
+        image
+    }
+}
+
+/// Renders a dimmed grayscale version of a display, which is used as the base of diff images.
+///
+/// The theme is applied to the display content before it is converted to grayscale.
+pub(crate) fn dimmed_grayscale<C>(
+    display: &SimulatorDisplay<C>,
+    theme: BinaryColorTheme,
+) -> SimulatorDisplay<Rgb888>
+where
+    C: PixelColor + Into<Rgb888>,
+{
+    let mut image = SimulatorDisplay::with_default_color(display.size(), Rgb888::BLACK);
+
+    for point in display.bounding_box().points() {
+        let color = theme.convert(display.get_pixel(point).into());
+        let luma =
+            (u32::from(color.r()) * 77 + u32::from(color.g()) * 150 + u32::from(color.b()) * 29)
+                >> 8;
+        let dimmed = (luma / 3) as u8;
+
+        Pixel(point, Rgb888::new(dimmed, dimmed, dimmed))
+            .draw(&mut image)
+            .unwrap();
+    }
+
+    image
+}
+
+/// Changed pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PixelChange<C> {
+    /// Position of the pixel.
+    pub point: Point,
+    /// Old color.
+    pub old: C,
+    /// New color.
+    pub new: C,
+}
+
+impl<C: PixelColor> PixelChange<C> {
+    /// Classifies this change relative to a background color.
+    pub fn kind(&self, background: C) -> ChangeKind {
+        if self.old == background {
+            ChangeKind::Added
+        } else if self.new == background {
+            ChangeKind::Removed
+        } else {
+            ChangeKind::Changed
+        }
+    }
+}
+
+/// Kind of a pixel change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// The pixel was set to a color other than the background color.
+    Added,
+    /// The pixel was reset to the background color.
+    Removed,
+    /// The pixel was changed from one non background color to another.
+    Changed,
+}
+
+/// Error returned when two displays with different sizes are compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SizeMismatchError {
+    /// Size of the display `diff` was called on.
+    pub self_size: Size,
+    /// Size of the other display.
+    pub other_size: Size,
+}
+
+impl fmt::Display for SizeMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "both displays must have the same size (self: {}x{}, other: {}x{})",
+            self.self_size.width,
+            self.self_size.height,
+            self.other_size.width,
+            self.other_size.height,
+        )
+    }
+}
+
+impl Error for SizeMismatchError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::OutputSettingsBuilder;
+
+    fn display(pixels: &[(i32, i32, Rgb888)]) -> SimulatorDisplay<Rgb888> {
+        let mut display = SimulatorDisplay::with_default_color(Size::new(4, 3), Rgb888::BLACK);
+        for (x, y, color) in pixels {
+            Pixel(Point::new(*x, *y), *color)
+                .draw(&mut display)
+                .unwrap();
+        }
+
+        display
+    }
+
+    #[test]
+    fn changes() {
+        let old = display(&[(1, 0, Rgb888::WHITE), (2, 2, Rgb888::BLUE)]);
+        let new = display(&[(0, 1, Rgb888::RED), (2, 2, Rgb888::GREEN)]);
+
+        let diff = new.diff(&old).unwrap().unwrap();
+        assert_eq!(diff.changed_pixels(), 3);
+        assert_eq!(
+            diff.bounding_box(),
+            Rectangle::with_corners(Point::zero(), Point::new(2, 2))
+        );
+
+        let kinds = diff
+            .changes()
+            .iter()
+            .map(|change| (change.point, change.kind(Rgb888::BLACK)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                (Point::new(1, 0), ChangeKind::Removed),
+                (Point::new(0, 1), ChangeKind::Added),
+                (Point::new(2, 2), ChangeKind::Changed),
+            ]
+        );
+
+        assert_eq!(
+            diff.changes()[2],
+            PixelChange {
+                point: Point::new(2, 2),
+                old: Rgb888::BLUE,
+                new: Rgb888::GREEN,
+            }
+        );
+    }
+
+    #[test]
+    fn overlay_image() {
+        let old = display(&[
+            (1, 0, Rgb888::WHITE),
+            (2, 2, Rgb888::BLUE),
+            (3, 0, Rgb888::WHITE),
+        ]);
+        let new = display(&[
+            (0, 1, Rgb888::RED),
+            (2, 2, Rgb888::GREEN),
+            (3, 0, Rgb888::WHITE),
+        ]);
+
+        let diff = new.diff(&old).unwrap().unwrap();
+        let settings = OutputSettingsBuilder::new().scale(2).build();
+        let image = diff
+            .to_overlay_image(Rgb888::BLACK, &settings)
+            .to_rgb_display();
+
+        assert_eq!(image.size(), Size::new(8, 6));
+        assert_eq!(image.get_pixel(Point::new(2, 0)), Rgb888::RED);
+        assert_eq!(image.get_pixel(Point::new(1, 3)), Rgb888::GREEN);
+        assert_eq!(image.get_pixel(Point::new(5, 5)), Rgb888::YELLOW);
+        assert_eq!(image.get_pixel(Point::new(7, 1)), Rgb888::new(85, 85, 85));
+        assert_eq!(image.get_pixel(Point::new(0, 0)), Rgb888::BLACK);
+    }
+}
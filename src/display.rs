@@ -1,22 +1,33 @@
 use std::{
     convert::TryFrom,
-    fs::File,
-    io::BufReader,
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
     path::Path,
     sync::atomic::{AtomicUsize, Ordering},
 };
 
 use embedded_graphics::{
-    pixelcolor::{raw::ToBytes, BinaryColor, Gray8, Rgb888},
+    pixelcolor::{
+        raw::{RawData, ToBytes},
+        BinaryColor, Gray8, Rgb888,
+    },
     prelude::*,
 };
 
-use crate::{output_image::OutputImage, output_settings::OutputSettings};
+use crate::{
+    diff::{DisplayDiff, SizeMismatchError},
+    image_format::ImageFileFormat,
+    import_settings::ImportSettings,
+    memory_layout::MemoryLayout,
+    output_image::OutputImage,
+    output_settings::OutputSettings,
+};
 
 static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
 /// Simulator display.
-#[derive(Debug, Clone, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Eq, PartialOrd, Ord)]
 pub struct SimulatorDisplay<C> {
     size: Size,
     pub(crate) pixels: Box<[C]>,
@@ -24,7 +35,7 @@ pub struct SimulatorDisplay<C> {
 }
 
 impl<C: PixelColor> SimulatorDisplay<C> {
-    fn new_common(size: Size, pixels: Box<[C]>) -> Self {
+    pub(crate) fn new_common(size: Size, pixels: Box<[C]>) -> Self {
         let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
 
         Self { size, pixels, id }
@@ -64,36 +75,23 @@ impl<C: PixelColor> SimulatorDisplay<C> {
 
     /// Compares the content of this display with another display.
     ///
-    /// If both displays are equal `None` is returned, otherwise a difference image is returned.
-    /// All pixels that are different will be filled with `BinaryColor::On` and all equal pixels
-    /// with `BinaryColor::Off`.
-    ///
-    /// # Panics
+    /// If both displays are equal `Ok(None)` is returned, otherwise a [`DisplayDiff`] is returned,
+    /// which contains the changed pixels. `other` is treated as the old and `self` as the new
+    /// display content.
     ///
-    /// Panics if the both display don't have the same size.
-    pub fn diff(&self, other: &SimulatorDisplay<C>) -> Option<SimulatorDisplay<BinaryColor>> {
-        assert!(
-            self.size == other.size,
-            // TODO: use Display impl for Size
-            "both displays must have the same size (self: {}x{}, other: {}x{})",
-            self.size.width,
-            self.size.height,
-            other.size.width,
-            other.size.height,
-        );
-
-        let pixels = self
-            .bounding_box()
-            .points()
-            .map(|p| BinaryColor::from(self.get_pixel(p) != other.get_pixel(p)))
-            .collect::<Vec<_>>()
-            .into_boxed_slice();
-
-        if pixels.iter().any(|p| *p == BinaryColor::On) {
-            Some(SimulatorDisplay::new_common(self.size, pixels))
-        } else {
-            None
+    /// An error is returned if the displays don't have the same size.
+    pub fn diff(
+        &self,
+        other: &SimulatorDisplay<C>,
+    ) -> Result<Option<DisplayDiff<C>>, SizeMismatchError> {
+        if self.size != other.size {
+            return Err(SizeMismatchError {
+                self_size: self.size,
+                other_size: other.size,
+            });
         }
+
+        Ok(DisplayDiff::new(other, self))
     }
 
     /// Calculates the rendered size of this display based on the output settings.
@@ -180,6 +178,13 @@ where
 
         output
     }
+
+    /// Converts the display into an RGB display without applying any output settings.
+    pub(crate) fn to_rgb_display(&self) -> SimulatorDisplay<Rgb888> {
+        let pixels = self.pixels.iter().map(|c| (*c).into()).collect();
+
+        SimulatorDisplay::new_common(self.size, pixels)
+    }
 }
 
 impl<C> SimulatorDisplay<C>
@@ -189,60 +194,220 @@ where
 {
     /// Converts the display content to big endian raw data.
     pub fn to_be_bytes(&self) -> Vec<u8> {
-        self.to_bytes(ToBytes::to_be_bytes)
+        self.to_be_bytes_with_layout(MemoryLayout::RowMajor)
     }
 
     /// Converts the display content to little endian raw data.
     pub fn to_le_bytes(&self) -> Vec<u8> {
-        self.to_bytes(ToBytes::to_le_bytes)
+        self.to_le_bytes_with_layout(MemoryLayout::RowMajor)
     }
 
     /// Converts the display content to native endian raw data.
     pub fn to_ne_bytes(&self) -> Vec<u8> {
-        self.to_bytes(ToBytes::to_ne_bytes)
+        self.to_bytes(MemoryLayout::RowMajor, cfg!(target_endian = "big"))
     }
 
-    fn to_bytes<F>(&self, pixel_to_bytes: F) -> Vec<u8>
-    where
-        F: Fn(C) -> C::Bytes,
-    {
-        let mut bytes = Vec::new();
+    /// Converts the display content to big endian raw data with the given memory layout.
+    ///
+    /// The byte order only applies to colors with 8 or more bits per pixel in a non-planar
+    /// layout. Colors with less than 8 bits per pixel are packed in the bit order defined by the
+    /// layout, and the [`Planar`](MemoryLayout::Planar) layout always stores single bits. In
+    /// these cases the result is the same as the result of
+    /// [`to_le_bytes_with_layout`](Self::to_le_bytes_with_layout).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_simulator::{MemoryLayout, SimulatorDisplay};
+    ///
+    /// let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(128, 64));
+    /// Pixel(Point::new(1, 2), BinaryColor::On).draw(&mut display).unwrap();
+    ///
+    /// // SSD1306 display RAM layout
+    /// let bytes = display.to_be_bytes_with_layout(MemoryLayout::VerticalPages);
+    /// assert_eq!(bytes.len(), 128 * 8);
+    /// assert_eq!(bytes[1], 0b0000_0100);
+    /// ```
+    pub fn to_be_bytes_with_layout(&self, layout: MemoryLayout) -> Vec<u8> {
+        self.to_bytes(layout, true)
+    }
 
-        if C::Raw::BITS_PER_PIXEL >= 8 {
-            for pixel in self.pixels.iter() {
-                bytes.extend_from_slice(pixel_to_bytes(*pixel).as_ref())
-            }
-        } else {
-            let pixels_per_byte = 8 / C::Raw::BITS_PER_PIXEL;
+    /// Converts the display content to little endian raw data with the given memory layout.
+    ///
+    /// The byte order is ignored for colors with less than 8 bits per pixel and for the
+    /// [`Planar`](MemoryLayout::Planar) layout. See
+    /// [`to_be_bytes_with_layout`](Self::to_be_bytes_with_layout) for more details.
+    pub fn to_le_bytes_with_layout(&self, layout: MemoryLayout) -> Vec<u8> {
+        self.to_bytes(layout, false)
+    }
+
+    fn to_bytes(&self, layout: MemoryLayout, big_endian: bool) -> Vec<u8> {
+        let values = self.pixels.iter().map(|pixel| {
+            pixel
+                .to_be_bytes()
+                .as_ref()
+                .iter()
+                .fold(0, |value, byte| value << 8 | u32::from(*byte))
+        });
+
+        layout.pack(self.size, C::Raw::BITS_PER_PIXEL, values, big_endian)
+    }
+}
+
+impl<C> SimulatorDisplay<C>
+where
+    C: PixelColor + From<C::Raw>,
+{
+    /// Creates a display from big endian raw data.
+    ///
+    /// The data must use the same format as the output of [`to_be_bytes`](Self::to_be_bytes).
+    /// Colors with less than 8 bits per pixel are packed into bytes, with the leftmost pixel in
+    /// the most significant bits, and each row starts at a new byte.
+    ///
+    /// An error is returned if the length of the data doesn't match the display size.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_simulator::SimulatorDisplay;
+    ///
+    /// let display =
+    ///     SimulatorDisplay::<BinaryColor>::from_be_bytes(Size::new(10, 2), &[0x80, 0x00, 0x00, 0x40])
+    ///         .unwrap();
+    ///
+    /// assert_eq!(display.get_pixel(Point::new(0, 0)), BinaryColor::On);
+    /// assert_eq!(display.get_pixel(Point::new(9, 1)), BinaryColor::On);
+    /// ```
+    pub fn from_be_bytes(size: Size, bytes: &[u8]) -> Result<Self, ByteLengthError> {
+        Self::from_bytes(size, bytes, MemoryLayout::RowMajor, true)
+    }
+
+    /// Creates a display from little endian raw data.
+    ///
+    /// The data must use the same format as the output of [`to_le_bytes`](Self::to_le_bytes).
+    /// See [`from_be_bytes`](Self::from_be_bytes) for more details.
+    pub fn from_le_bytes(size: Size, bytes: &[u8]) -> Result<Self, ByteLengthError> {
+        Self::from_bytes(size, bytes, MemoryLayout::RowMajor, false)
+    }
 
-            for row in self.pixels.chunks(self.size.width as usize) {
-                for byte_pixels in row.chunks(pixels_per_byte) {
-                    let mut value = 0;
+    /// Creates a display from native endian raw data.
+    ///
+    /// The data must use the same format as the output of [`to_ne_bytes`](Self::to_ne_bytes).
+    /// See [`from_be_bytes`](Self::from_be_bytes) for more details.
+    pub fn from_ne_bytes(size: Size, bytes: &[u8]) -> Result<Self, ByteLengthError> {
+        Self::from_bytes(
+            size,
+            bytes,
+            MemoryLayout::RowMajor,
+            cfg!(target_endian = "big"),
+        )
+    }
 
-                    for pixel in byte_pixels {
-                        value <<= C::Raw::BITS_PER_PIXEL;
-                        value |= pixel.to_be_bytes().as_ref()[0];
-                    }
+    /// Creates a display from big endian raw data with the given memory layout.
+    ///
+    /// An error is returned if the length of the data doesn't match the display size.
+    ///
+    /// The byte order is ignored for colors with less than 8 bits per pixel and for the
+    /// [`Planar`](MemoryLayout::Planar) layout. See
+    /// [`to_be_bytes_with_layout`](Self::to_be_bytes_with_layout) for more details.
+    pub fn from_be_bytes_with_layout(
+        size: Size,
+        bytes: &[u8],
+        layout: MemoryLayout,
+    ) -> Result<Self, ByteLengthError> {
+        Self::from_bytes(size, bytes, layout, true)
+    }
 
-                    value <<= C::Raw::BITS_PER_PIXEL * (pixels_per_byte - byte_pixels.len());
+    /// Creates a display from little endian raw data with the given memory layout.
+    ///
+    /// An error is returned if the length of the data doesn't match the display size.
+    ///
+    /// The byte order is ignored for colors with less than 8 bits per pixel and for the
+    /// [`Planar`](MemoryLayout::Planar) layout. See
+    /// [`to_be_bytes_with_layout`](Self::to_be_bytes_with_layout) for more details.
+    pub fn from_le_bytes_with_layout(
+        size: Size,
+        bytes: &[u8],
+        layout: MemoryLayout,
+    ) -> Result<Self, ByteLengthError> {
+        Self::from_bytes(size, bytes, layout, false)
+    }
 
-                    bytes.push(value);
-                }
-            }
+    fn from_bytes(
+        size: Size,
+        bytes: &[u8],
+        layout: MemoryLayout,
+        big_endian: bool,
+    ) -> Result<Self, ByteLengthError> {
+        let bits_per_pixel = C::Raw::BITS_PER_PIXEL;
+
+        let expected = layout.byte_len(size, bits_per_pixel);
+        if bytes.len() != expected {
+            return Err(ByteLengthError {
+                expected,
+                actual: bytes.len(),
+            });
         }
 
-        bytes
+        let pixels = layout
+            .unpack(size, bits_per_pixel, bytes, big_endian)
+            .into_iter()
+            .map(|value| C::from(C::Raw::from_u32(value)))
+            .collect();
+
+        Ok(Self::new_common(size, pixels))
+    }
+}
+
+/// Error returned when a display is created from raw data with an invalid length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ByteLengthError {
+    /// Expected number of bytes.
+    pub expected: usize,
+    /// Actual number of bytes.
+    pub actual: usize,
+}
+
+impl fmt::Display for ByteLengthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid raw data length (expected: {} bytes, actual: {} bytes)",
+            self.expected, self.actual
+        )
     }
 }
 
+impl Error for ByteLengthError {}
+
 impl<C> SimulatorDisplay<C>
 where
     C: PixelColor + From<Rgb888>,
 {
     /// Loads a PNG file.
     pub fn load_png<P: AsRef<Path>>(path: P) -> image::ImageResult<Self> {
-        let png_file = BufReader::new(File::open(path)?);
-        let image = image::load(png_file, image::ImageFormat::Png)?.to_rgb8();
+        Self::load_with_format(path, ImageFileFormat::Png)
+    }
+
+    /// Loads an image file.
+    ///
+    /// The file format is determined by the file extension. PNG files are always supported and
+    /// other formats can be enabled by using cargo features. See [`ImageFileFormat`] for more
+    /// details.
+    pub fn load<P: AsRef<Path>>(path: P) -> image::ImageResult<Self> {
+        let format = ImageFileFormat::from_path_or_error(path.as_ref())?;
+
+        Self::load_with_format(path, format)
+    }
+
+    /// Loads an image file with the given format.
+    pub fn load_with_format<P: AsRef<Path>>(
+        path: P,
+        format: ImageFileFormat,
+    ) -> image::ImageResult<Self> {
+        let image = format.load(path.as_ref())?.to_rgb8();
 
         let pixels = image
             .pixels()
@@ -256,6 +421,64 @@ where
     }
 }
 
+impl<C> SimulatorDisplay<C>
+where
+    C: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    /// Loads a PNG file with the given import settings.
+    ///
+    /// The import settings can be used to dither the image and to resize it to fit into a given
+    /// size. Dithering is useful to preview color images on displays with a low color depth, like
+    /// [`BinaryColor`] or [`Gray2`](embedded_graphics::pixelcolor::Gray2) displays.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_simulator::{Dithering, ImportSettingsBuilder, SimulatorDisplay};
+    ///
+    /// let settings = ImportSettingsBuilder::new()
+    ///     .dithering(Dithering::FloydSteinberg)
+    ///     .resize_to_fit(Size::new(128, 64))
+    ///     .build();
+    ///
+    /// let display = SimulatorDisplay::<BinaryColor>::load_png_with_settings("mockup.png", &settings)?;
+    /// # Ok::<(), image::ImageError>(())
+    /// ```
+    pub fn load_png_with_settings<P: AsRef<Path>>(
+        path: P,
+        settings: &ImportSettings,
+    ) -> image::ImageResult<Self> {
+        Self::load_with_format_and_settings(path.as_ref(), ImageFileFormat::Png, settings)
+    }
+
+    /// Loads an image file with the given import settings.
+    ///
+    /// The file format is determined by the file extension. See [`load`](Self::load) and
+    /// [`load_png_with_settings`](Self::load_png_with_settings) for more details.
+    pub fn load_with_settings<P: AsRef<Path>>(
+        path: P,
+        settings: &ImportSettings,
+    ) -> image::ImageResult<Self> {
+        let format = ImageFileFormat::from_path_or_error(path.as_ref())?;
+
+        Self::load_with_format_and_settings(path.as_ref(), format, settings)
+    }
+
+    fn load_with_format_and_settings(
+        path: &Path,
+        format: ImageFileFormat,
+        settings: &ImportSettings,
+    ) -> image::ImageResult<Self> {
+        let image = settings.resize(format.load(path)?).to_rgb8();
+
+        Ok(Self::new_common(
+            Size::new(image.width(), image.height()),
+            settings.convert(&image).into_boxed_slice(),
+        ))
+    }
+}
+
 impl<C: PixelColor> DrawTarget for SimulatorDisplay<C> {
     type Color = C;
     type Error = core::convert::Infallible;
@@ -286,6 +509,13 @@ impl<C: PartialEq> PartialEq for SimulatorDisplay<C> {
     }
 }
 
+impl<C: Hash> Hash for SimulatorDisplay<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        self.pixels.hash(state);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -472,12 +702,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_bytes_sub_byte() {
+        let bytes = [
+            0b10000001, 0b00000000, //
+            0b01000010, 0b10000000, //
+            0b00100100, 0b00000000, //
+        ];
+        let display = SimulatorDisplay::<BinaryColor>::from_be_bytes(Size::new(9, 3), &bytes);
+        assert_eq!(display.unwrap().to_be_bytes(), bytes);
+
+        let bytes = [
+            0b00011011, 0b00000000, //
+            0b01001110, 0b01000000, //
+        ];
+        let display = SimulatorDisplay::<Gray2>::from_le_bytes(Size::new(5, 2), &bytes).unwrap();
+        assert_eq!(display.get_pixel(Point::new(3, 1)), Gray2::new(2));
+        assert_eq!(display.to_le_bytes(), bytes);
+
+        let bytes = [0x01, 0x23, 0x40, 0x56, 0x78, 0x90];
+        let display = SimulatorDisplay::<Gray4>::from_ne_bytes(Size::new(5, 2), &bytes).unwrap();
+        assert_eq!(display.get_pixel(Point::new(4, 1)), Gray4::new(9));
+        assert_eq!(display.to_ne_bytes(), bytes);
+    }
+
+    #[test]
+    fn from_bytes_multi_byte() {
+        let pixels = [Rgb565::new(0x10, 0x00, 0x00), Rgb565::new(0x00, 0x00, 0x01)];
+
+        let display =
+            SimulatorDisplay::<Rgb565>::from_be_bytes(Size::new(2, 1), &[0x80, 0x00, 0x00, 0x01])
+                .unwrap();
+        assert_eq!(display.pixels.as_ref(), pixels);
+
+        let display =
+            SimulatorDisplay::<Rgb565>::from_le_bytes(Size::new(2, 1), &[0x00, 0x80, 0x01, 0x00])
+                .unwrap();
+        assert_eq!(display.pixels.as_ref(), pixels);
+
+        let mut display = SimulatorDisplay::<Rgb888>::new(Size::new(3, 2));
+        Pixel(Point::new(1, 1), Rgb888::new(1, 2, 3))
+            .draw(&mut display)
+            .unwrap();
+        for (bytes, from_bytes) in [
+            (
+                display.to_be_bytes(),
+                SimulatorDisplay::<Rgb888>::from_be_bytes as fn(_, &_) -> _,
+            ),
+            (display.to_le_bytes(), SimulatorDisplay::from_le_bytes),
+            (display.to_ne_bytes(), SimulatorDisplay::from_ne_bytes),
+        ] {
+            assert_eq!(from_bytes(display.size(), &bytes).unwrap(), display);
+        }
+    }
+
+    #[test]
+    fn from_bytes_invalid_length() {
+        assert_eq!(
+            SimulatorDisplay::<BinaryColor>::from_be_bytes(Size::new(9, 3), &[0; 3]),
+            Err(ByteLengthError {
+                expected: 6,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            SimulatorDisplay::<Rgb888>::from_le_bytes(Size::new(2, 2), &[0; 13]),
+            Err(ByteLengthError {
+                expected: 12,
+                actual: 13
+            })
+        );
+    }
+
     #[test]
     fn diff_equal() {
         let display = SimulatorDisplay::<BinaryColor>::new(Size::new(4, 6));
         let expected = display.clone();
 
-        assert_eq!(display.diff(&expected), None);
+        assert_eq!(display.diff(&expected), Ok(None));
     }
 
     #[test]
@@ -492,15 +794,25 @@ mod tests {
             .draw(&mut display)
             .unwrap();
 
-        assert_eq!(display.diff(&expected), Some(display));
+        let diff = display.diff(&expected).unwrap().unwrap();
+        assert_eq!(diff.to_mask(), display);
     }
 
     #[test]
-    #[should_panic(expected = "both displays must have the same size (self: 4x6, other: 4x5)")]
     fn diff_wrong_size() {
         let display = SimulatorDisplay::<BinaryColor>::new(Size::new(4, 6));
         let expected = SimulatorDisplay::<BinaryColor>::new(Size::new(4, 5));
 
-        assert_eq!(display.diff(&expected), None);
+        assert_eq!(
+            display.diff(&expected),
+            Err(SizeMismatchError {
+                self_size: Size::new(4, 6),
+                other_size: Size::new(4, 5),
+            })
+        );
+        assert_eq!(
+            display.diff(&expected).unwrap_err().to_string(),
+            "both displays must have the same size (self: 4x6, other: 4x5)"
+        );
     }
 }
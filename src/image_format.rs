@@ -0,0 +1,288 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use image::{
+    error::{ImageFormatHint, UnsupportedError},
+    ColorType, DynamicImage, ImageBuffer, ImageError, ImageFormat, ImageResult,
+};
+
+use crate::png_options::PngOptions;
+
+/// Image file format.
+///
+/// PNG is always supported. All other formats are optional and must be enabled by using the cargo
+/// feature with the same name: `bmp`, `pnm` (for PBM, PGM and PPM), `qoi` and `tga`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ImageFileFormat {
+    /// PNG.
+    Png,
+    /// BMP.
+    #[cfg(feature = "bmp")]
+    Bmp,
+    /// PBM (portable bitmap).
+    ///
+    /// Pixels are stored with one bit per pixel. Pixels with a luma of 50% or more are saved as
+    /// white and all other pixels as black. This makes PBM files a lossless format for displays
+    /// with [`BinaryColor`](embedded_graphics::pixelcolor::BinaryColor) pixels.
+    #[cfg(feature = "pnm")]
+    Pbm,
+    /// PGM (portable graymap).
+    #[cfg(feature = "pnm")]
+    Pgm,
+    /// PPM (portable pixmap).
+    #[cfg(feature = "pnm")]
+    Ppm,
+    /// QOI.
+    #[cfg(feature = "qoi")]
+    Qoi,
+    /// TGA.
+    #[cfg(feature = "tga")]
+    Tga,
+}
+
+impl ImageFileFormat {
+    /// Returns the format that is associated with the extension of a path.
+    ///
+    /// The extension isn't case sensitive. `None` is returned if the path has no extension or if
+    /// the format for the extension isn't supported or enabled.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+
+        Some(match extension.as_str() {
+            "png" => Self::Png,
+            #[cfg(feature = "bmp")]
+            "bmp" => Self::Bmp,
+            #[cfg(feature = "pnm")]
+            "pbm" => Self::Pbm,
+            #[cfg(feature = "pnm")]
+            "pgm" => Self::Pgm,
+            #[cfg(feature = "pnm")]
+            "ppm" => Self::Ppm,
+            #[cfg(feature = "qoi")]
+            "qoi" => Self::Qoi,
+            #[cfg(feature = "tga")]
+            "tga" => Self::Tga,
+            _ => return None,
+        })
+    }
+
+    /// Returns the file extension of this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            #[cfg(feature = "bmp")]
+            Self::Bmp => "bmp",
+            #[cfg(feature = "pnm")]
+            Self::Pbm => "pbm",
+            #[cfg(feature = "pnm")]
+            Self::Pgm => "pgm",
+            #[cfg(feature = "pnm")]
+            Self::Ppm => "ppm",
+            #[cfg(feature = "qoi")]
+            Self::Qoi => "qoi",
+            #[cfg(feature = "tga")]
+            Self::Tga => "tga",
+        }
+    }
+
+    /// Returns the format that is associated with the extension of a path or an error.
+    pub(crate) fn from_path_or_error(path: &Path) -> ImageResult<Self> {
+        Self::from_path(path).ok_or_else(|| {
+            let hint = match path.extension() {
+                Some(extension) => ImageFormatHint::PathExtension(extension.into()),
+                None => ImageFormatHint::Unknown,
+            };
+
+            ImageError::Unsupported(UnsupportedError::from(hint))
+        })
+    }
+
+    /// Returns the corresponding `image` crate format.
+    fn image_format(self) -> ImageFormat {
+        match self {
+            Self::Png => ImageFormat::Png,
+            #[cfg(feature = "bmp")]
+            Self::Bmp => ImageFormat::Bmp,
+            #[cfg(feature = "pnm")]
+            Self::Pbm | Self::Pgm | Self::Ppm => ImageFormat::Pnm,
+            #[cfg(feature = "qoi")]
+            Self::Qoi => ImageFormat::Qoi,
+            #[cfg(feature = "tga")]
+            Self::Tga => ImageFormat::Tga,
+        }
+    }
+
+    /// Loads an image file in this format.
+    pub(crate) fn load(self, path: &Path) -> ImageResult<DynamicImage> {
+        let file = BufReader::new(File::open(path)?);
+
+        image::load(file, self.image_format())
+    }
+
+    /// Encodes raw 8 bit grayscale or RGB image data in this format.
+    pub(crate) fn encode(
+        self,
+        data: &[u8],
+        width: u32,
+        height: u32,
+        color_type: ColorType,
+    ) -> ImageResult<Vec<u8>> {
+        let image = match color_type {
+            ColorType::L8 => {
+                ImageBuffer::from_raw(width, height, data.to_vec()).map(DynamicImage::ImageLuma8)
+            }
+            ColorType::Rgb8 => {
+                ImageBuffer::from_raw(width, height, data.to_vec()).map(DynamicImage::ImageRgb8)
+            }
+            _ => None,
+        }
+        .expect("invalid image data");
+
+        let mut encoded = Vec::new();
+        let data = &mut encoded;
+
+        match self {
+            Self::Png => {
+                *data = PngOptions::default().encode(image.as_bytes(), width, height, color_type)?
+            }
+            #[cfg(feature = "bmp")]
+            Self::Bmp => image.write_with_encoder(image::codecs::bmp::BmpEncoder::new(data))?,
+            #[cfg(feature = "pnm")]
+            Self::Pbm | Self::Pgm | Self::Ppm => {
+                use image::codecs::pnm::{PnmEncoder, PnmSubtype, SampleEncoding};
+
+                let (image, subtype) = match self {
+                    Self::Pbm => {
+                        // The PBM encoder expects `0` for black and `1` for white pixels.
+                        let mut image = image.to_luma8();
+                        for pixel in image.pixels_mut() {
+                            pixel[0] = u8::from(pixel[0] >= 128);
+                        }
+
+                        (image.into(), PnmSubtype::Bitmap(SampleEncoding::Binary))
+                    }
+                    Self::Pgm => (
+                        DynamicImage::from(image.to_luma8()),
+                        PnmSubtype::Graymap(SampleEncoding::Binary),
+                    ),
+                    _ => (
+                        DynamicImage::from(image.to_rgb8()),
+                        PnmSubtype::Pixmap(SampleEncoding::Binary),
+                    ),
+                };
+
+                image.write_with_encoder(PnmEncoder::new(data).with_subtype(subtype))?
+            }
+            #[cfg(feature = "qoi")]
+            Self::Qoi => DynamicImage::from(image.to_rgb8())
+                .write_with_encoder(image::codecs::qoi::QoiEncoder::new(data))?,
+            #[cfg(feature = "tga")]
+            Self::Tga => image.write_with_encoder(image::codecs::tga::TgaEncoder::new(data))?,
+        }
+
+        Ok(encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_utils::TempDir;
+
+    #[test]
+    fn from_path() {
+        assert_eq!(
+            ImageFileFormat::from_path("image.PNG"),
+            Some(ImageFileFormat::Png)
+        );
+        assert_eq!(ImageFileFormat::from_path("image"), None);
+        assert_eq!(ImageFileFormat::from_path("image.jpg"), None);
+
+        assert!(matches!(
+            ImageFileFormat::from_path_or_error(Path::new("image.jpg")),
+            Err(ImageError::Unsupported(_))
+        ));
+    }
+
+    #[cfg(feature = "pnm")]
+    #[test]
+    fn pnm_extensions() {
+        for format in [
+            ImageFileFormat::Pbm,
+            ImageFileFormat::Pgm,
+            ImageFileFormat::Ppm,
+        ] {
+            let path = format!("image.{}", format.extension());
+            assert_eq!(ImageFileFormat::from_path(path), Some(format));
+        }
+    }
+
+    #[cfg(feature = "pnm")]
+    #[test]
+    fn pbm_round_trip() {
+        use embedded_graphics::{
+            pixelcolor::BinaryColor,
+            prelude::*,
+            primitives::{Circle, PrimitiveStyle},
+        };
+
+        use crate::{OutputSettings, SimulatorDisplay};
+
+        let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(13, 9));
+        Circle::new(Point::new(1, 1), 7)
+            .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+            .draw(&mut display)
+            .unwrap();
+
+        let image = display.to_grayscale_output_image(&OutputSettings::default());
+        let pbm = image.encode(ImageFileFormat::Pbm).unwrap();
+        assert!(pbm.starts_with(b"P4\n13 9\n"));
+        // 2 bytes per row.
+        assert_eq!(pbm.len(), b"P4\n13 9\n".len() + 2 * 9);
+
+        let dir = TempDir::new("image-format-pbm");
+        let path = dir.join("image.pbm");
+        image.save(&path).unwrap();
+        let loaded = SimulatorDisplay::<BinaryColor>::load(&path);
+
+        assert_eq!(loaded.unwrap(), display);
+    }
+
+    #[test]
+    fn lossless_round_trip() {
+        use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+        use crate::{OutputSettings, SimulatorDisplay};
+
+        let mut display = SimulatorDisplay::<Rgb888>::new(Size::new(5, 3));
+        for (i, point) in display.bounding_box().points().enumerate() {
+            let value = i as u8 * 17;
+            Pixel(point, Rgb888::new(value, 255 - value, value / 2))
+                .draw(&mut display)
+                .unwrap();
+        }
+        let image = display.to_rgb_output_image(&OutputSettings::default());
+
+        let formats = [
+            ImageFileFormat::Png,
+            #[cfg(feature = "bmp")]
+            ImageFileFormat::Bmp,
+            #[cfg(feature = "pnm")]
+            ImageFileFormat::Ppm,
+            #[cfg(feature = "qoi")]
+            ImageFileFormat::Qoi,
+            #[cfg(feature = "tga")]
+            ImageFileFormat::Tga,
+        ];
+
+        let dir = TempDir::new("image-format-lossless");
+        for format in formats {
+            let path = dir.join(format!("image.{}", format.extension()));
+            image.save(&path).unwrap();
+            let loaded = SimulatorDisplay::<Rgb888>::load_with_format(&path, format);
+
+            assert_eq!(loaded.unwrap(), display, "{format:?}");
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use embedded_graphics::{
+    pixelcolor::{Gray8, Rgb888},
+    prelude::*,
+};
+use image::{imageops::FilterType, DynamicImage, RgbImage};
+
+/// Dithering method used to convert imported images into the display color type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Dithering {
+    /// Converts each pixel to the nearest color without dithering.
+    ///
+    /// For [`BinaryColor`](embedded_graphics::pixelcolor::BinaryColor) displays this is
+    /// equivalent to a threshold at 50% luma.
+    #[default]
+    Threshold,
+    /// Ordered dithering with an 8x8 Bayer matrix.
+    ///
+    /// Ordered dithering creates a regular pattern, which doesn't change if other parts of the
+    /// image are changed. This makes it a good choice for images that are shown in animations.
+    Ordered,
+    /// Floyd–Steinberg error diffusion dithering.
+    FloydSteinberg,
+}
+
+/// Import settings.
+///
+/// The import settings are used by [`SimulatorDisplay::load_png_with_settings`] to convert images
+/// into the color type of the display.
+///
+/// [`SimulatorDisplay::load_png_with_settings`]: crate::SimulatorDisplay::load_png_with_settings
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct ImportSettings {
+    /// Dithering method.
+    pub dithering: Dithering,
+    /// Maximum image size.
+    ///
+    /// If this is set, the image is resized to fit into the given size while keeping its aspect
+    /// ratio.
+    pub resize_to_fit: Option<Size>,
+}
+
+impl ImportSettings {
+    /// Resizes an image if it doesn't fit into the configured size.
+    pub(crate) fn resize(&self, image: DynamicImage) -> DynamicImage {
+        match self.resize_to_fit {
+            Some(size) if size.width > 0 && size.height > 0 => {
+                image.resize(size.width, size.height, FilterType::Triangle)
+            }
+            _ => image,
+        }
+    }
+
+    /// Converts an image into pixels with the given color type.
+    ///
+    /// The pixels are returned in row-major order.
+    pub(crate) fn convert<C>(&self, image: &RgbImage) -> Vec<C>
+    where
+        C: PixelColor + From<Rgb888> + Into<Rgb888>,
+    {
+        let width = image.width() as usize;
+        let grayscale = is_grayscale::<C>();
+        let mut values = image
+            .pixels()
+            .map(|p| {
+                // Images are converted to grayscale first to diffuse the error in luma instead of
+                // the individual channels, which can't be used to represent saturated colors.
+                if grayscale {
+                    let luma = Gray8::from(Rgb888::new(p[0], p[1], p[2])).luma();
+                    [f32::from(luma); 3]
+                } else {
+                    p.0.map(f32::from)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let steps = match self.dithering {
+            Dithering::Ordered => quantization_steps::<C>(),
+            _ => [0.0; 3],
+        };
+
+        let mut pixels = Vec::with_capacity(values.len());
+        for i in 0..values.len() {
+            let (x, y) = (i % width, i / width);
+
+            let mut value = values[i];
+            if self.dithering == Dithering::Ordered {
+                let offset = bayer(x, y);
+                for (channel, step) in value.iter_mut().zip(steps) {
+                    *channel += offset * step;
+                }
+            }
+
+            let color = C::from(to_rgb888(value));
+            pixels.push(color);
+
+            if self.dithering == Dithering::FloydSteinberg {
+                let quantized = color.into();
+                let quantized = [quantized.r(), quantized.g(), quantized.b()];
+                let clamped = value.map(|channel| channel.clamp(0.0, 255.0));
+
+                let mut diffuse = |dx: isize, dy: usize, weight: f32| {
+                    let x = x as isize + dx;
+                    if x < 0 || x as usize >= width {
+                        return;
+                    }
+
+                    if let Some(target) = values.get_mut((y + dy) * width + x as usize) {
+                        for channel in 0..3 {
+                            let error = clamped[channel] - f32::from(quantized[channel]);
+                            target[channel] += error * weight;
+                        }
+                    }
+                };
+
+                diffuse(1, 0, 7.0 / 16.0);
+                diffuse(-1, 1, 3.0 / 16.0);
+                diffuse(0, 1, 5.0 / 16.0);
+                diffuse(1, 1, 1.0 / 16.0);
+            }
+        }
+
+        pixels
+    }
+}
+
+/// Import settings builder.
+#[derive(Default)]
+pub struct ImportSettingsBuilder {
+    dithering: Dithering,
+    resize_to_fit: Option<Size>,
+}
+
+impl ImportSettingsBuilder {
+    /// Creates new import settings builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the dithering method.
+    ///
+    /// The default method is [`Dithering::Threshold`], which doesn't use dithering.
+    pub fn dithering(mut self, dithering: Dithering) -> Self {
+        self.dithering = dithering;
+
+        self
+    }
+
+    /// Resizes the image to fit into the given size.
+    ///
+    /// The aspect ratio of the image is preserved and the image is scaled up or down until it
+    /// fits into the given size. The resulting display has the size of the scaled image, which can
+    /// be smaller than the given size in one dimension.
+    pub fn resize_to_fit(mut self, size: Size) -> Self {
+        self.resize_to_fit = Some(size);
+
+        self
+    }
+
+    /// Builds the import settings.
+    pub fn build(self) -> ImportSettings {
+        ImportSettings {
+            dithering: self.dithering,
+            resize_to_fit: self.resize_to_fit,
+        }
+    }
+}
+
+/// Rounds and clamps channel values to a `Rgb888` color.
+fn to_rgb888(value: [f32; 3]) -> Rgb888 {
+    let [r, g, b] = value.map(|channel| channel.round().clamp(0.0, 255.0) as u8);
+
+    Rgb888::new(r, g, b)
+}
+
+/// Returns the threshold offset of the 8x8 Bayer matrix in the range `-0.5..0.5`.
+fn bayer(x: usize, y: usize) -> f32 {
+    let (x, v) = (x & 7, (x ^ y) & 7);
+    let index =
+        (v & 1) << 5 | (x & 1) << 4 | (v & 2) << 2 | (x & 2) << 1 | (v & 4) >> 1 | (x & 4) >> 2;
+
+    (index as f32 + 0.5) / 64.0 - 0.5
+}
+
+/// Returns `true` if `C` is a grayscale or binary color type.
+fn is_grayscale<C>() -> bool
+where
+    C: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    [Rgb888::RED, Rgb888::GREEN, Rgb888::BLUE]
+        .into_iter()
+        .map(|color| C::from(color).into())
+        .all(|color: Rgb888| color.r() == color.g() && color.g() == color.b())
+}
+
+/// Returns the distance between adjacent levels of each channel after conversion to `C`.
+///
+/// The steps are determined by converting ramps of single channels and grays, which also works
+/// for grayscale and binary color types.
+fn quantization_steps<C>() -> [f32; 3]
+where
+    C: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    let mut steps = [255u8; 3];
+
+    for value in 0..=255 {
+        let ramps = [
+            Rgb888::new(value, 0, 0),
+            Rgb888::new(0, value, 0),
+            Rgb888::new(0, 0, value),
+            Rgb888::new(value, value, value),
+        ];
+
+        for color in ramps {
+            let color: Rgb888 = C::from(color).into();
+            for (step, channel) in steps.iter_mut().zip([color.r(), color.g(), color.b()]) {
+                if channel > 0 {
+                    *step = (*step).min(channel);
+                }
+            }
+        }
+    }
+
+    steps.map(f32::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::pixelcolor::{BinaryColor, Gray2, Rgb565};
+
+    fn gray(width: u32, height: u32, luma: u8) -> RgbImage {
+        RgbImage::from_pixel(width, height, image::Rgb([luma; 3]))
+    }
+
+    fn on_pixels(pixels: &[BinaryColor]) -> usize {
+        pixels.iter().filter(|c| c.is_on()).count()
+    }
+
+    #[test]
+    fn steps() {
+        assert_eq!(quantization_steps::<BinaryColor>(), [255.0; 3]);
+        assert_eq!(quantization_steps::<Gray2>(), [85.0; 3]);
+        assert_eq!(quantization_steps::<Rgb565>(), [8.0, 4.0, 8.0]);
+    }
+
+    #[test]
+    fn threshold() {
+        let settings = ImportSettings::default();
+
+        assert_eq!(on_pixels(&settings.convert(&gray(8, 8, 127))), 0);
+        assert_eq!(on_pixels(&settings.convert(&gray(8, 8, 128))), 64);
+    }
+
+    #[test]
+    fn ordered() {
+        let settings = ImportSettingsBuilder::new()
+            .dithering(Dithering::Ordered)
+            .build();
+
+        // Each matrix index is used once in an 8x8 area.
+        let mut indices = (0..64).map(|i| bayer(i % 8, i / 8)).collect::<Vec<_>>();
+        indices.sort_by(f32::total_cmp);
+        assert_eq!(indices.first(), Some(&(0.5 / 64.0 - 0.5)));
+        assert_eq!(indices.last(), Some(&(63.5 / 64.0 - 0.5)));
+
+        assert_eq!(on_pixels(&settings.convert(&gray(8, 8, 0))), 0);
+        assert_eq!(on_pixels(&settings.convert(&gray(8, 8, 64))), 16);
+        assert_eq!(on_pixels(&settings.convert(&gray(8, 8, 128))), 32);
+        assert_eq!(on_pixels(&settings.convert(&gray(8, 8, 255))), 64);
+
+        // Colors that can be represented exactly aren't changed.
+        let pixels: Vec<Gray2> = settings.convert(&gray(8, 8, 170));
+        assert!(pixels.iter().all(|c| *c == Gray2::new(2)));
+    }
+
+    #[test]
+    fn floyd_steinberg() {
+        let settings = ImportSettingsBuilder::new()
+            .dithering(Dithering::FloydSteinberg)
+            .build();
+
+        assert_eq!(on_pixels(&settings.convert(&gray(16, 16, 0))), 0);
+        let pixels = settings.convert(&gray(16, 16, 64));
+        assert!((on_pixels(&pixels) as i32 - 64).abs() <= 2);
+        assert_eq!(on_pixels(&settings.convert(&gray(16, 16, 255))), 256);
+
+        // Red is converted to a mix of black and white with the luma of red.
+        let image = RgbImage::from_pixel(16, 16, image::Rgb([255, 0, 0]));
+        let pixels: Vec<BinaryColor> = settings.convert(&image);
+        assert!((on_pixels(&pixels) as i32 - 76).abs() <= 2);
+    }
+
+    #[test]
+    fn resize_to_fit() {
+        let settings = ImportSettingsBuilder::new()
+            .resize_to_fit(Size::new(32, 32))
+            .build();
+
+        let image = settings.resize(DynamicImage::ImageRgb8(gray(100, 50, 0)));
+        assert_eq!((image.width(), image.height()), (32, 16));
+
+        let image = settings.resize(DynamicImage::ImageRgb8(gray(4, 8, 0)));
+        assert_eq!((image.width(), image.height()), (16, 32));
+    }
+}
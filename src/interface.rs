@@ -0,0 +1,330 @@
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    rc::Rc,
+};
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use embedded_graphics::prelude::*;
+
+use crate::{
+    controller::{Controller, ControllerError},
+    display::SimulatorDisplay,
+};
+
+/// Write received by a [`SimulatorInterface`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InterfaceWrite {
+    /// Command bytes sent by `send_commands`.
+    Command(Vec<u8>),
+    /// Data bytes sent by `send_data`.
+    Data(Vec<u8>),
+}
+
+#[derive(Debug)]
+struct InterfaceState<T> {
+    controller: T,
+    log: Vec<InterfaceWrite>,
+    errors: Vec<ControllerError>,
+}
+
+/// Simulated display interface.
+///
+/// The simulated interface implements the [`WriteOnlyDataCommand`] trait of the
+/// `display-interface` crate and sends all commands and data to an emulated controller, which is
+/// selected by the type parameter `T`. All [`DataFormat`]s are supported. 16 bit formats are
+/// converted into bytes by using the byte order of the format, or the native byte order for
+/// [`DataFormat::U16`].
+///
+/// All writes are recorded in a log, which can be used to check the commands that were sent by a
+/// display driver. Errors reported by the controller are returned as [`DisplayError`]s and are
+/// also recorded, because the display error type can't contain the details of the error.
+///
+/// The interface is a cheaply cloneable handle to the shared controller. A clone can be passed to
+/// a display driver and the original can be used to render the panel content into a
+/// [`SimulatorDisplay`].
+///
+/// This type is only available if the `display-interface` feature is enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// use display_interface::{DataFormat, WriteOnlyDataCommand};
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+/// use embedded_graphics_simulator::{InterfaceWrite, SimulatorDisplay, SimulatorInterface, Ssd1306};
+///
+/// let interface = SimulatorInterface::new(Ssd1306::new(Size::new(128, 64)));
+///
+/// // The clone is usually passed to a display driver.
+/// let mut driver_interface = interface.clone();
+/// driver_interface
+///     .send_commands(DataFormat::U8(&[0xAF]))
+///     .unwrap();
+/// driver_interface
+///     .send_data(DataFormat::U8(&[0xFF]))
+///     .unwrap();
+///
+/// assert_eq!(interface.log()[0], InterfaceWrite::Command(vec![0xAF]));
+///
+/// let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(128, 64));
+/// interface.update(&mut display);
+/// assert_eq!(display.get_pixel(Point::new(0, 7)), BinaryColor::On);
+/// ```
+#[derive(Debug)]
+pub struct SimulatorInterface<T> {
+    state: Rc<RefCell<InterfaceState<T>>>,
+}
+
+impl<T> SimulatorInterface<T> {
+    /// Creates a new interface for the given controller.
+    pub fn new(controller: T) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(InterfaceState {
+                controller,
+                log: Vec::new(),
+                errors: Vec::new(),
+            })),
+        }
+    }
+
+    /// Returns a reference to the controller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the controller is currently mutably borrowed.
+    pub fn controller(&self) -> Ref<'_, T> {
+        Ref::map(self.state.borrow(), |state| &state.controller)
+    }
+
+    /// Returns a mutable reference to the controller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the controller is currently borrowed.
+    pub fn controller_mut(&self) -> RefMut<'_, T> {
+        RefMut::map(self.state.borrow_mut(), |state| &mut state.controller)
+    }
+
+    /// Returns the log of all received writes.
+    pub fn log(&self) -> Ref<'_, [InterfaceWrite]> {
+        Ref::map(self.state.borrow(), |state| state.log.as_slice())
+    }
+
+    /// Returns the command bytes of all received command writes.
+    pub fn commands(&self) -> Vec<u8> {
+        self.log()
+            .iter()
+            .filter_map(|write| match write {
+                InterfaceWrite::Command(bytes) => Some(bytes.as_slice()),
+                InterfaceWrite::Data(_) => None,
+            })
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// Returns all errors reported by the controller.
+    pub fn errors(&self) -> Ref<'_, [ControllerError]> {
+        Ref::map(self.state.borrow(), |state| state.errors.as_slice())
+    }
+
+    /// Clears the log and the recorded errors.
+    pub fn clear_log(&self) {
+        let mut state = self.state.borrow_mut();
+        state.log.clear();
+        state.errors.clear();
+    }
+}
+
+impl<T: Controller> SimulatorInterface<T> {
+    /// Updates a simulator display with the panel content.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display size doesn't match the panel size.
+    pub fn update<C>(&self, display: &mut SimulatorDisplay<C>)
+    where
+        C: PixelColor + From<T::Color>,
+    {
+        self.controller().update(display);
+    }
+
+    fn write(&self, write: InterfaceWrite) -> Result<(), DisplayError> {
+        let mut state = self.state.borrow_mut();
+
+        let result = match &write {
+            InterfaceWrite::Command(bytes) => state.controller.write_command(bytes),
+            InterfaceWrite::Data(bytes) => state.controller.write_data(bytes),
+        };
+        state.log.push(write);
+
+        result.map_err(|error| {
+            state.errors.push(error);
+
+            match error {
+                ControllerError::WindowOverflow => DisplayError::OutOfBoundsError,
+                _ => DisplayError::BusWriteError,
+            }
+        })
+    }
+}
+
+impl<T> Clone for SimulatorInterface<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Rc::clone(&self.state),
+        }
+    }
+}
+
+impl<T: Controller> WriteOnlyDataCommand for SimulatorInterface<T> {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        let bytes = to_bytes(cmd)?;
+        self.write(InterfaceWrite::Command(bytes))
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        let bytes = to_bytes(buf)?;
+        self.write(InterfaceWrite::Data(bytes))
+    }
+}
+
+/// Converts data in any supported format into bytes.
+fn to_bytes(data: DataFormat<'_>) -> Result<Vec<u8>, DisplayError> {
+    Ok(match data {
+        DataFormat::U8(bytes) => bytes.to_vec(),
+        DataFormat::U16(words) => words.iter().flat_map(|word| word.to_ne_bytes()).collect(),
+        DataFormat::U16BE(words) => words.iter().flat_map(|word| word.to_be_bytes()).collect(),
+        DataFormat::U16LE(words) => words.iter().flat_map(|word| word.to_le_bytes()).collect(),
+        DataFormat::U8Iter(bytes) => bytes.collect(),
+        DataFormat::U16BEIter(words) => words.flat_map(u16::to_be_bytes).collect(),
+        DataFormat::U16LEIter(words) => words.flat_map(u16::to_le_bytes).collect(),
+        _ => return Err(DisplayError::DataFormatNotImplemented),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    use crate::{MipiDcs, MipiDcsModel};
+
+    fn interface() -> SimulatorInterface<MipiDcs> {
+        let mut interface =
+            SimulatorInterface::new(MipiDcs::new(MipiDcsModel::St7789, Size::new(4, 1)));
+
+        for (command, parameters) in [
+            (0x11, &[][..]),
+            (0x3A, &[0x55]),
+            (0x29, &[]),
+            (0x2A, &[0, 0, 0, 3]),
+            (0x2B, &[0, 0, 0, 0]),
+        ] {
+            interface.send_commands(DataFormat::U8(&[command])).unwrap();
+            if !parameters.is_empty() {
+                interface.send_data(DataFormat::U8(parameters)).unwrap();
+            }
+        }
+
+        interface
+    }
+
+    fn pixels(interface: &SimulatorInterface<MipiDcs>) -> Vec<Rgb565> {
+        let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(4, 1));
+        interface.update(&mut display);
+
+        display
+            .bounding_box()
+            .points()
+            .map(|p| display.get_pixel(p))
+            .collect()
+    }
+
+    #[test]
+    fn data_formats() {
+        let red = Rgb565::RED.into_storage();
+        let green = Rgb565::GREEN.into_storage();
+        let expected = vec![Rgb565::RED, Rgb565::GREEN, Rgb565::RED, Rgb565::GREEN];
+
+        let mut interface = interface();
+        interface.send_commands(DataFormat::U8(&[0x2C])).unwrap();
+        interface
+            .send_data(DataFormat::U16BE(&mut [red, green]))
+            .unwrap();
+        interface
+            .send_data(DataFormat::U16BEIter(&mut [red, green].into_iter()))
+            .unwrap();
+        assert_eq!(pixels(&interface), expected);
+
+        let mut interface = self::interface();
+        interface
+            .send_commands(DataFormat::U8Iter(&mut [0x2C].into_iter()))
+            .unwrap();
+        interface
+            .send_data(DataFormat::U16LE(&mut [
+                red.swap_bytes(),
+                green.swap_bytes(),
+            ]))
+            .unwrap();
+        interface
+            .send_data(DataFormat::U16LEIter(
+                &mut [red.swap_bytes(), green.swap_bytes()].into_iter(),
+            ))
+            .unwrap();
+        assert_eq!(pixels(&interface), expected);
+
+        let mut interface = self::interface();
+        interface.send_commands(DataFormat::U8(&[0x2C])).unwrap();
+        interface
+            .send_data(DataFormat::U16(&[
+                u16::from_ne_bytes(red.to_be_bytes()),
+                u16::from_ne_bytes(green.to_be_bytes()),
+            ]))
+            .unwrap();
+        interface
+            .send_data(DataFormat::U8Iter(
+                &mut [red.to_be_bytes(), green.to_be_bytes()]
+                    .into_iter()
+                    .flatten(),
+            ))
+            .unwrap();
+        assert_eq!(pixels(&interface), expected);
+    }
+
+    #[test]
+    fn log() {
+        let interface = interface();
+
+        assert_eq!(interface.commands(), [0x11, 0x3A, 0x29, 0x2A, 0x2B]);
+        assert_eq!(interface.log()[1], InterfaceWrite::Command(vec![0x3A]));
+        assert_eq!(interface.log()[2], InterfaceWrite::Data(vec![0x55]));
+
+        interface.clear_log();
+        assert!(interface.log().is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let mut interface = interface();
+
+        assert!(matches!(
+            interface.send_commands(DataFormat::U8(&[0x80])),
+            Err(DisplayError::BusWriteError)
+        ));
+
+        interface.send_commands(DataFormat::U8(&[0x2C])).unwrap();
+        assert!(matches!(
+            interface.send_data(DataFormat::U8(&[0; 10])),
+            Err(DisplayError::OutOfBoundsError)
+        ));
+
+        assert_eq!(
+            *interface.errors(),
+            [
+                ControllerError::UnknownCommand(0x80),
+                ControllerError::WindowOverflow
+            ]
+        );
+    }
+}
@@ -102,6 +102,31 @@
 //! applies the output settings before exporting the PNG file and the later dumps the unaltered
 //! display content.
 //!
+//! To export a later frame instead of the first one, the frame index can be set by using the
+//! `EG_SIMULATOR_DUMP_FRAME` variable. The first frame has the index `0`:
+//!
+//! ```bash
+//! EG_SIMULATOR_DUMP=screenshot.png EG_SIMULATOR_DUMP_FRAME=100 cargo run
+//! ```
+//!
+//! All frames can be exported by setting `EG_SIMULATOR_DUMP_FRAMES` or `EG_SIMULATOR_DUMP_RAW_FRAMES`
+//! to a path pattern. The pattern must contain a `%d` placeholder, optionally with a zero padded width
+//! like `%05d`, which is replaced by the frame index. In this mode the process isn't terminated after a
+//! frame was exported:
+//!
+//! ```bash
+//! EG_SIMULATOR_DUMP_FRAMES=out/frame_%05d.png cargo run
+//! ```
+//!
+//! PNG files are saved with the best compression level by default, which can be slow for large
+//! images or many frames. The compression level can be changed by setting
+//! `EG_SIMULATOR_DUMP_COMPRESSION` to `none`, `fast`, `balanced`, `best` or a level between `0` and
+//! `9`:
+//!
+//! ```bash
+//! EG_SIMULATOR_DUMP_FRAMES=out/frame_%05d.png EG_SIMULATOR_DUMP_COMPRESSION=fast cargo run
+//! ```
+//!
 //! # Exporting images
 //!
 //! If a program doesn't require to display a window and only needs to export one or more images, a
@@ -110,6 +135,58 @@
 //! The resulting buffer can then be used to save the display content to any format supported by
 //! [`image`].
 //!
+//! PNG files can be saved directly by using [`OutputImage::save_png`] or
+//! [`OutputImage::save_png_with_options`], which uses [`PngOptions`] to configure the compression
+//! level, filter type and text metadata. Other file formats are supported by
+//! [`OutputImage::save`], which selects the format based on the file extension, and by
+//! [`SimulatorDisplay::load`]. BMP, PBM/PGM/PPM, QOI and TGA files can be enabled by using the
+//! `bmp`, `pnm`, `qoi` and `tga` features. Monochrome displays are stored losslessly in PBM files:
+//!
+//! ```toml
+//! [dependencies.embedded-graphics-simulator]
+//! version = "0.8.0"
+//! features = ["pnm"]
+//! ```
+//!
+//! Vector images, e.g. for datasheets, can be created by using [`SimulatorDisplay::to_svg`] or
+//! [`SvgExport`]. The SVG export uses the same scale, pixel spacing and theme as the output image,
+//! merges runs of equal pixels into rectangles to keep the files small and can optionally render
+//! round pixels.
+//!
+//! # Importing images
+//!
+//! PNG files can be loaded into a display by using [`load_png`](SimulatorDisplay::load_png), which
+//! converts each pixel to the nearest color. To preview color images on displays with a low color
+//! depth, [`load_png_with_settings`](SimulatorDisplay::load_png_with_settings) supports ordered and
+//! Floyd–Steinberg dithering and can resize images to fit onto the display:
+//!
+//! ```rust,no_run
+//! use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+//! use embedded_graphics_simulator::{Dithering, ImportSettingsBuilder, SimulatorDisplay};
+//!
+//! let settings = ImportSettingsBuilder::new()
+//!     .dithering(Dithering::FloydSteinberg)
+//!     .resize_to_fit(Size::new(128, 64))
+//!     .build();
+//! let display = SimulatorDisplay::<BinaryColor>::load_png_with_settings("mockup.png", &settings)?;
+//! # Ok::<(), image::ImageError>(())
+//! ```
+//!
+//! # Exporting source code
+//!
+//! Display content can be embedded in firmware by exporting it as source code. The
+//! [`to_rust_source`](SimulatorDisplay::to_rust_source) method creates a `const` byte array and a
+//! ready-to-use `ImageRaw` definition and [`to_c_source`](SimulatorDisplay::to_c_source) creates
+//! a C header. The raw data uses the same format as [`to_be_bytes`](SimulatorDisplay::to_be_bytes),
+//! which matches the format expected by `ImageRaw` for the color type of the display. Additional
+//! options, like little endian byte order, are provided by [`SourceExport`].
+//!
+//! PNG files can be converted to source code by using the `eg-simulator-export` binary:
+//!
+//! ```bash
+//! cargo run --bin eg-simulator-export -- --color rgb565 --name logo logo.png > logo.rs
+//! ```
+//!
 //! # Using the simulator in CI
 //!
 //! The simulator supports two environment variables to check if the display content matches a
@@ -124,6 +201,29 @@
 //! `EG_SIMULATOR_CHECK` assumes that the reference image was created using the same
 //! `OutputSetting`s, while `EG_SIMULATOR_CHECK_RAW` assumes an unstyled reference image.
 //!
+//! By default the display content must match the reference image exactly. Small differences can be
+//! tolerated by setting `EG_SIMULATOR_CHECK_THRESHOLD` to the maximum allowed difference per color
+//! channel and `EG_SIMULATOR_CHECK_MAX_DIFF_PIXELS` to the maximum number of differing pixels.
+//! Differences that aren't visible, e.g. caused by different rounding in anti-aliased output, can
+//! be tolerated by using the perceptual criteria `EG_SIMULATOR_CHECK_MAX_DELTA_E`,
+//! `EG_SIMULATOR_CHECK_MIN_SSIM` and `EG_SIMULATOR_CHECK_MAX_MAE`. See [`Tolerance`] and
+//! [`ImageMetrics`] for more details.
+//!
+//! If the check fails, an image that shows the expected image, the actual display content and the
+//! differing pixels side by side is written next to the reference image, e.g. `screenshot.diff.png`
+//! for `screenshot.png`. The path can be changed by setting `EG_SIMULATOR_CHECK_DIFF`. The actual
+//! display content is written to `screenshot.actual.png`.
+//!
+//! The exit code distinguishes between the possible outcomes: `10` if the display content doesn't
+//! match, `11` if the sizes don't match and `12` if the reference image is missing. If
+//! `EG_SIMULATOR_CHECK_REPORT` is set and the `json` feature is enabled, a machine readable JSON
+//! report of the result is written to the given path. See [`CheckReport`] for a description of the
+//! format.
+//!
+//! The JSON reports of many checks, or the results of snapshot assertions, can be combined into a
+//! single JUnit XML file and a static HTML page, which shows the expected image, the actual display
+//! content and the differences of all checks, by using [`BatchReport`].
+//!
 //! # Usage without SDL2
 //!
 //! When the simulator is used in headless/CI environments that don't require showing a window, SDL2
@@ -142,6 +242,157 @@
 //! Features](https://doc.rust-lang.org/cargo/reference/specifying-dependencies.html#choosing-features)
 //! Cargo manifest documentation for more details.
 //!
+//! # Headless windows
+//!
+//! Windows created with [`Window::new_headless`], or all windows if the `with-sdl` feature is
+//! disabled, don't show anything on the screen. Instead the rendered frames are kept in a bounded
+//! frame history that can be inspected by using [`Window::last_frame`] and [`Window::frames`]. This
+//! makes it possible to test animated or interactive applications without a display server.
+//!
+//! # Terminal windows
+//!
+//! If the `terminal` feature is enabled, windows can be displayed directly in the terminal, which is
+//! useful when working over SSH without X forwarding. Terminal windows are created by using
+//! `Window::new_terminal` or by setting the `EG_SIMULATOR_TERMINAL` environment variable, which
+//! makes it possible to run existing applications in the terminal without code changes:
+//!
+//! ```bash
+//! EG_SIMULATOR_TERMINAL=half-blocks cargo run --features embedded-graphics-simulator/terminal
+//! ```
+//!
+//! The output is drawn using Unicode half blocks with 24-bit colors (`half-blocks`), sixel graphics
+//! (`sixel`) or the kitty graphics protocol (`kitty`). Keystrokes are returned as key events and
+//! `Ctrl+C` terminates the application after the terminal was restored.
+//!
+//! # Browser windows
+//!
+//! If the `browser` feature is enabled, windows can be displayed in a web browser. A small web page
+//! is served on a local socket and each frame is pushed to the browser over a WebSocket, either as a
+//! PNG image or as raw RGB data. Mouse, keyboard and touch input in the browser is sent back and
+//! returned as [`SimulatorEvent`]s. This makes it possible to watch and interact with a running
+//! simulation without a Rust toolchain or SDL2. Browser windows are created by using
+//! `Window::new_browser` or by setting the `EG_SIMULATOR_BROWSER` environment variable:
+//!
+//! ```bash
+//! EG_SIMULATOR_BROWSER=127.0.0.1:8080 cargo run --features embedded-graphics-simulator/browser
+//! ```
+//!
+//! The frame encoding can be selected by setting `EG_SIMULATOR_BROWSER_ENCODING` to `png` or
+//! `rgb`. A `MultiWindow` can be mirrored to the browser by using `MultiWindow::serve_browser`.
+//!
+//! The server runs on background threads, which makes sure that slow browsers don't block the
+//! application. WebSocket connections are only accepted from the served page, which must be opened
+//! by using `localhost` or the IP address of the server. The `browser` feature enables the
+//! `tungstenite` dependency.
+//!
+//! # Scripted input
+//!
+//! Interactive applications can be tested without user interaction by playing back a script of
+//! [`SimulatorEvent`]s. A script can either be created in code and passed to
+//! [`Window::set_input_script`] or be loaded from a file by setting the `EG_SIMULATOR_REPLAY`
+//! environment variable:
+//!
+//! ```bash
+//! EG_SIMULATOR_REPLAY=input.jsonl cargo run
+//! ```
+//!
+//! See [`InputScript`] for a description of the file format. Scripted events are supported with and
+//! without the `with-sdl` feature. Loading scripts from files requires the `json` feature, which
+//! enables the `serde` and `serde_json` dependencies.
+//!
+//! Input sessions can be recorded by setting the `EG_SIMULATOR_RECORD` environment variable or by
+//! calling `Window::record_events`, which also requires the `json` feature. The recording can be
+//! replayed by using `EG_SIMULATOR_REPLAY`, which makes it easy to reproduce bugs:
+//!
+//! ```bash
+//! EG_SIMULATOR_RECORD=session.jsonl cargo run
+//! EG_SIMULATOR_REPLAY=session.jsonl cargo run
+//! ```
+//!
+//! Input scripts and event recordings are only supported by [`Window`]. `EG_SIMULATOR_REPLAY` and
+//! `EG_SIMULATOR_RECORD` are ignored by `MultiWindow`, because it doesn't keep track of frames.
+//!
+//! # Recording animations
+//!
+//! Animated GIF or APNG files of a running application can be created by setting the
+//! `EG_SIMULATOR_RECORD_GIF` environment variable to the path of the output file. The format is
+//! selected by the file extension, `.gif` for GIF and `.png` or `.apng` for APNG:
+//!
+//! ```bash
+//! EG_SIMULATOR_RECORD_GIF=demo.gif cargo run
+//! EG_SIMULATOR_RECORD_GIF=demo.gif EG_SIMULATOR_RECORD_MAX_DURATION=5 cargo run
+//! ```
+//!
+//! The recording uses the themed output image, not the SDL window, and therefore also works for
+//! headless windows. `EG_SIMULATOR_RECORD_MAX_DURATION` stops the recording after the given number
+//! of seconds. Recordings can also be started in code by using `Window::record_animation` or
+//! `MultiWindow::record_animation`, which additionally support a fixed virtual frame timing by using
+//! `AnimationSettings`. Headless windows don't limit the frame rate and therefore record each
+//! frame with a fixed duration of `1 / max_fps` by default.
+//!
+//! Animation recordings require the `animation` feature, which enables the `gif` dependency.
+//!
+//! Long recordings, e.g. of soak tests, can be streamed into an uncompressed YUV4MPEG2 video by
+//! setting the `EG_SIMULATOR_RECORD_Y4M` environment variable to the path of the output file or to
+//! `-` for stdout. The simulator doesn't encode the video, which can be converted by using external
+//! tools:
+//!
+//! ```bash
+//! EG_SIMULATOR_RECORD_Y4M=- cargo run | ffmpeg -i - demo.mp4
+//! ```
+//!
+//! # Snapshot tests
+//!
+//! Unit tests can compare the content of a display with a reference PNG file, called a snapshot,
+//! by using the [`assert_display_snapshot`] macro or [`SimulatorDisplay::assert_matches_snapshot`].
+//! Snapshots are stored in the `tests/snapshots` directory of the crate under test. If a snapshot
+//! doesn't match, the actual display content and an image that highlights the differences are
+//! written next to the snapshot.
+//!
+//! Snapshots are created and updated by running the tests with the `EG_SIMULATOR_BLESS`
+//! environment variable set:
+//!
+//! ```bash
+//! EG_SIMULATOR_BLESS=1 cargo test
+//! ```
+//!
+//! See [`Snapshot`] for more options, like custom tolerances or output settings.
+//!
+//! # Emulated display controllers
+//!
+//! Display driver code can be tested without hardware by sending its command and data byte stream
+//! to an emulated display controller, which renders its display RAM into a [`SimulatorDisplay`].
+//! Emulated controllers report invalid commands as [`ControllerError`]s, which makes it possible
+//! to find bugs in drivers that aren't visible when drawing to a [`SimulatorDisplay`] directly.
+//!
+//! The following controllers are supported:
+//!
+//! * SSD1306 monochrome OLED controller: [`Ssd1306`]
+//! * ST7735, ST7789 and ILI9341 MIPI DCS TFT controllers: [`MipiDcs`]
+//!
+//! If the `embedded-hal` feature is enabled, a `ControllerBus` can be used to connect an emulated
+//! controller to `embedded-hal` SPI and I2C devices. This makes it possible to run existing display
+//! driver crates against the simulator and show the result in a [`Window`]:
+//!
+//! ```rust,ignore
+//! use embedded_graphics_simulator::{ControllerBus, SimulatorDisplay, Ssd1306};
+//!
+//! let bus = ControllerBus::new(Ssd1306::new(Size::new(128, 64)));
+//!
+//! // Initialize a driver that uses the simulated I2C bus and draw to it.
+//! let interface = ssd1306::I2CDisplayInterface::new(bus.i2c(0x3C));
+//! // ...
+//!
+//! let mut display = SimulatorDisplay::new(Size::new(128, 64));
+//! bus.update(&mut display);
+//! window.update(&display);
+//! ```
+//!
+//! Drivers that use the `WriteOnlyDataCommand` trait from the `display-interface` crate can be
+//! connected to an emulated controller by using a `SimulatorInterface`, which requires the
+//! `display-interface` feature. The interface also keeps a log of all received commands, which can
+//! be used to check the command sequence of a driver in tests.
+//!
 //! [`ImageBuffer`]: image::ImageBuffer
 //! [`to_rgb_output_image`]: SimulatorDisplay::to_rgb_output_image
 //! [`to_grayscale_output_image`]: SimulatorDisplay::to_grayscale_output_image
@@ -152,9 +403,28 @@
     rustdoc::private_intra_doc_links
 )]
 
+#[cfg(feature = "embedded-hal")]
+mod bus;
+mod check;
+mod controller;
+mod diff;
 mod display;
+mod image_format;
+mod import_settings;
+#[cfg(feature = "display-interface")]
+mod interface;
+mod memory_layout;
+mod metrics;
 mod output_image;
 mod output_settings;
+mod parse_error;
+mod png_options;
+mod report;
+mod snapshot;
+mod source;
+mod svg;
+#[cfg(test)]
+mod test_utils;
 mod theme;
 mod window;
 
@@ -170,13 +440,56 @@ pub mod sdl2 {
     };
 }
 
+// The compatibility types are also compiled in SDL2 test builds to compare them with `sdl2`.
+#[cfg(any(not(feature = "with-sdl"), test))]
+#[cfg_attr(feature = "with-sdl", allow(dead_code))]
+mod sdl2_compat;
+
+/// Replacements for the sdl2 types used in [`SimulatorEvent`].
+///
+/// If the `with-sdl` feature is disabled this module contains replacements for the types that are
+/// re-exported from the `sdl2` crate in builds with SDL2 support. The replacements provide the
+/// same constants and use the same numeric values as the original types.
+#[cfg(not(feature = "with-sdl"))]
+pub mod sdl2 {
+    pub use crate::sdl2_compat::{Keycode, Mod, MouseButton, MouseWheelDirection};
+}
+
 pub use crate::{
-    display::SimulatorDisplay,
+    check::{CheckReport, CheckStatus, Tolerance},
+    controller::{AddressingMode, Controller, ControllerError, MipiDcs, MipiDcsModel, Ssd1306},
+    diff::{ChangeKind, DisplayDiff, PixelChange, SizeMismatchError},
+    display::{ByteLengthError, SimulatorDisplay},
+    image_format::ImageFileFormat,
+    import_settings::{Dithering, ImportSettings, ImportSettingsBuilder},
+    memory_layout::MemoryLayout,
+    metrics::ImageMetrics,
     output_image::OutputImage,
     output_settings::{OutputSettings, OutputSettingsBuilder},
+    parse_error::ParseError,
+    png_options::{PngCompression, PngFilter, PngOptions},
+    report::BatchReport,
+    snapshot::Snapshot,
+    source::{SourceExport, SourceLanguage},
+    svg::{PixelShape, SvgExport},
     theme::BinaryColorTheme,
-    window::Window,
+    window::{EventTrigger, Frame, InputScript, SimulatorEvent, SimulatorEventsIter, Window},
 };
 
+#[cfg(feature = "animation")]
+pub use window::{AnimationSettings, FrameTiming};
+
 #[cfg(feature = "with-sdl")]
-pub use window::{MultiWindow, SimulatorEvent, SimulatorEventsIter};
+pub use window::MultiWindow;
+
+#[cfg(feature = "terminal")]
+pub use window::TerminalGraphics;
+
+#[cfg(feature = "browser")]
+pub use window::BrowserFrameEncoding;
+
+#[cfg(feature = "embedded-hal")]
+pub use bus::{BusError, ControllerBus, SimulatorDcPin, SimulatorI2c, SimulatorSpiDevice};
+
+#[cfg(feature = "display-interface")]
+pub use interface::{InterfaceWrite, SimulatorInterface};
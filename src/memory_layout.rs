@@ -0,0 +1,326 @@
+use embedded_graphics::prelude::*;
+
+/// Memory layout of raw display data.
+///
+/// The memory layout defines how pixels are arranged in raw display data, which is created by
+/// [`SimulatorDisplay::to_be_bytes_with_layout`] and similar methods. Most layouts only affect
+/// colors with less than 8 bits per pixel, which are packed into bytes. Colors with 8 or more bits
+/// per pixel are always stored in row-major order, except in the [`Planar`](Self::Planar) layout.
+///
+/// The byte order, which is selected by using the big or little endian variant of a method, is
+/// only used for colors with 8 or more bits per pixel in a non-planar layout. Packed colors with
+/// less than 8 bits per pixel use the bit order that is defined by the layout.
+///
+/// [`SimulatorDisplay::to_be_bytes_with_layout`]: crate::SimulatorDisplay::to_be_bytes_with_layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MemoryLayout {
+    /// Row-major layout with the leftmost pixel in the most significant bits.
+    ///
+    /// Each row starts at a new byte. This is the layout used by
+    /// [`to_be_bytes`](crate::SimulatorDisplay::to_be_bytes) and the `ImageRaw` type of
+    /// embedded-graphics.
+    #[default]
+    RowMajor,
+    /// Row-major layout with the leftmost pixel in the least significant bits.
+    ///
+    /// Each row starts at a new byte. This layout is used by some e-paper and memory LCD
+    /// controllers.
+    RowMajorLsbFirst,
+    /// Vertical pages with the topmost pixel in the least significant bits.
+    ///
+    /// Each byte contains a vertical column of pixels, which is 8 pixels high for
+    /// [`BinaryColor`](embedded_graphics::pixelcolor::BinaryColor). The bytes are ordered from
+    /// left to right and page by page from top to bottom. If the display height isn't a multiple
+    /// of the page height, the unused bits of the last page are set to zero.
+    ///
+    /// This layout is used by SSD1306, SH1106 and other monochrome OLED and LCD controllers.
+    VerticalPages,
+    /// Planar layout with one bit plane per bit of the color.
+    ///
+    /// Each plane uses the [`RowMajor`](Self::RowMajor) layout with one bit per pixel. The plane
+    /// for the most significant bit of the color comes first.
+    ///
+    /// Because every plane stores single bits, the byte order of the `*_be_bytes_with_layout` and
+    /// `*_le_bytes_with_layout` methods has no effect on this layout.
+    ///
+    /// This layout is used by some grayscale e-paper controllers, which store the bits of each
+    /// pixel in separate RAM banks.
+    Planar,
+}
+
+impl MemoryLayout {
+    /// Returns the number of bytes required to store a display in this layout.
+    pub(crate) fn byte_len(self, size: Size, bits_per_pixel: usize) -> usize {
+        let (width, height) = (size.width as usize, size.height as usize);
+
+        match self {
+            _ if bits_per_pixel >= 8 && self != MemoryLayout::Planar => {
+                width * height * bits_per_pixel / 8
+            }
+            MemoryLayout::RowMajor | MemoryLayout::RowMajorLsbFirst => {
+                (width * bits_per_pixel).div_ceil(8) * height
+            }
+            MemoryLayout::VerticalPages => width * height.div_ceil(8 / bits_per_pixel),
+            MemoryLayout::Planar => width.div_ceil(8) * height * bits_per_pixel,
+        }
+    }
+
+    /// Returns the byte index and bit shift of a pixel in a layout with less than 8 bits per
+    /// pixel.
+    fn position(
+        self,
+        size: Size,
+        bits_per_pixel: usize,
+        point: (usize, usize),
+        plane: usize,
+    ) -> (usize, usize) {
+        let (width, height) = (size.width as usize, size.height as usize);
+        let (x, y) = point;
+        let pixels_per_byte = 8 / bits_per_pixel;
+
+        match self {
+            MemoryLayout::RowMajor | MemoryLayout::RowMajorLsbFirst => {
+                let bytes_per_row = (width * bits_per_pixel).div_ceil(8);
+                let index = y * bytes_per_row + x / pixels_per_byte;
+                let slot = x % pixels_per_byte;
+
+                if self == MemoryLayout::RowMajor {
+                    (index, 8 - bits_per_pixel * (slot + 1))
+                } else {
+                    (index, bits_per_pixel * slot)
+                }
+            }
+            MemoryLayout::VerticalPages => (
+                y / pixels_per_byte * width + x,
+                bits_per_pixel * (y % pixels_per_byte),
+            ),
+            MemoryLayout::Planar => {
+                let bytes_per_row = width.div_ceil(8);
+                ((plane * height + y) * bytes_per_row + x / 8, 7 - x % 8)
+            }
+        }
+    }
+
+    /// Packs raw color values into bytes.
+    ///
+    /// The values are expected in row-major order.
+    pub(crate) fn pack(
+        self,
+        size: Size,
+        bits_per_pixel: usize,
+        values: impl Iterator<Item = u32>,
+        big_endian: bool,
+    ) -> Vec<u8> {
+        let mut bytes = vec![0; self.byte_len(size, bits_per_pixel)];
+        let width = size.width as usize;
+        if width == 0 {
+            return bytes;
+        }
+
+        if bits_per_pixel >= 8 && self != MemoryLayout::Planar {
+            let bytes_per_pixel = bits_per_pixel / 8;
+
+            for (chunk, value) in bytes.chunks_mut(bytes_per_pixel).zip(values) {
+                if big_endian {
+                    chunk.copy_from_slice(&value.to_be_bytes()[4 - bytes_per_pixel..]);
+                } else {
+                    chunk.copy_from_slice(&value.to_le_bytes()[..bytes_per_pixel]);
+                }
+            }
+        } else if self == MemoryLayout::Planar {
+            for (i, value) in values.enumerate() {
+                let point = (i % width, i / width);
+                for plane in 0..bits_per_pixel {
+                    let bit = value >> (bits_per_pixel - 1 - plane) & 1;
+                    let (index, shift) = self.position(size, 1, point, plane);
+                    bytes[index] |= (bit as u8) << shift;
+                }
+            }
+        } else {
+            for (i, value) in values.enumerate() {
+                let (index, shift) = self.position(size, bits_per_pixel, (i % width, i / width), 0);
+                bytes[index] |= (value as u8) << shift;
+            }
+        }
+
+        bytes
+    }
+
+    /// Unpacks raw color values from bytes.
+    ///
+    /// The values are returned in row-major order. The length of `bytes` must be checked by the
+    /// caller.
+    pub(crate) fn unpack(
+        self,
+        size: Size,
+        bits_per_pixel: usize,
+        bytes: &[u8],
+        big_endian: bool,
+    ) -> Vec<u32> {
+        let (width, height) = (size.width as usize, size.height as usize);
+        debug_assert_eq!(bytes.len(), self.byte_len(size, bits_per_pixel));
+
+        if bits_per_pixel >= 8 && self != MemoryLayout::Planar {
+            return bytes
+                .chunks(bits_per_pixel / 8)
+                .map(|pixel| {
+                    let fold = |value, byte: &u8| value << 8 | u32::from(*byte);
+                    if big_endian {
+                        pixel.iter().fold(0, fold)
+                    } else {
+                        pixel.iter().rev().fold(0, fold)
+                    }
+                })
+                .collect();
+        }
+
+        let mut values = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let value = if self == MemoryLayout::Planar {
+                    (0..bits_per_pixel).fold(0, |value, plane| {
+                        let (index, shift) = self.position(size, 1, (x, y), plane);
+                        value << 1 | u32::from(bytes[index] >> shift & 1)
+                    })
+                } else {
+                    let (index, shift) = self.position(size, bits_per_pixel, (x, y), 0);
+                    u32::from(bytes[index] >> shift) & ((1 << bits_per_pixel) - 1)
+                };
+                values.push(value);
+            }
+        }
+
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::pixelcolor::{BinaryColor, Gray2, Gray4, Rgb565};
+
+    use crate::SimulatorDisplay;
+
+    /// 10x9 display with a diagonal line and a pixel in the bottom right corner.
+    fn binary_display() -> SimulatorDisplay<BinaryColor> {
+        let mut display = SimulatorDisplay::new(Size::new(10, 9));
+        for p in [(0, 0), (1, 1), (2, 2), (9, 8)] {
+            Pixel(Point::new(p.0, p.1), BinaryColor::On)
+                .draw(&mut display)
+                .unwrap();
+        }
+
+        display
+    }
+
+    #[test]
+    fn vertical_pages() {
+        let display = binary_display();
+        let bytes = display.to_be_bytes_with_layout(MemoryLayout::VerticalPages);
+
+        let mut expected = vec![0; 20];
+        expected[0..3].copy_from_slice(&[0x01, 0x02, 0x04]);
+        expected[19] = 0x01;
+        assert_eq!(bytes, expected);
+
+        let imported = SimulatorDisplay::<BinaryColor>::from_be_bytes_with_layout(
+            Size::new(10, 9),
+            &bytes,
+            MemoryLayout::VerticalPages,
+        );
+        assert_eq!(imported.unwrap(), display);
+    }
+
+    #[test]
+    fn vertical_pages_gray2() {
+        let mut display = SimulatorDisplay::<Gray2>::new(Size::new(2, 5));
+        Pixel(Point::new(1, 1), Gray2::new(2))
+            .draw(&mut display)
+            .unwrap();
+        Pixel(Point::new(0, 4), Gray2::new(3))
+            .draw(&mut display)
+            .unwrap();
+
+        let bytes = display.to_le_bytes_with_layout(MemoryLayout::VerticalPages);
+        assert_eq!(bytes, [0b0000_0000, 0b0000_1000, 0b0000_0011, 0b0000_0000]);
+    }
+
+    #[test]
+    fn row_major_lsb_first() {
+        let display = binary_display();
+        let bytes = display.to_be_bytes_with_layout(MemoryLayout::RowMajorLsbFirst);
+
+        assert_eq!(&bytes[0..6], &[0x01, 0x00, 0x02, 0x00, 0x04, 0x00]);
+        assert_eq!(&bytes[16..18], &[0x00, 0x02]);
+
+        let imported = SimulatorDisplay::<BinaryColor>::from_le_bytes_with_layout(
+            Size::new(10, 9),
+            &bytes,
+            MemoryLayout::RowMajorLsbFirst,
+        );
+        assert_eq!(imported.unwrap(), display);
+    }
+
+    #[test]
+    fn planar() {
+        let mut display = SimulatorDisplay::<Gray4>::new(Size::new(9, 1));
+        Pixel(Point::new(0, 0), Gray4::new(0b1010))
+            .draw(&mut display)
+            .unwrap();
+        Pixel(Point::new(8, 0), Gray4::new(0b0011))
+            .draw(&mut display)
+            .unwrap();
+
+        let bytes = display.to_be_bytes_with_layout(MemoryLayout::Planar);
+        assert_eq!(
+            bytes,
+            [
+                0x80, 0x00, // bit 3
+                0x00, 0x00, // bit 2
+                0x80, 0x80, // bit 1
+                0x00, 0x80, // bit 0
+            ]
+        );
+
+        let imported = SimulatorDisplay::<Gray4>::from_be_bytes_with_layout(
+            Size::new(9, 1),
+            &bytes,
+            MemoryLayout::Planar,
+        );
+        assert_eq!(imported.unwrap(), display);
+    }
+
+    #[test]
+    fn multi_byte_colors() {
+        let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(2, 2));
+        Pixel(Point::new(1, 0), Rgb565::RED)
+            .draw(&mut display)
+            .unwrap();
+
+        // Layouts for packed pixels don't affect colors with 8 or more bits per pixel.
+        for layout in [MemoryLayout::RowMajorLsbFirst, MemoryLayout::VerticalPages] {
+            assert_eq!(
+                display.to_be_bytes_with_layout(layout),
+                display.to_be_bytes()
+            );
+            assert_eq!(
+                display.to_le_bytes_with_layout(layout),
+                display.to_le_bytes()
+            );
+        }
+
+        let bytes = display.to_be_bytes_with_layout(MemoryLayout::Planar);
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(&bytes[0..2], &[0x40, 0x00]);
+        assert_eq!(
+            SimulatorDisplay::<Rgb565>::from_le_bytes_with_layout(
+                Size::new(2, 2),
+                &bytes,
+                MemoryLayout::Planar
+            )
+            .unwrap(),
+            display
+        );
+    }
+}
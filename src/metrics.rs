@@ -0,0 +1,340 @@
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+
+use crate::{
+    diff::SizeMismatchError,
+    display::SimulatorDisplay,
+    output_image::{OutputImage, OutputImageColor},
+};
+
+/// Perceptual image comparison metrics.
+///
+/// Image metrics can be used to check if two images are visually the same, even if they aren't
+/// identical, e.g. because anti-aliased edges differ in the least significant bit of a color
+/// channel. Metrics are calculated by using [`SimulatorDisplay::metrics`] or
+/// [`OutputImage::metrics`].
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+/// use embedded_graphics_simulator::SimulatorDisplay;
+///
+/// let mut expected = SimulatorDisplay::<Rgb888>::new(Size::new(16, 16));
+/// expected.clear(Rgb888::new(200, 100, 50)).unwrap();
+///
+/// let mut actual = SimulatorDisplay::<Rgb888>::new(Size::new(16, 16));
+/// actual.clear(Rgb888::new(201, 100, 50)).unwrap();
+///
+/// let metrics = actual.metrics(&expected).unwrap();
+/// assert!(metrics.ssim() > 0.99);
+/// assert!(metrics.max_delta_e() < 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageMetrics {
+    ssim: f64,
+    mean_absolute_error: f64,
+    max_delta_e: f64,
+}
+
+impl ImageMetrics {
+    /// Calculates the metrics for two RGB images of the same size.
+    pub(crate) fn new(a: &SimulatorDisplay<Rgb888>, b: &SimulatorDisplay<Rgb888>) -> Self {
+        debug_assert_eq!(a.size(), b.size());
+
+        let mut absolute_error = 0u64;
+        let mut max_delta_e = 0.0f64;
+
+        for p in a.bounding_box().points() {
+            let (a, b) = (a.get_pixel(p), b.get_pixel(p));
+
+            absolute_error += u64::from(a.r().abs_diff(b.r()))
+                + u64::from(a.g().abs_diff(b.g()))
+                + u64::from(a.b().abs_diff(b.b()));
+
+            if a != b {
+                max_delta_e = max_delta_e.max(delta_e(a, b));
+            }
+        }
+
+        let channels = a.size().width as u64 * a.size().height as u64 * 3;
+        let mean_absolute_error = if channels > 0 {
+            absolute_error as f64 / channels as f64
+        } else {
+            0.0
+        };
+
+        Self {
+            ssim: ssim(a, b),
+            mean_absolute_error,
+            max_delta_e,
+        }
+    }
+
+    /// Returns the structural similarity index (SSIM).
+    ///
+    /// The SSIM is calculated for the luma of both images in 8x8 pixel windows, which overlap by
+    /// 4 pixels, and averaged over all windows. The value is `1.0` for identical images and
+    /// decreases with increasing structural differences.
+    pub fn ssim(&self) -> f64 {
+        self.ssim
+    }
+
+    /// Returns the mean absolute error.
+    ///
+    /// The mean absolute error is the average absolute difference of all red, green and blue
+    /// channels in the range from `0.0` to `255.0`.
+    pub fn mean_absolute_error(&self) -> f64 {
+        self.mean_absolute_error
+    }
+
+    /// Returns the largest color difference of a single pixel.
+    ///
+    /// The color difference is calculated as CIE76 delta E in the CIELAB color space. A delta E
+    /// of about `2.3` corresponds to a just noticeable difference.
+    pub fn max_delta_e(&self) -> f64 {
+        self.max_delta_e
+    }
+}
+
+impl<C> SimulatorDisplay<C>
+where
+    C: PixelColor + Into<Rgb888>,
+{
+    /// Calculates perceptual comparison metrics for the content of this and another display.
+    ///
+    /// An error is returned if the displays don't have the same size.
+    pub fn metrics(&self, other: &SimulatorDisplay<C>) -> Result<ImageMetrics, SizeMismatchError> {
+        if self.size() != other.size() {
+            return Err(SizeMismatchError {
+                self_size: self.size(),
+                other_size: other.size(),
+            });
+        }
+
+        Ok(ImageMetrics::new(
+            &self.to_rgb_display(),
+            &other.to_rgb_display(),
+        ))
+    }
+}
+
+impl<C: OutputImageColor> OutputImage<C> {
+    /// Calculates perceptual comparison metrics for this and another output image.
+    ///
+    /// An error is returned if the images don't have the same size.
+    pub fn metrics(&self, other: &OutputImage<C>) -> Result<ImageMetrics, SizeMismatchError> {
+        if self.size() != other.size() {
+            return Err(SizeMismatchError {
+                self_size: self.size(),
+                other_size: other.size(),
+            });
+        }
+
+        Ok(ImageMetrics::new(
+            &self.to_rgb_display(),
+            &other.to_rgb_display(),
+        ))
+    }
+}
+
+/// Calculates the CIE76 color difference between two colors.
+pub(crate) fn delta_e(a: Rgb888, b: Rgb888) -> f64 {
+    let (a, b) = (to_lab(a), to_lab(b));
+
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+/// Converts a sRGB color into the CIELAB color space using a D65 white point.
+fn to_lab(color: Rgb888) -> [f64; 3] {
+    fn linearize(value: u8) -> f64 {
+        let value = f64::from(value) / 255.0;
+        if value <= 0.04045 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    }
+
+    fn f(t: f64) -> f64 {
+        const DELTA: f64 = 6.0 / 29.0;
+
+        if t > DELTA.powi(3) {
+            t.cbrt()
+        } else {
+            t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+        }
+    }
+
+    let (r, g, b) = (
+        linearize(color.r()),
+        linearize(color.g()),
+        linearize(color.b()),
+    );
+
+    let x = (0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / 0.95047;
+    let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    let z = (0.0193339 * r + 0.1191920 * g + 0.9503041 * b) / 1.08883;
+
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// Calculates the mean SSIM of the luma of two images.
+fn ssim(a: &SimulatorDisplay<Rgb888>, b: &SimulatorDisplay<Rgb888>) -> f64 {
+    const WINDOW: u32 = 8;
+    const STEP: usize = 4;
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+    fn luma(color: Rgb888) -> f64 {
+        0.299 * f64::from(color.r()) + 0.587 * f64::from(color.g()) + 0.114 * f64::from(color.b())
+    }
+
+    /// Returns the start offsets of all windows along one axis.
+    fn offsets(length: u32, window: u32) -> Vec<u32> {
+        let last = length - window;
+        let mut offsets = (0..=last).step_by(STEP).collect::<Vec<_>>();
+        if offsets.last() != Some(&last) {
+            offsets.push(last);
+        }
+
+        offsets
+    }
+
+    let size = a.size();
+    if size.width == 0 || size.height == 0 {
+        return 1.0;
+    }
+
+    let window = Size::new(size.width.min(WINDOW), size.height.min(WINDOW));
+    let n = f64::from(window.width * window.height);
+
+    let mut total = 0.0;
+    let mut count = 0;
+
+    for y in offsets(size.height, window.height) {
+        for x in offsets(size.width, window.width) {
+            let area = Rectangle::new(Point::new(x as i32, y as i32), window);
+
+            let (mut sum_a, mut sum_b) = (0.0, 0.0);
+            let (mut sum_aa, mut sum_bb, mut sum_ab) = (0.0, 0.0, 0.0);
+            for p in area.points() {
+                let (la, lb) = (luma(a.get_pixel(p)), luma(b.get_pixel(p)));
+                sum_a += la;
+                sum_b += lb;
+                sum_aa += la * la;
+                sum_bb += lb * lb;
+                sum_ab += la * lb;
+            }
+
+            let (mean_a, mean_b) = (sum_a / n, sum_b / n);
+            let var_a = sum_aa / n - mean_a * mean_a;
+            let var_b = sum_bb / n - mean_b * mean_b;
+            let covariance = sum_ab / n - mean_a * mean_b;
+
+            total += ((2.0 * mean_a * mean_b + C1) * (2.0 * covariance + C2))
+                / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2));
+            count += 1;
+        }
+    }
+
+    total / f64::from(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::{
+        pixelcolor::Gray8,
+        primitives::{Circle, PrimitiveStyle},
+    };
+
+    use crate::OutputSettings;
+
+    fn circle(size: u32, color: Rgb888) -> SimulatorDisplay<Rgb888> {
+        let mut display = SimulatorDisplay::with_default_color(Size::new(24, 20), Rgb888::BLACK);
+        Circle::new(Point::new(2, 2), size)
+            .into_styled(PrimitiveStyle::with_fill(color))
+            .draw(&mut display)
+            .unwrap();
+
+        display
+    }
+
+    #[test]
+    fn identical() {
+        let display = circle(12, Rgb888::CSS_ORANGE);
+        let metrics = display.metrics(&display).unwrap();
+
+        assert_eq!(metrics.ssim(), 1.0);
+        assert_eq!(metrics.mean_absolute_error(), 0.0);
+        assert_eq!(metrics.max_delta_e(), 0.0);
+    }
+
+    #[test]
+    fn one_lsb_difference() {
+        let a = circle(12, Rgb888::new(200, 100, 50));
+        let b = circle(12, Rgb888::new(201, 100, 51));
+        let metrics = a.metrics(&b).unwrap();
+
+        assert!(metrics.ssim() > 0.999, "{metrics:?}");
+        assert!(metrics.mean_absolute_error() < 0.5, "{metrics:?}");
+        assert!(metrics.max_delta_e() > 0.0, "{metrics:?}");
+        assert!(metrics.max_delta_e() < 1.0, "{metrics:?}");
+    }
+
+    #[test]
+    fn structural_difference() {
+        let a = circle(12, Rgb888::WHITE);
+        let b = circle(16, Rgb888::WHITE);
+        let metrics = a.metrics(&b).unwrap();
+
+        assert!(metrics.ssim() < 0.9, "{metrics:?}");
+        assert!(metrics.max_delta_e() > 99.0, "{metrics:?}");
+    }
+
+    #[test]
+    fn small_images() {
+        let a = SimulatorDisplay::with_default_color(Size::new(3, 1), Rgb888::BLACK);
+        let b = SimulatorDisplay::with_default_color(Size::new(3, 1), Rgb888::WHITE);
+        let metrics = a.metrics(&b).unwrap();
+
+        assert!(metrics.ssim() < 0.01, "{metrics:?}");
+        assert_eq!(metrics.mean_absolute_error(), 255.0);
+    }
+
+    #[test]
+    fn delta_e_black_white() {
+        assert!((delta_e(Rgb888::BLACK, Rgb888::WHITE) - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn output_image_metrics() {
+        let display = circle(12, Rgb888::WHITE);
+        let settings = OutputSettings::default();
+
+        let a = display.to_grayscale_output_image(&settings);
+        let b = circle(12, Rgb888::new(254, 254, 254)).to_grayscale_output_image(&settings);
+        let metrics = a.metrics(&b).unwrap();
+        assert!(metrics.ssim() > 0.999, "{metrics:?}");
+
+        let c = OutputImage::<Gray8>::new(Size::new(1, 1));
+        assert!(a.metrics(&c).is_err());
+    }
+
+    #[test]
+    fn size_mismatch() {
+        let a = SimulatorDisplay::with_default_color(Size::new(3, 1), Rgb888::BLACK);
+        let b = SimulatorDisplay::with_default_color(Size::new(3, 2), Rgb888::BLACK);
+
+        assert_eq!(
+            a.metrics(&b),
+            Err(SizeMismatchError {
+                self_size: Size::new(3, 1),
+                other_size: Size::new(3, 2),
+            })
+        );
+    }
+}
@@ -6,17 +6,17 @@ use embedded_graphics::{
     prelude::*,
     primitives::Rectangle,
 };
-use image::{
-    codecs::png::{CompressionType, FilterType, PngEncoder},
-    ImageBuffer, ImageEncoder, Luma, Rgb,
-};
+use image::{ImageBuffer, Luma, Rgb};
 
-use crate::{display::SimulatorDisplay, output_settings::OutputSettings};
+use crate::{
+    display::SimulatorDisplay, image_format::ImageFileFormat, output_settings::OutputSettings,
+    png_options::PngOptions,
+};
 
 /// Output image.
 ///
 /// An output image is the result of applying [`OutputSettings`] to a [`SimulatorDisplay`]. It can
-/// be used to save a simulator display to a PNG file.
+/// be used to save a simulator display to a PNG file or any other enabled [`ImageFileFormat`].
 ///
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct OutputImage<C> {
@@ -99,38 +99,92 @@ where
 impl<C: OutputImageColor> OutputImage<C> {
     /// Saves the image content to a PNG file.
     pub fn save_png<PATH: AsRef<Path>>(&self, path: PATH) -> image::ImageResult<()> {
-        let png = self.encode_png()?;
+        self.save_png_with_options(path, &PngOptions::default())
+    }
+
+    /// Saves the image content to a PNG file with the given encoding options.
+    pub fn save_png_with_options<PATH: AsRef<Path>>(
+        &self,
+        path: PATH,
+        options: &PngOptions,
+    ) -> image::ImageResult<()> {
+        let png = self.encode_png(options)?;
 
         std::fs::write(path, png)?;
 
         Ok(())
     }
 
+    /// Saves the image content to a file.
+    ///
+    /// The file format is determined by the file extension. PNG files are always supported and
+    /// other formats can be enabled by using cargo features. See [`ImageFileFormat`] for more
+    /// details.
+    pub fn save<PATH: AsRef<Path>>(&self, path: PATH) -> image::ImageResult<()> {
+        let format = ImageFileFormat::from_path_or_error(path.as_ref())?;
+
+        self.save_with_format(path, format)
+    }
+
+    /// Saves the image content to a file with the given format.
+    pub fn save_with_format<PATH: AsRef<Path>>(
+        &self,
+        path: PATH,
+        format: ImageFileFormat,
+    ) -> image::ImageResult<()> {
+        let data = self.encode(format)?;
+
+        std::fs::write(path, data)?;
+
+        Ok(())
+    }
+
     /// Returns the image as a base64 encoded PNG.
     pub fn to_base64_png(&self) -> image::ImageResult<String> {
-        let png = self.encode_png()?;
+        let png = self.encode_png(&PngOptions::default())?;
 
         Ok(base64::engine::general_purpose::STANDARD.encode(png))
     }
 
-    fn encode_png(&self) -> image::ImageResult<Vec<u8>> {
-        let mut png = Vec::new();
-
-        PngEncoder::new_with_quality(&mut png, CompressionType::Best, FilterType::default())
-            .write_image(
-                self.data.as_ref(),
-                self.size.width,
-                self.size.height,
-                C::IMAGE_COLOR_TYPE.into(),
-            )?;
+    /// Encodes the image content as PNG with the given encoding options.
+    pub fn encode_png(&self, options: &PngOptions) -> image::ImageResult<Vec<u8>> {
+        options.encode(
+            self.data.as_ref(),
+            self.size.width,
+            self.size.height,
+            C::IMAGE_COLOR_TYPE,
+        )
+    }
 
-        Ok(png)
+    /// Encodes the image content in the given format.
+    pub fn encode(&self, format: ImageFileFormat) -> image::ImageResult<Vec<u8>> {
+        format.encode(
+            self.data.as_ref(),
+            self.size.width,
+            self.size.height,
+            C::IMAGE_COLOR_TYPE,
+        )
     }
 
     /// Returns the output image as an [`image`] crate [`ImageBuffer`].
     pub fn as_image_buffer(&self) -> ImageBuffer<C::ImageColor, &[u8]> {
         ImageBuffer::from_raw(self.size.width, self.size.height, self.data.as_ref()).unwrap()
     }
+
+    /// Converts the output image into a RGB display with the same size and content.
+    pub(crate) fn to_rgb_display(&self) -> SimulatorDisplay<Rgb888> {
+        let pixels = self
+            .data
+            .chunks_exact(C::BYTES_PER_PIXEL)
+            .map(|p| match *p {
+                [luma] => Rgb888::new(luma, luma, luma),
+                [r, g, b] => Rgb888::new(r, g, b),
+                _ => unreachable!(),
+            })
+            .collect();
+
+        SimulatorDisplay::new_common(self.size, pixels)
+    }
 }
 
 impl DrawTarget for OutputImage<Rgb888> {
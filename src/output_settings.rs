@@ -1,4 +1,5 @@
 use crate::theme::BinaryColorTheme;
+#[cfg(any(feature = "with-sdl", feature = "browser"))]
 use embedded_graphics::prelude::*;
 
 /// Output settings.
@@ -12,7 +13,7 @@ pub struct OutputSettings {
     pub theme: BinaryColorTheme,
 }
 
-#[cfg(feature = "with-sdl")]
+#[cfg(any(feature = "with-sdl", feature = "browser"))]
 impl OutputSettings {
     /// Translates a output coordinate to the corresponding display coordinate.
     pub(crate) const fn output_to_display(&self, output_point: Point) -> Point {
@@ -0,0 +1,34 @@
+use std::{error::Error, fmt};
+
+/// Error returned when an option can't be parsed from a string.
+///
+/// This error is returned by the [`FromStr`](std::str::FromStr) implementations of the option
+/// types in this crate, for example [`PngCompression`](crate::PngCompression).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseError {
+    /// The string that couldn't be parsed.
+    pub value: String,
+    /// Description of the valid values.
+    pub expected: &'static str,
+}
+
+impl ParseError {
+    pub(crate) fn new(value: &str, expected: &'static str) -> Self {
+        Self {
+            value: value.to_string(),
+            expected,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid value \"{}\" (expected {})",
+            self.value, self.expected
+        )
+    }
+}
+
+impl Error for ParseError {}
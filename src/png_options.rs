@@ -0,0 +1,275 @@
+use std::{env, str::FromStr};
+
+use image::{
+    error::{EncodingError, ImageFormatHint},
+    ColorType, ImageError, ImageFormat, ImageResult,
+};
+use png::{BitDepth, DeflateCompression, Encoder, Filter};
+
+use crate::parse_error::ParseError;
+
+/// PNG compression level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PngCompression {
+    /// No compression.
+    None,
+    /// Fast compression with a lower compression ratio.
+    Fast,
+    /// Balance between encoding speed and compression ratio.
+    Balanced,
+    /// Best compression ratio.
+    ///
+    /// This is the slowest compression level and the default.
+    #[default]
+    Best,
+    /// Deflate compression level between `0` and `9`.
+    ///
+    /// Higher values result in smaller files at the cost of encoding speed. Values larger than `9`
+    /// are treated as `9`.
+    Level(u8),
+}
+
+impl PngCompression {
+    fn deflate_compression(self) -> DeflateCompression {
+        match self {
+            Self::None | Self::Level(0) => DeflateCompression::NoCompression,
+            Self::Fast => DeflateCompression::FdeflateUltraFast,
+            Self::Balanced => DeflateCompression::Level(6),
+            Self::Best => DeflateCompression::Level(9),
+            Self::Level(level) => DeflateCompression::Level(level.min(9)),
+        }
+    }
+}
+
+impl FromStr for PngCompression {
+    type Err = ParseError;
+
+    /// Parses a compression level.
+    ///
+    /// Valid values are `none`, `fast`, `balanced`, `best` or a number between `0` and `9`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "none" => Self::None,
+            "fast" => Self::Fast,
+            "balanced" => Self::Balanced,
+            "best" => Self::Best,
+            _ => match s.parse() {
+                Ok(level @ 0..=9) => Self::Level(level),
+                _ => {
+                    return Err(ParseError::new(
+                        s,
+                        "none, fast, balanced, best or a level between 0 and 9",
+                    ))
+                }
+            },
+        })
+    }
+}
+
+/// PNG filter type.
+///
+/// Filters are applied to each row of the image before compression to improve the compression
+/// ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PngFilter {
+    /// No filter.
+    None,
+    /// Difference to the pixel on the left.
+    Sub,
+    /// Difference to the pixel above.
+    Up,
+    /// Difference to the average of the pixels on the left and above.
+    Average,
+    /// Paeth filter.
+    Paeth,
+    /// Selects a filter for each row by using a heuristic.
+    #[default]
+    Adaptive,
+}
+
+impl PngFilter {
+    fn filter(self) -> Filter {
+        match self {
+            Self::None => Filter::NoFilter,
+            Self::Sub => Filter::Sub,
+            Self::Up => Filter::Up,
+            Self::Average => Filter::Avg,
+            Self::Paeth => Filter::Paeth,
+            Self::Adaptive => Filter::Adaptive,
+        }
+    }
+}
+
+/// PNG encoding options.
+///
+/// The default options use the best compression and the adaptive filter, which results in small
+/// files but is relatively slow. Faster compression levels are useful if many images are saved,
+/// for example if all frames of an animation are exported.
+///
+/// The compression level used by the `EG_SIMULATOR_DUMP*` environment variables can be overridden
+/// by setting the `EG_SIMULATOR_DUMP_COMPRESSION` environment variable to `none`, `fast`,
+/// `balanced`, `best` or a level between `0` and `9`.
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+/// use embedded_graphics_simulator::{OutputSettings, PngCompression, PngOptions, SimulatorDisplay};
+///
+/// let display = SimulatorDisplay::<Rgb888>::new(Size::new(64, 64));
+/// let image = display.to_rgb_output_image(&OutputSettings::default());
+///
+/// let options = PngOptions::new()
+///     .compression(PngCompression::Fast)
+///     .text("Title", "Main menu");
+/// let png = image.encode_png(&options)?;
+/// # Ok::<(), image::ImageError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct PngOptions {
+    compression: PngCompression,
+    filter: PngFilter,
+    text: Vec<(String, String)>,
+}
+
+impl PngOptions {
+    /// Creates new PNG options with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates PNG options for images that are exported by using environment variables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `EG_SIMULATOR_DUMP_COMPRESSION` is set to an invalid value.
+    pub(crate) fn from_env() -> Self {
+        let mut options = Self::new();
+
+        if let Ok(value) = env::var("EG_SIMULATOR_DUMP_COMPRESSION") {
+            options.compression = value.parse().unwrap_or_else(|_| {
+                panic!(
+                    "EG_SIMULATOR_DUMP_COMPRESSION must be none, fast, balanced, best or a level \
+                     between 0 and 9"
+                )
+            });
+        }
+
+        options
+    }
+
+    /// Sets the compression level.
+    pub fn compression(mut self, compression: PngCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets the filter type.
+    pub fn filter(mut self, filter: PngFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Adds a text metadata entry.
+    ///
+    /// The entry is stored in an uncompressed `tEXt` chunk. Common keywords are defined in the PNG
+    /// specification, for example `Title`, `Author`, `Description` or `Software`. Keywords must be
+    /// between 1 and 79 characters long and keywords and text must only contain Latin-1
+    /// characters, otherwise encoding the image fails.
+    pub fn text(mut self, keyword: &str, text: &str) -> Self {
+        self.text.push((String::from(keyword), String::from(text)));
+        self
+    }
+
+    /// Encodes raw 8 bit grayscale or RGB image data as PNG.
+    pub(crate) fn encode(
+        &self,
+        data: &[u8],
+        width: u32,
+        height: u32,
+        color_type: ColorType,
+    ) -> ImageResult<Vec<u8>> {
+        let mut png = Vec::new();
+
+        let mut encoder = Encoder::new(&mut png, width, height);
+        encoder.set_color(match color_type {
+            ColorType::L8 => png::ColorType::Grayscale,
+            ColorType::Rgb8 => png::ColorType::Rgb,
+            _ => unreachable!("unsupported color type"),
+        });
+        encoder.set_depth(BitDepth::Eight);
+        encoder.set_deflate_compression(self.compression.deflate_compression());
+        encoder.set_filter(self.filter.filter());
+
+        let result = self
+            .text
+            .iter()
+            .try_for_each(|(keyword, text)| encoder.add_text_chunk(keyword.clone(), text.clone()))
+            .and_then(|_| {
+                let mut writer = encoder.write_header()?;
+                writer.write_image_data(data)?;
+                writer.finish()
+            });
+
+        result.map_err(|e| {
+            ImageError::Encoding(EncodingError::new(
+                ImageFormatHint::Exact(ImageFormat::Png),
+                e,
+            ))
+        })?;
+
+        Ok(png)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes a gradient, which can be compressed well.
+    fn encode(options: &PngOptions) -> Vec<u8> {
+        let data = (0..64 * 64).map(|i| (i % 64) as u8).collect::<Vec<_>>();
+
+        options.encode(&data, 64, 64, ColorType::L8).unwrap()
+    }
+
+    #[test]
+    fn compression() {
+        let none = encode(&PngOptions::new().compression(PngCompression::None));
+        let fast = encode(&PngOptions::new().compression(PngCompression::Fast));
+        let best = encode(&PngOptions::new());
+
+        assert!(none.len() > 64 * 64);
+        assert!(fast.len() < none.len());
+        assert!(best.len() <= fast.len());
+
+        let image = image::load_from_memory(&fast).unwrap().to_luma8();
+        assert_eq!(image.get_pixel(10, 3).0, [10]);
+    }
+
+    #[test]
+    fn text() {
+        let png = encode(&PngOptions::new().text("Software", "simulator"));
+
+        let position = png.windows(4).position(|chunk| chunk == b"tEXt").unwrap();
+        assert_eq!(&png[position + 4..position + 22], b"Software\0simulator");
+
+        let invalid = PngOptions::new().text("", "empty keyword");
+        assert!(invalid.encode(&[0], 1, 1, ColorType::L8).is_err());
+    }
+
+    #[test]
+    fn parse_compression() {
+        assert_eq!("fast".parse(), Ok(PngCompression::Fast));
+        assert_eq!("best".parse(), Ok(PngCompression::Best));
+        assert_eq!("3".parse(), Ok(PngCompression::Level(3)));
+
+        let error = "10".parse::<PngCompression>().unwrap_err();
+        assert_eq!(error.value, "10");
+        assert_eq!(
+            error.to_string(),
+            "invalid value \"10\" (expected none, fast, balanced, best or a level between 0 and 9)"
+        );
+        assert!("slow".parse::<PngCompression>().is_err());
+    }
+}
@@ -0,0 +1,381 @@
+#[cfg(feature = "json")]
+use std::io::ErrorKind;
+use std::{fmt::Write as _, fs, io, path::Path};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+use crate::{
+    check::{CheckReport, CheckStatus},
+    display::SimulatorDisplay,
+    output_settings::OutputSettings,
+};
+
+/// Report for a batch of visual checks.
+///
+/// A batch report collects the results of many reference image checks or snapshot assertions
+/// and can be exported as a JUnit XML file, which is understood by most CI systems, and as a
+/// static HTML page. The HTML page shows the expected image, the actual display content and the
+/// differences of every check inline, which makes it possible to review all visual regressions
+/// from a single artifact.
+///
+/// Results are added as [`CheckReport`]s. The reports can either be created by
+/// [`Snapshot::check`](crate::Snapshot::check) or, if the `json` feature is enabled, be loaded
+/// from the JSON files that are written by using the `EG_SIMULATOR_CHECK_REPORT` environment
+/// variable.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # #[cfg(feature = "json")] {
+/// use embedded_graphics_simulator::BatchReport;
+///
+/// // Collect the reports that were written by running the examples with
+/// // `EG_SIMULATOR_CHECK_REPORT=reports/<example>.json`.
+/// let mut report = BatchReport::new("examples");
+/// report.add_dir("reports").unwrap();
+///
+/// report.save_junit_xml("report.xml").unwrap();
+/// report.save_html("report.html").unwrap();
+///
+/// if report.failures() > 0 {
+///     std::process::exit(1);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchReport {
+    name: String,
+    entries: Vec<(String, CheckReport)>,
+}
+
+impl BatchReport {
+    /// Creates a new empty batch report.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds a check result.
+    pub fn add(&mut self, name: &str, report: CheckReport) {
+        self.entries.push((String::from(name), report));
+    }
+
+    /// Adds a check result from a JSON file.
+    ///
+    /// The file name without the extension is used as the name of the check.
+    ///
+    /// This method requires the `json` feature.
+    #[cfg(feature = "json")]
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        self.add(&name, CheckReport::load(path)?);
+
+        Ok(())
+    }
+
+    /// Adds all JSON check results in a directory.
+    ///
+    /// The files are added in alphabetical order.
+    ///
+    /// This method requires the `json` feature.
+    #[cfg(feature = "json")]
+    pub fn add_dir<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        for path in paths {
+            self.add_file(&path).map_err(|e| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("invalid check report {}: {e}", path.display()),
+                )
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of checks.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the report doesn't contain any checks.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of checks that didn't pass.
+    pub fn failures(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|(_, report)| report.status() != CheckStatus::Passed)
+            .count()
+    }
+
+    /// Returns an iterator over the names and results of all checks.
+    pub fn checks(&self) -> impl Iterator<Item = (&str, &CheckReport)> {
+        self.entries
+            .iter()
+            .map(|(name, report)| (name.as_str(), report))
+    }
+
+    /// Returns the report in JUnit XML format.
+    pub fn to_junit_xml(&self) -> String {
+        let mut xml = String::new();
+        let name = escape(&self.name);
+
+        let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(
+            xml,
+            r#"<testsuites name="{name}" tests="{}" failures="{}">"#,
+            self.len(),
+            self.failures()
+        );
+        let _ = writeln!(
+            xml,
+            r#"  <testsuite name="{name}" tests="{}" failures="{}" errors="0">"#,
+            self.len(),
+            self.failures()
+        );
+
+        for (check, report) in &self.entries {
+            let check = escape(check);
+
+            if report.status() == CheckStatus::Passed {
+                let _ = writeln!(xml, r#"    <testcase name="{check}" classname="{name}"/>"#);
+                continue;
+            }
+
+            let summary = report.message().lines().next().unwrap_or_default();
+            let _ = writeln!(xml, r#"    <testcase name="{check}" classname="{name}">"#);
+            let _ = writeln!(
+                xml,
+                r#"      <failure type="{}" message="{}">{}</failure>"#,
+                status_name(report.status()),
+                escape(summary),
+                escape(report.message())
+            );
+            let _ = writeln!(xml, "    </testcase>");
+        }
+
+        let _ = writeln!(xml, "  </testsuite>");
+        let _ = writeln!(xml, "</testsuites>");
+
+        xml
+    }
+
+    /// Returns the report as a static HTML page.
+    ///
+    /// The images are embedded into the page as base64 encoded PNGs.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        let name = escape(&self.name);
+
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n\
+             <html>\n\
+             <head>\n\
+             <meta charset=\"utf-8\">\n\
+             <title>{name}</title>\n\
+             <style>\n\
+             body {{ font-family: sans-serif; margin: 2em; }}\n\
+             section {{ border: 1px solid #ccc; margin-bottom: 1em; padding: 0 1em 1em 1em; }}\n\
+             .passed h2 {{ color: #080; }}\n\
+             .failed h2 {{ color: #c00; }}\n\
+             pre {{ white-space: pre-wrap; }}\n\
+             figure {{ display: inline-block; margin: 0 1em 0 0; vertical-align: top; }}\n\
+             img {{ image-rendering: pixelated; max-width: 100%; background: #888; }}\n\
+             </style>\n\
+             </head>\n\
+             <body>\n\
+             <h1>{name}</h1>\n\
+             <p>{} checks, {} failed</p>\n",
+            self.len(),
+            self.failures()
+        );
+
+        for (check, report) in &self.entries {
+            let passed = report.status() == CheckStatus::Passed;
+            let _ = write!(
+                html,
+                "<section class=\"{}\">\n<h2>{}: {}</h2>\n<pre>{}</pre>\n",
+                if passed { "passed" } else { "failed" },
+                escape(check),
+                status_name(report.status()),
+                escape(report.message())
+            );
+
+            let expected = SimulatorDisplay::<Rgb888>::load_png(report.expected_path()).ok();
+            let actual = report
+                .actual_path()
+                .and_then(|path| SimulatorDisplay::<Rgb888>::load_png(path).ok());
+            let diff = expected.as_ref().zip(actual.as_ref()).and_then(|(e, a)| {
+                (e.size() == a.size())
+                    .then(|| report.tolerance().compare(e, a))
+                    .flatten()
+                    .map(|mismatch| mismatch.to_diff_image(a))
+            });
+
+            for (caption, image) in [
+                ("expected", expected.as_ref()),
+                ("actual", actual.as_ref()),
+                ("diff", diff.as_ref()),
+            ] {
+                let Some(image) = image else {
+                    continue;
+                };
+                let Ok(png) = image
+                    .to_rgb_output_image(&OutputSettings::default())
+                    .to_base64_png()
+                else {
+                    continue;
+                };
+
+                let _ = writeln!(
+                    html,
+                    "<figure><img src=\"data:image/png;base64,{png}\" alt=\"{caption}\">\
+                     <figcaption>{caption}</figcaption></figure>"
+                );
+            }
+
+            let _ = writeln!(html, "</section>");
+        }
+
+        let _ = write!(html, "</body>\n</html>\n");
+
+        html
+    }
+
+    /// Saves the report as a JUnit XML file.
+    pub fn save_junit_xml<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_junit_xml())
+    }
+
+    /// Saves the report as a static HTML page.
+    pub fn save_html<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_html())
+    }
+}
+
+fn status_name(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Passed => "passed",
+        CheckStatus::Mismatch => "mismatch",
+        CheckStatus::SizeMismatch => "size_mismatch",
+        CheckStatus::ReferenceMissing => "reference_missing",
+    }
+}
+
+/// Escapes a string for use in XML and HTML text and attributes.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    use crate::{test_utils::TempDir, Snapshot};
+
+    fn batch(dir: &Path) -> BatchReport {
+        let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(4, 4));
+        let snapshot = Snapshot::new("a&b").directory(dir);
+
+        let mut batch = BatchReport::new("visual <checks>");
+        batch.add("passed", snapshot.clone().bless(true).check(&display));
+
+        Pixel(Point::new(1, 2), BinaryColor::On)
+            .draw(&mut display)
+            .unwrap();
+        batch.add("mismatch", snapshot.bless(false).check(&display));
+
+        let missing = Snapshot::new("missing").directory(dir).bless(false);
+        batch.add("missing", missing.check(&display));
+
+        batch
+    }
+
+    #[test]
+    fn junit_xml() {
+        let dir = TempDir::new("report-junit");
+        let batch = batch(dir.path());
+
+        assert_eq!(batch.len(), 3);
+        assert_eq!(batch.failures(), 2);
+
+        let xml = batch.to_junit_xml();
+        assert!(xml.contains(
+            r#"<testsuite name="visual &lt;checks&gt;" tests="3" failures="2" errors="0">"#
+        ));
+        assert!(xml.contains(r#"<testcase name="passed" classname="visual &lt;checks&gt;"/>"#));
+        assert!(xml.contains(
+            r#"<failure type="mismatch" message="snapshot &quot;a&amp;b&quot; doesn&apos;t match: "#
+        ));
+        assert!(xml.contains(r#"<failure type="reference_missing" "#));
+    }
+
+    #[test]
+    fn html() {
+        let dir = TempDir::new("report-html");
+        let batch = batch(dir.path());
+
+        let html = batch.to_html();
+        assert!(html.contains("<p>3 checks, 2 failed</p>"));
+        assert!(html.contains("<h2>mismatch: mismatch</h2>"));
+        // expected image for the passed check, all three images for the mismatch and the actual
+        // image for the missing snapshot
+        assert_eq!(html.matches("data:image/png;base64,").count(), 5);
+        assert_eq!(html.matches("alt=\"diff\"").count(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn add_dir() {
+        let dir = TempDir::new("report-dir");
+        let batch = batch(dir.path());
+
+        for (name, report) in batch.checks() {
+            report.save(dir.join(format!("{name}.json"))).unwrap();
+        }
+        fs::write(dir.join("ignored.txt"), "").unwrap();
+
+        let mut loaded = BatchReport::new("visual <checks>");
+        loaded.add_dir(dir.path()).unwrap();
+
+        let names = loaded.checks().map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(names, ["mismatch", "missing", "passed"]);
+        assert_eq!(loaded.failures(), 2);
+    }
+}
@@ -0,0 +1,732 @@
+//! Replacements for the `sdl2` types that are used in [`SimulatorEvent`].
+//!
+//! These types are only used if the `with-sdl` feature is disabled. They mirror the API of the
+//! corresponding `sdl2` types and use the same numeric values, which allows event scripts to be
+//! shared between builds with and without SDL2 support.
+//!
+//! [`SimulatorEvent`]: crate::SimulatorEvent
+
+#![allow(missing_docs)]
+
+use std::{fmt, ops};
+
+/// Virtual key code.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Keycode(i32);
+
+#[allow(non_upper_case_globals)]
+impl Keycode {
+    pub const BACKSPACE: Keycode = Keycode(8);
+    pub const TAB: Keycode = Keycode(9);
+    pub const RETURN: Keycode = Keycode(13);
+    pub const ESCAPE: Keycode = Keycode(27);
+    pub const SPACE: Keycode = Keycode(32);
+    pub const EXCLAIM: Keycode = Keycode(33);
+    pub const QUOTEDBL: Keycode = Keycode(34);
+    pub const HASH: Keycode = Keycode(35);
+    pub const DOLLAR: Keycode = Keycode(36);
+    pub const PERCENT: Keycode = Keycode(37);
+    pub const AMPERSAND: Keycode = Keycode(38);
+    pub const QUOTE: Keycode = Keycode(39);
+    pub const LEFTPAREN: Keycode = Keycode(40);
+    pub const RIGHTPAREN: Keycode = Keycode(41);
+    pub const ASTERISK: Keycode = Keycode(42);
+    pub const PLUS: Keycode = Keycode(43);
+    pub const COMMA: Keycode = Keycode(44);
+    pub const MINUS: Keycode = Keycode(45);
+    pub const PERIOD: Keycode = Keycode(46);
+    pub const SLASH: Keycode = Keycode(47);
+    pub const NUM_0: Keycode = Keycode(48);
+    pub const NUM_1: Keycode = Keycode(49);
+    pub const NUM_2: Keycode = Keycode(50);
+    pub const NUM_3: Keycode = Keycode(51);
+    pub const NUM_4: Keycode = Keycode(52);
+    pub const NUM_5: Keycode = Keycode(53);
+    pub const NUM_6: Keycode = Keycode(54);
+    pub const NUM_7: Keycode = Keycode(55);
+    pub const NUM_8: Keycode = Keycode(56);
+    pub const NUM_9: Keycode = Keycode(57);
+    pub const COLON: Keycode = Keycode(58);
+    pub const SEMICOLON: Keycode = Keycode(59);
+    pub const LESS: Keycode = Keycode(60);
+    pub const EQUALS: Keycode = Keycode(61);
+    pub const GREATER: Keycode = Keycode(62);
+    pub const QUESTION: Keycode = Keycode(63);
+    pub const AT: Keycode = Keycode(64);
+    pub const LEFTBRACKET: Keycode = Keycode(91);
+    pub const BACKSLASH: Keycode = Keycode(92);
+    pub const RIGHTBRACKET: Keycode = Keycode(93);
+    pub const CARET: Keycode = Keycode(94);
+    pub const UNDERSCORE: Keycode = Keycode(95);
+    pub const BACKQUOTE: Keycode = Keycode(96);
+    pub const A: Keycode = Keycode(97);
+    pub const B: Keycode = Keycode(98);
+    pub const C: Keycode = Keycode(99);
+    pub const D: Keycode = Keycode(100);
+    pub const E: Keycode = Keycode(101);
+    pub const F: Keycode = Keycode(102);
+    pub const G: Keycode = Keycode(103);
+    pub const H: Keycode = Keycode(104);
+    pub const I: Keycode = Keycode(105);
+    pub const J: Keycode = Keycode(106);
+    pub const K: Keycode = Keycode(107);
+    pub const L: Keycode = Keycode(108);
+    pub const M: Keycode = Keycode(109);
+    pub const N: Keycode = Keycode(110);
+    pub const O: Keycode = Keycode(111);
+    pub const P: Keycode = Keycode(112);
+    pub const Q: Keycode = Keycode(113);
+    pub const R: Keycode = Keycode(114);
+    pub const S: Keycode = Keycode(115);
+    pub const T: Keycode = Keycode(116);
+    pub const U: Keycode = Keycode(117);
+    pub const V: Keycode = Keycode(118);
+    pub const W: Keycode = Keycode(119);
+    pub const X: Keycode = Keycode(120);
+    pub const Y: Keycode = Keycode(121);
+    pub const Z: Keycode = Keycode(122);
+    pub const DELETE: Keycode = Keycode(127);
+    pub const CAPSLOCK: Keycode = Keycode(0x40000039);
+    pub const F1: Keycode = Keycode(0x4000003A);
+    pub const F2: Keycode = Keycode(0x4000003B);
+    pub const F3: Keycode = Keycode(0x4000003C);
+    pub const F4: Keycode = Keycode(0x4000003D);
+    pub const F5: Keycode = Keycode(0x4000003E);
+    pub const F6: Keycode = Keycode(0x4000003F);
+    pub const F7: Keycode = Keycode(0x40000040);
+    pub const F8: Keycode = Keycode(0x40000041);
+    pub const F9: Keycode = Keycode(0x40000042);
+    pub const F10: Keycode = Keycode(0x40000043);
+    pub const F11: Keycode = Keycode(0x40000044);
+    pub const F12: Keycode = Keycode(0x40000045);
+    pub const PRINTSCREEN: Keycode = Keycode(0x40000046);
+    pub const SCROLLLOCK: Keycode = Keycode(0x40000047);
+    pub const PAUSE: Keycode = Keycode(0x40000048);
+    pub const INSERT: Keycode = Keycode(0x40000049);
+    pub const HOME: Keycode = Keycode(0x4000004A);
+    pub const PAGEUP: Keycode = Keycode(0x4000004B);
+    pub const END: Keycode = Keycode(0x4000004D);
+    pub const PAGEDOWN: Keycode = Keycode(0x4000004E);
+    pub const RIGHT: Keycode = Keycode(0x4000004F);
+    pub const LEFT: Keycode = Keycode(0x40000050);
+    pub const DOWN: Keycode = Keycode(0x40000051);
+    pub const UP: Keycode = Keycode(0x40000052);
+    pub const NUMLOCKCLEAR: Keycode = Keycode(0x40000053);
+    pub const KP_DIVIDE: Keycode = Keycode(0x40000054);
+    pub const KP_MULTIPLY: Keycode = Keycode(0x40000055);
+    pub const KP_MINUS: Keycode = Keycode(0x40000056);
+    pub const KP_PLUS: Keycode = Keycode(0x40000057);
+    pub const KP_ENTER: Keycode = Keycode(0x40000058);
+    pub const KP_1: Keycode = Keycode(0x40000059);
+    pub const KP_2: Keycode = Keycode(0x4000005A);
+    pub const KP_3: Keycode = Keycode(0x4000005B);
+    pub const KP_4: Keycode = Keycode(0x4000005C);
+    pub const KP_5: Keycode = Keycode(0x4000005D);
+    pub const KP_6: Keycode = Keycode(0x4000005E);
+    pub const KP_7: Keycode = Keycode(0x4000005F);
+    pub const KP_8: Keycode = Keycode(0x40000060);
+    pub const KP_9: Keycode = Keycode(0x40000061);
+    pub const KP_0: Keycode = Keycode(0x40000062);
+    pub const KP_PERIOD: Keycode = Keycode(0x40000063);
+    pub const APPLICATION: Keycode = Keycode(0x40000065);
+    pub const POWER: Keycode = Keycode(0x40000066);
+    pub const KP_EQUALS: Keycode = Keycode(0x40000067);
+    pub const F13: Keycode = Keycode(0x40000068);
+    pub const F14: Keycode = Keycode(0x40000069);
+    pub const F15: Keycode = Keycode(0x4000006A);
+    pub const F16: Keycode = Keycode(0x4000006B);
+    pub const F17: Keycode = Keycode(0x4000006C);
+    pub const F18: Keycode = Keycode(0x4000006D);
+    pub const F19: Keycode = Keycode(0x4000006E);
+    pub const F20: Keycode = Keycode(0x4000006F);
+    pub const F21: Keycode = Keycode(0x40000070);
+    pub const F22: Keycode = Keycode(0x40000071);
+    pub const F23: Keycode = Keycode(0x40000072);
+    pub const F24: Keycode = Keycode(0x40000073);
+    pub const EXECUTE: Keycode = Keycode(0x40000074);
+    pub const HELP: Keycode = Keycode(0x40000075);
+    pub const MENU: Keycode = Keycode(0x40000076);
+    pub const SELECT: Keycode = Keycode(0x40000077);
+    pub const STOP: Keycode = Keycode(0x40000078);
+    pub const AGAIN: Keycode = Keycode(0x40000079);
+    pub const UNDO: Keycode = Keycode(0x4000007A);
+    pub const CUT: Keycode = Keycode(0x4000007B);
+    pub const COPY: Keycode = Keycode(0x4000007C);
+    pub const PASTE: Keycode = Keycode(0x4000007D);
+    pub const FIND: Keycode = Keycode(0x4000007E);
+    pub const MUTE: Keycode = Keycode(0x4000007F);
+    pub const VOLUMEUP: Keycode = Keycode(0x40000080);
+    pub const VOLUMEDOWN: Keycode = Keycode(0x40000081);
+    pub const KP_COMMA: Keycode = Keycode(0x40000085);
+    pub const KP_EQUALSAS400: Keycode = Keycode(0x40000086);
+    pub const ALTERASE: Keycode = Keycode(0x40000099);
+    pub const SYSREQ: Keycode = Keycode(0x4000009A);
+    pub const CANCEL: Keycode = Keycode(0x4000009B);
+    pub const CLEAR: Keycode = Keycode(0x4000009C);
+    pub const PRIOR: Keycode = Keycode(0x4000009D);
+    pub const RETURN2: Keycode = Keycode(0x4000009E);
+    pub const SEPARATOR: Keycode = Keycode(0x4000009F);
+    pub const OUT: Keycode = Keycode(0x400000A0);
+    pub const OPER: Keycode = Keycode(0x400000A1);
+    pub const CLEARAGAIN: Keycode = Keycode(0x400000A2);
+    pub const CRSEL: Keycode = Keycode(0x400000A3);
+    pub const EXSEL: Keycode = Keycode(0x400000A4);
+    pub const KP_00: Keycode = Keycode(0x400000B0);
+    pub const KP_000: Keycode = Keycode(0x400000B1);
+    pub const THOUSANDSSEPARATOR: Keycode = Keycode(0x400000B2);
+    pub const DECIMALSEPARATOR: Keycode = Keycode(0x400000B3);
+    pub const CURRENCYUNIT: Keycode = Keycode(0x400000B4);
+    pub const CURRENCYSUBUNIT: Keycode = Keycode(0x400000B5);
+    pub const KP_LEFTPAREN: Keycode = Keycode(0x400000B6);
+    pub const KP_RIGHTPAREN: Keycode = Keycode(0x400000B7);
+    pub const KP_LEFTBRACE: Keycode = Keycode(0x400000B8);
+    pub const KP_RIGHTBRACE: Keycode = Keycode(0x400000B9);
+    pub const KP_TAB: Keycode = Keycode(0x400000BA);
+    pub const KP_BACKSPACE: Keycode = Keycode(0x400000BB);
+    pub const KP_A: Keycode = Keycode(0x400000BC);
+    pub const KP_B: Keycode = Keycode(0x400000BD);
+    pub const KP_C: Keycode = Keycode(0x400000BE);
+    pub const KP_D: Keycode = Keycode(0x400000BF);
+    pub const KP_E: Keycode = Keycode(0x400000C0);
+    pub const KP_F: Keycode = Keycode(0x400000C1);
+    pub const KP_XOR: Keycode = Keycode(0x400000C2);
+    pub const KP_POWER: Keycode = Keycode(0x400000C3);
+    pub const KP_PERCENT: Keycode = Keycode(0x400000C4);
+    pub const KP_LESS: Keycode = Keycode(0x400000C5);
+    pub const KP_GREATER: Keycode = Keycode(0x400000C6);
+    pub const KP_AMPERSAND: Keycode = Keycode(0x400000C7);
+    pub const KP_DBLAMPERSAND: Keycode = Keycode(0x400000C8);
+    pub const KP_VERTICALBAR: Keycode = Keycode(0x400000C9);
+    pub const KP_DBLVERTICALBAR: Keycode = Keycode(0x400000CA);
+    pub const KP_COLON: Keycode = Keycode(0x400000CB);
+    pub const KP_HASH: Keycode = Keycode(0x400000CC);
+    pub const KP_SPACE: Keycode = Keycode(0x400000CD);
+    pub const KP_AT: Keycode = Keycode(0x400000CE);
+    pub const KP_EXCLAM: Keycode = Keycode(0x400000CF);
+    pub const KP_MEMSTORE: Keycode = Keycode(0x400000D0);
+    pub const KP_MEMRECALL: Keycode = Keycode(0x400000D1);
+    pub const KP_MEMCLEAR: Keycode = Keycode(0x400000D2);
+    pub const KP_MEMADD: Keycode = Keycode(0x400000D3);
+    pub const KP_MEMSUBTRACT: Keycode = Keycode(0x400000D4);
+    pub const KP_MEMMULTIPLY: Keycode = Keycode(0x400000D5);
+    pub const KP_MEMDIVIDE: Keycode = Keycode(0x400000D6);
+    pub const KP_PLUSMINUS: Keycode = Keycode(0x400000D7);
+    pub const KP_CLEAR: Keycode = Keycode(0x400000D8);
+    pub const KP_CLEARENTRY: Keycode = Keycode(0x400000D9);
+    pub const KP_BINARY: Keycode = Keycode(0x400000DA);
+    pub const KP_OCTAL: Keycode = Keycode(0x400000DB);
+    pub const KP_DECIMAL: Keycode = Keycode(0x400000DC);
+    pub const KP_HEXADECIMAL: Keycode = Keycode(0x400000DD);
+    pub const LCTRL: Keycode = Keycode(0x400000E0);
+    pub const LSHIFT: Keycode = Keycode(0x400000E1);
+    pub const LALT: Keycode = Keycode(0x400000E2);
+    pub const LGUI: Keycode = Keycode(0x400000E3);
+    pub const RCTRL: Keycode = Keycode(0x400000E4);
+    pub const RSHIFT: Keycode = Keycode(0x400000E5);
+    pub const RALT: Keycode = Keycode(0x400000E6);
+    pub const RGUI: Keycode = Keycode(0x400000E7);
+    pub const MODE: Keycode = Keycode(0x40000101);
+    pub const AUDIONEXT: Keycode = Keycode(0x40000102);
+    pub const AUDIOPREV: Keycode = Keycode(0x40000103);
+    pub const AUDIOSTOP: Keycode = Keycode(0x40000104);
+    pub const AUDIOPLAY: Keycode = Keycode(0x40000105);
+    pub const AUDIOMUTE: Keycode = Keycode(0x40000106);
+    pub const MEDIASELECT: Keycode = Keycode(0x40000107);
+    pub const WWW: Keycode = Keycode(0x40000108);
+    pub const MAIL: Keycode = Keycode(0x40000109);
+    pub const CALCULATOR: Keycode = Keycode(0x4000010A);
+    pub const COMPUTER: Keycode = Keycode(0x4000010B);
+    pub const AC_SEARCH: Keycode = Keycode(0x4000010C);
+    pub const AC_HOME: Keycode = Keycode(0x4000010D);
+    pub const AC_BACK: Keycode = Keycode(0x4000010E);
+    pub const AC_FORWARD: Keycode = Keycode(0x4000010F);
+    pub const AC_STOP: Keycode = Keycode(0x40000110);
+    pub const AC_REFRESH: Keycode = Keycode(0x40000111);
+    pub const AC_BOOKMARKS: Keycode = Keycode(0x40000112);
+    pub const BRIGHTNESSDOWN: Keycode = Keycode(0x40000113);
+    pub const BRIGHTNESSUP: Keycode = Keycode(0x40000114);
+    pub const DISPLAYSWITCH: Keycode = Keycode(0x40000115);
+    pub const KBDILLUMTOGGLE: Keycode = Keycode(0x40000116);
+    pub const KBDILLUMDOWN: Keycode = Keycode(0x40000117);
+    pub const KBDILLUMUP: Keycode = Keycode(0x40000118);
+    pub const EJECT: Keycode = Keycode(0x40000119);
+    pub const SLEEP: Keycode = Keycode(0x4000011A);
+    pub const Backspace: Keycode = Keycode::BACKSPACE;
+    pub const Tab: Keycode = Keycode::TAB;
+    pub const Return: Keycode = Keycode::RETURN;
+    pub const Escape: Keycode = Keycode::ESCAPE;
+    pub const Space: Keycode = Keycode::SPACE;
+    pub const Exclaim: Keycode = Keycode::EXCLAIM;
+    pub const Quotedbl: Keycode = Keycode::QUOTEDBL;
+    pub const Hash: Keycode = Keycode::HASH;
+    pub const Dollar: Keycode = Keycode::DOLLAR;
+    pub const Percent: Keycode = Keycode::PERCENT;
+    pub const Ampersand: Keycode = Keycode::AMPERSAND;
+    pub const Quote: Keycode = Keycode::QUOTE;
+    pub const LeftParen: Keycode = Keycode::LEFTPAREN;
+    pub const RightParen: Keycode = Keycode::RIGHTPAREN;
+    pub const Asterisk: Keycode = Keycode::ASTERISK;
+    pub const Plus: Keycode = Keycode::PLUS;
+    pub const Comma: Keycode = Keycode::COMMA;
+    pub const Minus: Keycode = Keycode::MINUS;
+    pub const Period: Keycode = Keycode::PERIOD;
+    pub const Slash: Keycode = Keycode::SLASH;
+    pub const Num0: Keycode = Keycode::NUM_0;
+    pub const Num1: Keycode = Keycode::NUM_1;
+    pub const Num2: Keycode = Keycode::NUM_2;
+    pub const Num3: Keycode = Keycode::NUM_3;
+    pub const Num4: Keycode = Keycode::NUM_4;
+    pub const Num5: Keycode = Keycode::NUM_5;
+    pub const Num6: Keycode = Keycode::NUM_6;
+    pub const Num7: Keycode = Keycode::NUM_7;
+    pub const Num8: Keycode = Keycode::NUM_8;
+    pub const Num9: Keycode = Keycode::NUM_9;
+    pub const Colon: Keycode = Keycode::COLON;
+    pub const Semicolon: Keycode = Keycode::SEMICOLON;
+    pub const Less: Keycode = Keycode::LESS;
+    pub const Equals: Keycode = Keycode::EQUALS;
+    pub const Greater: Keycode = Keycode::GREATER;
+    pub const Question: Keycode = Keycode::QUESTION;
+    pub const At: Keycode = Keycode::AT;
+    pub const LeftBracket: Keycode = Keycode::LEFTBRACKET;
+    pub const Backslash: Keycode = Keycode::BACKSLASH;
+    pub const RightBracket: Keycode = Keycode::RIGHTBRACKET;
+    pub const Caret: Keycode = Keycode::CARET;
+    pub const Underscore: Keycode = Keycode::UNDERSCORE;
+    pub const Backquote: Keycode = Keycode::BACKQUOTE;
+    pub const Delete: Keycode = Keycode::DELETE;
+    pub const CapsLock: Keycode = Keycode::CAPSLOCK;
+    pub const PrintScreen: Keycode = Keycode::PRINTSCREEN;
+    pub const ScrollLock: Keycode = Keycode::SCROLLLOCK;
+    pub const Pause: Keycode = Keycode::PAUSE;
+    pub const Insert: Keycode = Keycode::INSERT;
+    pub const Home: Keycode = Keycode::HOME;
+    pub const PageUp: Keycode = Keycode::PAGEUP;
+    pub const End: Keycode = Keycode::END;
+    pub const PageDown: Keycode = Keycode::PAGEDOWN;
+    pub const Right: Keycode = Keycode::RIGHT;
+    pub const Left: Keycode = Keycode::LEFT;
+    pub const Down: Keycode = Keycode::DOWN;
+    pub const Up: Keycode = Keycode::UP;
+    pub const NumLockClear: Keycode = Keycode::NUMLOCKCLEAR;
+    pub const KpDivide: Keycode = Keycode::KP_DIVIDE;
+    pub const KpMultiply: Keycode = Keycode::KP_MULTIPLY;
+    pub const KpMinus: Keycode = Keycode::KP_MINUS;
+    pub const KpPlus: Keycode = Keycode::KP_PLUS;
+    pub const KpEnter: Keycode = Keycode::KP_ENTER;
+    pub const Kp1: Keycode = Keycode::KP_1;
+    pub const Kp2: Keycode = Keycode::KP_2;
+    pub const Kp3: Keycode = Keycode::KP_3;
+    pub const Kp4: Keycode = Keycode::KP_4;
+    pub const Kp5: Keycode = Keycode::KP_5;
+    pub const Kp6: Keycode = Keycode::KP_6;
+    pub const Kp7: Keycode = Keycode::KP_7;
+    pub const Kp8: Keycode = Keycode::KP_8;
+    pub const Kp9: Keycode = Keycode::KP_9;
+    pub const Kp0: Keycode = Keycode::KP_0;
+    pub const KpPeriod: Keycode = Keycode::KP_PERIOD;
+    pub const Application: Keycode = Keycode::APPLICATION;
+    pub const Power: Keycode = Keycode::POWER;
+    pub const KpEquals: Keycode = Keycode::KP_EQUALS;
+    pub const Execute: Keycode = Keycode::EXECUTE;
+    pub const Help: Keycode = Keycode::HELP;
+    pub const Menu: Keycode = Keycode::MENU;
+    pub const Select: Keycode = Keycode::SELECT;
+    pub const Stop: Keycode = Keycode::STOP;
+    pub const Again: Keycode = Keycode::AGAIN;
+    pub const Undo: Keycode = Keycode::UNDO;
+    pub const Cut: Keycode = Keycode::CUT;
+    pub const Copy: Keycode = Keycode::COPY;
+    pub const Paste: Keycode = Keycode::PASTE;
+    pub const Find: Keycode = Keycode::FIND;
+    pub const Mute: Keycode = Keycode::MUTE;
+    pub const VolumeUp: Keycode = Keycode::VOLUMEUP;
+    pub const VolumeDown: Keycode = Keycode::VOLUMEDOWN;
+    pub const KpComma: Keycode = Keycode::KP_COMMA;
+    pub const KpEqualsAS400: Keycode = Keycode::KP_EQUALSAS400;
+    pub const AltErase: Keycode = Keycode::ALTERASE;
+    pub const Sysreq: Keycode = Keycode::SYSREQ;
+    pub const Cancel: Keycode = Keycode::CANCEL;
+    pub const Clear: Keycode = Keycode::CLEAR;
+    pub const Prior: Keycode = Keycode::PRIOR;
+    pub const Return2: Keycode = Keycode::RETURN2;
+    pub const Separator: Keycode = Keycode::SEPARATOR;
+    pub const Out: Keycode = Keycode::OUT;
+    pub const Oper: Keycode = Keycode::OPER;
+    pub const ClearAgain: Keycode = Keycode::CLEARAGAIN;
+    pub const CrSel: Keycode = Keycode::CRSEL;
+    pub const ExSel: Keycode = Keycode::EXSEL;
+    pub const Kp00: Keycode = Keycode::KP_00;
+    pub const Kp000: Keycode = Keycode::KP_000;
+    pub const ThousandsSeparator: Keycode = Keycode::THOUSANDSSEPARATOR;
+    pub const DecimalSeparator: Keycode = Keycode::DECIMALSEPARATOR;
+    pub const CurrencyUnit: Keycode = Keycode::CURRENCYUNIT;
+    pub const CurrencySubUnit: Keycode = Keycode::CURRENCYSUBUNIT;
+    pub const KpLeftParen: Keycode = Keycode::KP_LEFTPAREN;
+    pub const KpRightParen: Keycode = Keycode::KP_RIGHTPAREN;
+    pub const KpLeftBrace: Keycode = Keycode::KP_LEFTBRACE;
+    pub const KpRightBrace: Keycode = Keycode::KP_RIGHTBRACE;
+    pub const KpTab: Keycode = Keycode::KP_TAB;
+    pub const KpBackspace: Keycode = Keycode::KP_BACKSPACE;
+    pub const KpA: Keycode = Keycode::KP_A;
+    pub const KpB: Keycode = Keycode::KP_B;
+    pub const KpC: Keycode = Keycode::KP_C;
+    pub const KpD: Keycode = Keycode::KP_D;
+    pub const KpE: Keycode = Keycode::KP_E;
+    pub const KpF: Keycode = Keycode::KP_F;
+    pub const KpXor: Keycode = Keycode::KP_XOR;
+    pub const KpPower: Keycode = Keycode::KP_POWER;
+    pub const KpPercent: Keycode = Keycode::KP_PERCENT;
+    pub const KpLess: Keycode = Keycode::KP_LESS;
+    pub const KpGreater: Keycode = Keycode::KP_GREATER;
+    pub const KpAmpersand: Keycode = Keycode::KP_AMPERSAND;
+    pub const KpDblAmpersand: Keycode = Keycode::KP_DBLAMPERSAND;
+    pub const KpVerticalBar: Keycode = Keycode::KP_VERTICALBAR;
+    pub const KpDblVerticalBar: Keycode = Keycode::KP_DBLVERTICALBAR;
+    pub const KpColon: Keycode = Keycode::KP_COLON;
+    pub const KpHash: Keycode = Keycode::KP_HASH;
+    pub const KpSpace: Keycode = Keycode::KP_SPACE;
+    pub const KpAt: Keycode = Keycode::KP_AT;
+    pub const KpExclam: Keycode = Keycode::KP_EXCLAM;
+    pub const KpMemStore: Keycode = Keycode::KP_MEMSTORE;
+    pub const KpMemRecall: Keycode = Keycode::KP_MEMRECALL;
+    pub const KpMemClear: Keycode = Keycode::KP_MEMCLEAR;
+    pub const KpMemAdd: Keycode = Keycode::KP_MEMADD;
+    pub const KpMemSubtract: Keycode = Keycode::KP_MEMSUBTRACT;
+    pub const KpMemMultiply: Keycode = Keycode::KP_MEMMULTIPLY;
+    pub const KpMemDivide: Keycode = Keycode::KP_MEMDIVIDE;
+    pub const KpPlusMinus: Keycode = Keycode::KP_PLUSMINUS;
+    pub const KpClear: Keycode = Keycode::KP_CLEAR;
+    pub const KpClearEntry: Keycode = Keycode::KP_CLEARENTRY;
+    pub const KpBinary: Keycode = Keycode::KP_BINARY;
+    pub const KpOctal: Keycode = Keycode::KP_OCTAL;
+    pub const KpDecimal: Keycode = Keycode::KP_DECIMAL;
+    pub const KpHexadecimal: Keycode = Keycode::KP_HEXADECIMAL;
+    pub const LCtrl: Keycode = Keycode::LCTRL;
+    pub const LShift: Keycode = Keycode::LSHIFT;
+    pub const LAlt: Keycode = Keycode::LALT;
+    pub const LGui: Keycode = Keycode::LGUI;
+    pub const RCtrl: Keycode = Keycode::RCTRL;
+    pub const RShift: Keycode = Keycode::RSHIFT;
+    pub const RAlt: Keycode = Keycode::RALT;
+    pub const RGui: Keycode = Keycode::RGUI;
+    pub const Mode: Keycode = Keycode::MODE;
+    pub const AudioNext: Keycode = Keycode::AUDIONEXT;
+    pub const AudioPrev: Keycode = Keycode::AUDIOPREV;
+    pub const AudioStop: Keycode = Keycode::AUDIOSTOP;
+    pub const AudioPlay: Keycode = Keycode::AUDIOPLAY;
+    pub const AudioMute: Keycode = Keycode::AUDIOMUTE;
+    pub const MediaSelect: Keycode = Keycode::MEDIASELECT;
+    pub const Www: Keycode = Keycode::WWW;
+    pub const Mail: Keycode = Keycode::MAIL;
+    pub const Calculator: Keycode = Keycode::CALCULATOR;
+    pub const Computer: Keycode = Keycode::COMPUTER;
+    pub const AcSearch: Keycode = Keycode::AC_SEARCH;
+    pub const AcHome: Keycode = Keycode::AC_HOME;
+    pub const AcBack: Keycode = Keycode::AC_BACK;
+    pub const AcForward: Keycode = Keycode::AC_FORWARD;
+    pub const AcStop: Keycode = Keycode::AC_STOP;
+    pub const AcRefresh: Keycode = Keycode::AC_REFRESH;
+    pub const AcBookmarks: Keycode = Keycode::AC_BOOKMARKS;
+    pub const BrightnessDown: Keycode = Keycode::BRIGHTNESSDOWN;
+    pub const BrightnessUp: Keycode = Keycode::BRIGHTNESSUP;
+    pub const DisplaySwitch: Keycode = Keycode::DISPLAYSWITCH;
+    pub const KbdIllumToggle: Keycode = Keycode::KBDILLUMTOGGLE;
+    pub const KbdIllumDown: Keycode = Keycode::KBDILLUMDOWN;
+    pub const KbdIllumUp: Keycode = Keycode::KBDILLUMUP;
+    pub const Eject: Keycode = Keycode::EJECT;
+    pub const Sleep: Keycode = Keycode::SLEEP;
+}
+
+impl Keycode {
+    // The signature matches `sdl2::keyboard::Keycode::into_i32`.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn into_i32(&self) -> i32 {
+        self.0
+    }
+
+    pub fn from_i32(n: i32) -> Option<Keycode> {
+        if n != 0 {
+            Some(Keycode(n))
+        } else {
+            None
+        }
+    }
+}
+
+impl ops::Deref for Keycode {
+    type Target = i32;
+
+    fn deref(&self) -> &i32 {
+        &self.0
+    }
+}
+
+impl From<Keycode> for i32 {
+    fn from(val: Keycode) -> Self {
+        val.into_i32()
+    }
+}
+
+/// Keyboard modifier state.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Mod(u16);
+
+impl Mod {
+    pub const NOMOD: Mod = Mod(0x0000);
+    pub const LSHIFTMOD: Mod = Mod(0x0001);
+    pub const RSHIFTMOD: Mod = Mod(0x0002);
+    pub const LCTRLMOD: Mod = Mod(0x0040);
+    pub const RCTRLMOD: Mod = Mod(0x0080);
+    pub const LALTMOD: Mod = Mod(0x0100);
+    pub const RALTMOD: Mod = Mod(0x0200);
+    pub const LGUIMOD: Mod = Mod(0x0400);
+    pub const RGUIMOD: Mod = Mod(0x0800);
+    pub const NUMMOD: Mod = Mod(0x1000);
+    pub const CAPSMOD: Mod = Mod(0x2000);
+    pub const MODEMOD: Mod = Mod(0x4000);
+    pub const RESERVEDMOD: Mod = Mod(0x8000);
+
+    pub const fn empty() -> Mod {
+        Mod(0)
+    }
+
+    pub const fn all() -> Mod {
+        Mod(0xFFC3)
+    }
+
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub const fn from_bits(bits: u16) -> Option<Mod> {
+        if bits & !Self::all().0 == 0 {
+            Some(Mod(bits))
+        } else {
+            None
+        }
+    }
+
+    pub const fn from_bits_truncate(bits: u16) -> Mod {
+        Mod(bits & Self::all().0)
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn contains(&self, other: Mod) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn intersects(&self, other: Mod) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn insert(&mut self, other: Mod) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Mod) {
+        self.0 &= !other.0;
+    }
+}
+
+impl ops::BitOr for Mod {
+    type Output = Mod;
+
+    fn bitor(self, rhs: Mod) -> Mod {
+        Mod(self.0 | rhs.0)
+    }
+}
+
+impl ops::BitOrAssign for Mod {
+    fn bitor_assign(&mut self, rhs: Mod) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl ops::BitAnd for Mod {
+    type Output = Mod;
+
+    fn bitand(self, rhs: Mod) -> Mod {
+        Mod(self.0 & rhs.0)
+    }
+}
+
+impl ops::BitAndAssign for Mod {
+    fn bitand_assign(&mut self, rhs: Mod) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl ops::Not for Mod {
+    type Output = Mod;
+
+    fn not(self) -> Mod {
+        Mod::from_bits_truncate(!self.0)
+    }
+}
+
+impl fmt::Display for Mod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04x}", self.0)
+    }
+}
+
+/// Mouse wheel direction.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum MouseWheelDirection {
+    Normal,
+    Flipped,
+    Unknown(u32),
+}
+
+impl MouseWheelDirection {
+    pub fn from_ll(direction: u32) -> MouseWheelDirection {
+        match direction {
+            0 => MouseWheelDirection::Normal,
+            1 => MouseWheelDirection::Flipped,
+            _ => MouseWheelDirection::Unknown(direction),
+        }
+    }
+
+    pub fn to_ll(self) -> u32 {
+        match self {
+            MouseWheelDirection::Normal => 0,
+            MouseWheelDirection::Flipped => 1,
+            MouseWheelDirection::Unknown(direction) => direction,
+        }
+    }
+}
+
+/// Mouse button.
+#[repr(u8)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum MouseButton {
+    Unknown = 0,
+    Left = 1,
+    Middle = 2,
+    Right = 3,
+    X1 = 4,
+    X2 = 5,
+}
+
+impl MouseButton {
+    pub fn from_ll(button: u8) -> MouseButton {
+        match button {
+            1 => MouseButton::Left,
+            2 => MouseButton::Middle,
+            3 => MouseButton::Right,
+            4 => MouseButton::X1,
+            5 => MouseButton::X2,
+            _ => MouseButton::Unknown,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "with-sdl"))]
+mod tests {
+    use super::*;
+
+    macro_rules! assert_keycodes {
+        [$($name:ident),* $(,)?] => {
+            $(
+                assert_eq!(
+                    Keycode::$name.into_i32(),
+                    sdl2::keyboard::Keycode::$name.into_i32(),
+                    stringify!($name)
+                );
+            )*
+        };
+    }
+
+    macro_rules! assert_mods {
+        [$($name:ident),* $(,)?] => {
+            $(
+                assert_eq!(
+                    Mod::$name.bits(),
+                    sdl2::keyboard::Mod::$name.bits(),
+                    stringify!($name)
+                );
+            )*
+        };
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn keycodes_match_sdl2() {
+        assert_keycodes![
+            BACKSPACE, TAB, RETURN, ESCAPE, SPACE, EXCLAIM, QUOTEDBL, HASH, DOLLAR, PERCENT,
+            AMPERSAND, QUOTE, LEFTPAREN, RIGHTPAREN, ASTERISK, PLUS, COMMA, MINUS, PERIOD, SLASH,
+            NUM_0, NUM_1, NUM_2, NUM_3, NUM_4, NUM_5, NUM_6, NUM_7, NUM_8, NUM_9, COLON, SEMICOLON,
+            LESS, EQUALS, GREATER, QUESTION, AT, LEFTBRACKET, BACKSLASH, RIGHTBRACKET, CARET,
+            UNDERSCORE, BACKQUOTE, A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V,
+            W, X, Y, Z, DELETE, CAPSLOCK, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+            PRINTSCREEN, SCROLLLOCK, PAUSE, INSERT, HOME, PAGEUP, END, PAGEDOWN, RIGHT, LEFT, DOWN,
+            UP, NUMLOCKCLEAR, KP_DIVIDE, KP_MULTIPLY, KP_MINUS, KP_PLUS, KP_ENTER, KP_1, KP_2, KP_3,
+            KP_4, KP_5, KP_6, KP_7, KP_8, KP_9, KP_0, KP_PERIOD, APPLICATION, POWER, KP_EQUALS, F13,
+            F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24, EXECUTE, HELP, MENU, SELECT,
+            STOP, AGAIN, UNDO, CUT, COPY, PASTE, FIND, MUTE, VOLUMEUP, VOLUMEDOWN, KP_COMMA,
+            KP_EQUALSAS400, ALTERASE, SYSREQ, CANCEL, CLEAR, PRIOR, RETURN2, SEPARATOR, OUT, OPER,
+            CLEARAGAIN, CRSEL, EXSEL, KP_00, KP_000, THOUSANDSSEPARATOR, DECIMALSEPARATOR,
+            CURRENCYUNIT, CURRENCYSUBUNIT, KP_LEFTPAREN, KP_RIGHTPAREN, KP_LEFTBRACE, KP_RIGHTBRACE,
+            KP_TAB, KP_BACKSPACE, KP_A, KP_B, KP_C, KP_D, KP_E, KP_F, KP_XOR, KP_POWER, KP_PERCENT,
+            KP_LESS, KP_GREATER, KP_AMPERSAND, KP_DBLAMPERSAND, KP_VERTICALBAR, KP_DBLVERTICALBAR,
+            KP_COLON, KP_HASH, KP_SPACE, KP_AT, KP_EXCLAM, KP_MEMSTORE, KP_MEMRECALL, KP_MEMCLEAR,
+            KP_MEMADD, KP_MEMSUBTRACT, KP_MEMMULTIPLY, KP_MEMDIVIDE, KP_PLUSMINUS, KP_CLEAR,
+            KP_CLEARENTRY, KP_BINARY, KP_OCTAL, KP_DECIMAL, KP_HEXADECIMAL, LCTRL, LSHIFT, LALT,
+            LGUI, RCTRL, RSHIFT, RALT, RGUI, MODE, AUDIONEXT, AUDIOPREV, AUDIOSTOP, AUDIOPLAY,
+            AUDIOMUTE, MEDIASELECT, WWW, MAIL, CALCULATOR, COMPUTER, AC_SEARCH, AC_HOME, AC_BACK,
+            AC_FORWARD, AC_STOP, AC_REFRESH, AC_BOOKMARKS, BRIGHTNESSDOWN, BRIGHTNESSUP,
+            DISPLAYSWITCH, KBDILLUMTOGGLE, KBDILLUMDOWN, KBDILLUMUP, EJECT, SLEEP, Backspace, Tab,
+            Return, Escape, Space, Exclaim, Quotedbl, Hash, Dollar, Percent, Ampersand, Quote,
+            LeftParen, RightParen, Asterisk, Plus, Comma, Minus, Period, Slash, Num0, Num1, Num2,
+            Num3, Num4, Num5, Num6, Num7, Num8, Num9, Colon, Semicolon, Less, Equals, Greater,
+            Question, At, LeftBracket, Backslash, RightBracket, Caret, Underscore, Backquote,
+            Delete, CapsLock, PrintScreen, ScrollLock, Pause, Insert, Home, PageUp, End, PageDown,
+            Right, Left, Down, Up, NumLockClear, KpDivide, KpMultiply, KpMinus, KpPlus, KpEnter,
+            Kp1, Kp2, Kp3, Kp4, Kp5, Kp6, Kp7, Kp8, Kp9, Kp0, KpPeriod, Application, Power,
+            KpEquals, Execute, Help, Menu, Select, Stop, Again, Undo, Cut, Copy, Paste, Find, Mute,
+            VolumeUp, VolumeDown, KpComma, KpEqualsAS400, AltErase, Sysreq, Cancel, Clear, Prior,
+            Return2, Separator, Out, Oper, ClearAgain, CrSel, ExSel, Kp00, Kp000,
+            ThousandsSeparator, DecimalSeparator, CurrencyUnit, CurrencySubUnit, KpLeftParen,
+            KpRightParen, KpLeftBrace, KpRightBrace, KpTab, KpBackspace, KpA, KpB, KpC, KpD, KpE,
+            KpF, KpXor, KpPower, KpPercent, KpLess, KpGreater, KpAmpersand, KpDblAmpersand,
+            KpVerticalBar, KpDblVerticalBar, KpColon, KpHash, KpSpace, KpAt, KpExclam, KpMemStore,
+            KpMemRecall, KpMemClear, KpMemAdd, KpMemSubtract, KpMemMultiply, KpMemDivide,
+            KpPlusMinus, KpClear, KpClearEntry, KpBinary, KpOctal, KpDecimal, KpHexadecimal, LCtrl,
+            LShift, LAlt, LGui, RCtrl, RShift, RAlt, RGui, Mode, AudioNext, AudioPrev, AudioStop,
+            AudioPlay, AudioMute, MediaSelect, Www, Mail, Calculator, Computer, AcSearch, AcHome,
+            AcBack, AcForward, AcStop, AcRefresh, AcBookmarks, BrightnessDown, BrightnessUp,
+            DisplaySwitch, KbdIllumToggle, KbdIllumDown, KbdIllumUp, Eject, Sleep,
+        ];
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn mods_match_sdl2() {
+        assert_mods![
+            NOMOD, LSHIFTMOD, RSHIFTMOD, LCTRLMOD, RCTRLMOD, LALTMOD, RALTMOD, LGUIMOD, RGUIMOD,
+            NUMMOD, CAPSMOD, MODEMOD, RESERVEDMOD,
+        ];
+        assert_eq!(Mod::all().bits(), sdl2::keyboard::Mod::all().bits());
+    }
+
+    #[test]
+    fn mouse_match_sdl2() {
+        for button in 0..=6 {
+            assert_eq!(
+                MouseButton::from_ll(button) as u8,
+                sdl2::mouse::MouseButton::from_ll(button) as u8
+            );
+        }
+
+        for direction in 0..=2 {
+            assert_eq!(
+                MouseWheelDirection::from_ll(direction).to_ll(),
+                sdl2::mouse::MouseWheelDirection::from_ll(direction).to_ll()
+            );
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+use crate::{
+    check::{self, CheckReport, CheckStatus, Tolerance},
+    display::SimulatorDisplay,
+    output_settings::OutputSettings,
+};
+
+/// Snapshot assertion.
+///
+/// A snapshot assertion compares the content of a [`SimulatorDisplay`] with a reference PNG
+/// file, the snapshot. Snapshots are stored in the `tests/snapshots` directory of the crate by
+/// default and are named `<name>.png`.
+///
+/// If the display content doesn't match the snapshot, the actual display content is written to
+/// `<name>.actual.png` and an image which highlights the differences is written to
+/// `<name>.diff.png`. Both files are removed again once the snapshot matches.
+///
+/// New snapshots are created, and existing snapshots are updated, if the `EG_SIMULATOR_BLESS`
+/// environment variable is set:
+///
+/// ```bash
+/// EG_SIMULATOR_BLESS=1 cargo test
+/// ```
+///
+/// In most cases it is more convenient to use the [`assert_display_snapshot`] macro or the
+/// [`SimulatorDisplay::assert_matches_snapshot`] method instead of using this type directly.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+/// use embedded_graphics_simulator::{
+///     BinaryColorTheme, OutputSettingsBuilder, SimulatorDisplay, Snapshot, Tolerance,
+/// };
+///
+/// let display = SimulatorDisplay::<BinaryColor>::new(Size::new(128, 64));
+///
+/// // draw something to the display
+///
+/// let output_settings = OutputSettingsBuilder::new()
+///     .theme(BinaryColorTheme::OledBlue)
+///     .build();
+///
+/// Snapshot::new("oled_blue")
+///     .directory("tests/themed_snapshots")
+///     .output_settings(&output_settings)
+///     .tolerance(Tolerance::exact().max_differing_pixels(4))
+///     .assert_matches(&display);
+/// ```
+///
+/// [`assert_display_snapshot`]: crate::assert_display_snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    name: String,
+    directory: PathBuf,
+    output_settings: OutputSettings,
+    tolerance: Tolerance,
+    bless: bool,
+}
+
+impl Snapshot {
+    /// Creates a new snapshot assertion.
+    ///
+    /// The snapshot is stored in the `tests/snapshots` directory relative to the
+    /// `CARGO_MANIFEST_DIR` environment variable, which is set by cargo while running tests. If
+    /// the variable isn't set the path is relative to the current working directory.
+    pub fn new(name: &str) -> Self {
+        let manifest_dir = env::var_os("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_default();
+
+        Self {
+            name: String::from(name),
+            directory: manifest_dir.join("tests").join("snapshots"),
+            output_settings: OutputSettings::default(),
+            tolerance: Tolerance::exact(),
+            bless: env::var_os("EG_SIMULATOR_BLESS").is_some_and(|value| value != "0"),
+        }
+    }
+
+    /// Sets the directory the snapshot is stored in.
+    pub fn directory<P: AsRef<Path>>(mut self, directory: P) -> Self {
+        self.directory = directory.as_ref().to_path_buf();
+
+        self
+    }
+
+    /// Sets the output settings.
+    ///
+    /// The output settings are applied to the display before it is compared to the snapshot,
+    /// which can be used to assert themed output.
+    pub fn output_settings(mut self, output_settings: &OutputSettings) -> Self {
+        self.output_settings = *output_settings;
+
+        self
+    }
+
+    /// Sets the tolerance.
+    pub fn tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.tolerance = tolerance;
+
+        self
+    }
+
+    /// Enables or disables bless mode.
+    ///
+    /// Bless mode is enabled by default if the `EG_SIMULATOR_BLESS` environment variable is set.
+    pub fn bless(mut self, bless: bool) -> Self {
+        self.bless = bless;
+
+        self
+    }
+
+    /// Returns the path of the snapshot file.
+    pub fn path(&self) -> PathBuf {
+        self.sibling_path("png")
+    }
+
+    fn sibling_path(&self, extension: &str) -> PathBuf {
+        self.directory.join(format!("{}.{}", self.name, extension))
+    }
+
+    /// Asserts that the display content matches the snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the snapshot doesn't exist or doesn't match the display content. If bless mode
+    /// is enabled the snapshot is updated instead.
+    pub fn assert_matches<C>(&self, display: &SimulatorDisplay<C>)
+    where
+        C: PixelColor + Into<Rgb888>,
+    {
+        let report = self.check(display);
+        if report.status() != CheckStatus::Passed {
+            panic!("{}", report.message());
+        }
+    }
+
+    /// Compares the display content with the snapshot without panicking.
+    ///
+    /// The returned report can be used to collect the results of many snapshots in a
+    /// [`BatchReport`](crate::BatchReport). If bless mode is enabled the snapshot is updated
+    /// before it is compared.
+    pub fn check<C>(&self, display: &SimulatorDisplay<C>) -> CheckReport
+    where
+        C: PixelColor + Into<Rgb888>,
+    {
+        let path = self.path();
+        let actual_path = self.sibling_path("actual.png");
+        let diff_path = self.sibling_path("diff.png");
+
+        if let Some(parent) = path.parent() {
+            // Errors are reported when the files are written.
+            let _ = fs::create_dir_all(parent);
+        }
+
+        let output = display.to_rgb_output_image(&self.output_settings);
+
+        let mut bless_error = None;
+        if self.bless {
+            if let Err(e) = output.save_png(&path) {
+                bless_error = Some(format!("failed to write snapshot {}: {e}", path.display()));
+            }
+        }
+
+        let mut report = check::check_reference(
+            SimulatorDisplay::load_png(&path),
+            &output.to_rgb_display(),
+            &path,
+            &actual_path,
+            &diff_path,
+            &self.tolerance,
+        );
+
+        let mut message = match report.status() {
+            CheckStatus::Passed => {
+                remove_stale_file(&actual_path);
+                remove_stale_file(&diff_path);
+
+                return report;
+            }
+            CheckStatus::ReferenceMissing => {
+                format!(
+                    "snapshot \"{}\" is missing: {}",
+                    self.name,
+                    report.message()
+                )
+            }
+            CheckStatus::Mismatch | CheckStatus::SizeMismatch => {
+                format!(
+                    "snapshot \"{}\" doesn't match: {}\n  expected: {}",
+                    self.name,
+                    report.message(),
+                    path.display()
+                )
+            }
+        };
+
+        if let Some(path) = report.actual_path() {
+            message += &format!("\n  actual: {}", path.display());
+        }
+        if let Some(path) = report.diff_path() {
+            message += &format!("\n  diff: {}", path.display());
+        }
+        if let Some(error) = bless_error {
+            message += &format!("\n{error}");
+        }
+
+        let action = if report.status() == CheckStatus::ReferenceMissing {
+            "create"
+        } else {
+            "update"
+        };
+        message += &format!("\nRun the test with EG_SIMULATOR_BLESS=1 to {action} the snapshot.");
+
+        report.message = message;
+
+        report
+    }
+}
+
+fn remove_stale_file(path: &Path) {
+    // The file usually doesn't exist, which isn't an error.
+    let _ = fs::remove_file(path);
+}
+
+impl<C> SimulatorDisplay<C>
+where
+    C: PixelColor + Into<Rgb888>,
+{
+    /// Asserts that the display content matches a snapshot.
+    ///
+    /// This is a shorthand for
+    /// `Snapshot::new(name).output_settings(output_settings).assert_matches(self)`. See
+    /// [`Snapshot`] for more details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the snapshot doesn't exist or doesn't match the display content.
+    pub fn assert_matches_snapshot(&self, name: &str, output_settings: &OutputSettings) {
+        Snapshot::new(name)
+            .output_settings(output_settings)
+            .assert_matches(self);
+    }
+}
+
+/// Asserts that the content of a display matches a snapshot.
+///
+/// The snapshot is stored in the `tests/snapshots` directory of the crate that invokes the macro.
+/// An optional third argument can be used to pass a reference to the [`OutputSettings`] that
+/// should be used. See [`Snapshot`] for more details.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+/// use embedded_graphics_simulator::{
+///     assert_display_snapshot, BinaryColorTheme, OutputSettingsBuilder, SimulatorDisplay,
+/// };
+///
+/// let display = SimulatorDisplay::<BinaryColor>::new(Size::new(128, 64));
+///
+/// // draw something to the display
+///
+/// assert_display_snapshot!(display, "empty_display");
+///
+/// let output_settings = OutputSettingsBuilder::new()
+///     .theme(BinaryColorTheme::LcdGreen)
+///     .build();
+/// assert_display_snapshot!(display, "empty_display_lcd_green", &output_settings);
+/// ```
+///
+/// [`OutputSettings`]: crate::OutputSettings
+/// [`Snapshot`]: crate::Snapshot
+#[macro_export]
+macro_rules! assert_display_snapshot {
+    ($display:expr, $name:expr $(,)?) => {
+        $crate::assert_display_snapshot!($display, $name, &$crate::OutputSettings::default())
+    };
+    ($display:expr, $name:expr, $output_settings:expr $(,)?) => {
+        $crate::Snapshot::new($name)
+            .directory(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots"))
+            .output_settings($output_settings)
+            .assert_matches(&$display)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    use crate::{test_utils::TempDir, BinaryColorTheme, OutputSettingsBuilder};
+
+    fn display(on: &[Point]) -> SimulatorDisplay<BinaryColor> {
+        let mut display = SimulatorDisplay::new(Size::new(6, 4));
+        for p in on {
+            Pixel(*p, BinaryColor::On).draw(&mut display).unwrap();
+        }
+
+        display
+    }
+
+    #[test]
+    fn missing_snapshot() {
+        let dir = TempDir::new("snapshot-missing");
+        let snapshot = Snapshot::new("test").directory(dir.path()).bless(false);
+
+        let report = snapshot.check(&display(&[]));
+        assert_eq!(report.status(), CheckStatus::ReferenceMissing);
+        assert!(
+            report
+                .message()
+                .ends_with("EG_SIMULATOR_BLESS=1 to create the snapshot."),
+            "{}",
+            report.message()
+        );
+        assert!(dir.join("test.actual.png").exists());
+    }
+
+    #[test]
+    fn bless_and_compare() {
+        let dir = TempDir::new("snapshot-bless");
+        let output_settings = OutputSettingsBuilder::new()
+            .theme(BinaryColorTheme::LcdGreen)
+            .build();
+        let snapshot = Snapshot::new("themed")
+            .directory(dir.path())
+            .output_settings(&output_settings);
+
+        let expected = display(&[Point::new(1, 1)]);
+        let report = snapshot.clone().bless(true).check(&expected);
+        assert_eq!(report.status(), CheckStatus::Passed);
+        assert!(snapshot.path().exists());
+
+        let snapshot = snapshot.bless(false);
+        assert_eq!(snapshot.check(&expected).status(), CheckStatus::Passed);
+
+        let actual = display(&[Point::new(1, 1), Point::new(4, 2)]);
+        let report = snapshot.check(&actual);
+        assert_eq!(report.status(), CheckStatus::Mismatch);
+        assert!(
+            report
+                .message()
+                .contains("9 pixels differ in area (16, 8) 3x3"),
+            "{}",
+            report.message()
+        );
+        assert_eq!(
+            report.actual_path(),
+            Some(dir.join("themed.actual.png").as_path())
+        );
+        assert_eq!(
+            report.diff_path(),
+            Some(dir.join("themed.diff.png").as_path())
+        );
+        assert!(dir.join("themed.actual.png").exists());
+        assert!(dir.join("themed.diff.png").exists());
+
+        let report = snapshot
+            .clone()
+            .tolerance(Tolerance::exact().max_differing_pixels(9))
+            .check(&actual);
+        assert_eq!(report.status(), CheckStatus::Passed);
+        assert!(!dir.join("themed.actual.png").exists());
+        assert!(!dir.join("themed.diff.png").exists());
+    }
+
+    #[test]
+    fn size_mismatch() {
+        let dir = TempDir::new("snapshot-size");
+        let snapshot = Snapshot::new("size").directory(dir.path());
+
+        snapshot.clone().bless(true).check(&display(&[]));
+
+        let report = snapshot
+            .bless(false)
+            .check(&SimulatorDisplay::<BinaryColor>::new(Size::new(6, 5)));
+        assert_eq!(report.status(), CheckStatus::SizeMismatch);
+        assert!(
+            report.message().contains("(display: 6x5, PNG: 6x4)"),
+            "{}",
+            report.message()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "snapshot \"test\" is missing")]
+    fn assert_missing_snapshot() {
+        let dir = TempDir::new("snapshot-assert");
+
+        Snapshot::new("test")
+            .directory(dir.path())
+            .bless(false)
+            .assert_matches(&display(&[]));
+    }
+}
@@ -0,0 +1,326 @@
+use std::{any::type_name, fmt::Write as _, fs, io, path::Path};
+
+use embedded_graphics::{
+    pixelcolor::raw::{RawData, ToBytes},
+    prelude::*,
+};
+
+use crate::display::SimulatorDisplay;
+
+/// Source code language used by [`SourceExport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SourceLanguage {
+    /// Rust source with a `const` byte array and an `ImageRaw` definition.
+    #[default]
+    Rust,
+    /// C header with a `uint8_t` array and width and height defines.
+    C,
+}
+
+/// Exporter for display content as Rust or C source code.
+///
+/// The exported image data uses the same format as [`SimulatorDisplay::to_be_bytes`] or
+/// [`SimulatorDisplay::to_le_bytes`], which is the format expected by the `ImageRaw` type of
+/// embedded-graphics for the color type of the display. This makes it possible to draw an image
+/// in the simulator and embed it in firmware without further conversion.
+///
+/// Images can also be exported by using the `eg-simulator-export` binary, which converts PNG
+/// files to source code.
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+/// use embedded_graphics_simulator::{SimulatorDisplay, SourceExport, SourceLanguage};
+///
+/// let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(8, 2));
+/// Pixel(Point::new(0, 1), BinaryColor::On).draw(&mut display).unwrap();
+///
+/// let rust = SourceExport::new("icon").to_source(&display);
+/// assert!(rust.contains("pub const ICON_DATA: &[u8] = &[\n    0x00, 0x80,\n];"));
+///
+/// let c = SourceExport::new("icon")
+///     .language(SourceLanguage::C)
+///     .to_source(&display);
+/// assert!(c.contains("#define ICON_WIDTH 8"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceExport {
+    name: String,
+    language: SourceLanguage,
+    little_endian: bool,
+}
+
+impl SourceExport {
+    /// Creates a new source exporter.
+    ///
+    /// The name is used to derive the identifiers in the generated source. Characters that
+    /// aren't allowed in identifiers are replaced by underscores.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+            language: SourceLanguage::default(),
+            little_endian: false,
+        }
+    }
+
+    /// Sets the source code language.
+    ///
+    /// The default language is [`SourceLanguage::Rust`].
+    pub fn language(mut self, language: SourceLanguage) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Sets the byte order of colors with more than 8 bits per pixel.
+    ///
+    /// Big endian byte order is used by default. If little endian byte order is selected, the
+    /// exported Rust source uses `ImageRawLE` instead of `ImageRaw`.
+    pub fn little_endian(mut self, little_endian: bool) -> Self {
+        self.little_endian = little_endian;
+        self
+    }
+
+    /// Returns the identifier that is derived from the name.
+    fn identifier(&self) -> String {
+        let mut identifier = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+
+        if !identifier.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            identifier.insert(0, '_');
+        }
+
+        identifier
+    }
+
+    /// Exports the display content as source code.
+    pub fn to_source<C>(&self, display: &SimulatorDisplay<C>) -> String
+    where
+        C: PixelColor + ToBytes,
+        <C as ToBytes>::Bytes: AsRef<[u8]>,
+    {
+        let data = if self.little_endian {
+            display.to_le_bytes()
+        } else {
+            display.to_be_bytes()
+        };
+
+        let identifier = self.identifier();
+        let size = display.size();
+        let color = color_path::<C>();
+        let color_name = color.rsplit("::").next().unwrap_or_default();
+        let bits_per_pixel = C::Raw::BITS_PER_PIXEL;
+        let byte_order = if self.little_endian {
+            "little endian"
+        } else {
+            "big endian"
+        };
+
+        let mut source = String::new();
+        match self.language {
+            SourceLanguage::Rust => {
+                let image_raw = if self.little_endian {
+                    "ImageRawLE"
+                } else {
+                    "ImageRaw"
+                };
+
+                let _ = writeln!(
+                    source,
+                    "/// Raw data of the {}x{} `{color_name}` image `{identifier}`.",
+                    size.width, size.height
+                );
+                let _ = writeln!(source, "pub const {identifier}_DATA: &[u8] = &[");
+                write_bytes(&mut source, &data);
+                let _ = writeln!(source, "];");
+                let _ = writeln!(source);
+                let _ = writeln!(
+                    source,
+                    "/// {}x{} `{color_name}` image.",
+                    size.width, size.height
+                );
+                let _ = writeln!(
+                    source,
+                    "pub const {identifier}: embedded_graphics::image::{image_raw}<{color}> =\n    \
+                     embedded_graphics::image::{image_raw}::new({identifier}_DATA, {});",
+                    size.width
+                );
+            }
+            SourceLanguage::C => {
+                let _ = writeln!(source, "#ifndef {identifier}_H");
+                let _ = writeln!(source, "#define {identifier}_H");
+                let _ = writeln!(source);
+                let _ = writeln!(source, "#include <stdint.h>");
+                let _ = writeln!(source);
+                let _ = writeln!(
+                    source,
+                    "// {}x{} {color_name} image, {bits_per_pixel} bpp, {byte_order}.",
+                    size.width, size.height
+                );
+                if bits_per_pixel < 8 {
+                    let _ = writeln!(
+                        source,
+                        "// Pixels are packed MSB first and each row starts at a new byte."
+                    );
+                }
+                let _ = writeln!(source, "#define {identifier}_WIDTH {}", size.width);
+                let _ = writeln!(source, "#define {identifier}_HEIGHT {}", size.height);
+                let _ = writeln!(source);
+                let _ = writeln!(
+                    source,
+                    "static const uint8_t {}_data[{}] = {{",
+                    identifier.to_ascii_lowercase(),
+                    data.len()
+                );
+                write_bytes(&mut source, &data);
+                let _ = writeln!(source, "}};");
+                let _ = writeln!(source);
+                let _ = writeln!(source, "#endif // {identifier}_H");
+            }
+        }
+
+        source
+    }
+
+    /// Exports the display content as source code and saves it to a file.
+    pub fn save<C, P>(&self, display: &SimulatorDisplay<C>, path: P) -> io::Result<()>
+    where
+        C: PixelColor + ToBytes,
+        <C as ToBytes>::Bytes: AsRef<[u8]>,
+        P: AsRef<Path>,
+    {
+        fs::write(path, self.to_source(display))
+    }
+}
+
+impl<C> SimulatorDisplay<C>
+where
+    C: PixelColor + ToBytes,
+    <C as ToBytes>::Bytes: AsRef<[u8]>,
+{
+    /// Exports the display content as Rust source code.
+    ///
+    /// The source contains a `const` byte array with the big endian raw data and an `ImageRaw`
+    /// definition. See [`SourceExport`] for more options.
+    pub fn to_rust_source(&self, name: &str) -> String {
+        SourceExport::new(name).to_source(self)
+    }
+
+    /// Exports the display content as a C header.
+    ///
+    /// The header contains a `uint8_t` array with the big endian raw data and width and height
+    /// defines. See [`SourceExport`] for more options.
+    pub fn to_c_source(&self, name: &str) -> String {
+        SourceExport::new(name)
+            .language(SourceLanguage::C)
+            .to_source(self)
+    }
+}
+
+/// Returns the path that is used to refer to a color type in the generated source.
+fn color_path<C>() -> String {
+    let name = type_name::<C>();
+
+    // Colors from `embedded-graphics-core` are referred to by their public path.
+    match name.strip_prefix("embedded_graphics_core::pixelcolor::") {
+        Some(path) => format!(
+            "embedded_graphics::pixelcolor::{}",
+            path.rsplit("::").next().unwrap_or_default()
+        ),
+        None => String::from(name),
+    }
+}
+
+/// Writes bytes as a comma separated list of hex literals with 16 bytes per line.
+fn write_bytes(source: &mut String, bytes: &[u8]) {
+    for line in bytes.chunks(16) {
+        source.push_str("   ");
+        for byte in line {
+            let _ = write!(source, " 0x{byte:02X},");
+        }
+        source.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::pixelcolor::{BinaryColor, Rgb565};
+
+    fn display() -> SimulatorDisplay<Rgb565> {
+        let mut display = SimulatorDisplay::new(Size::new(9, 2));
+        Pixel(Point::new(8, 1), Rgb565::RED)
+            .draw(&mut display)
+            .unwrap();
+
+        display
+    }
+
+    #[test]
+    fn rust_source() {
+        let source = display().to_rust_source("my icon");
+
+        assert!(source.contains("pub const MY_ICON_DATA: &[u8] = &[\n"));
+        assert!(source.contains(
+            "    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, \
+             0x00, 0x00, 0x00,\n"
+        ));
+        assert!(source.contains("    0x00, 0x00, 0xF8, 0x00,\n];\n"));
+        assert!(source.contains(
+            "pub const MY_ICON: embedded_graphics::image::ImageRaw<\
+             embedded_graphics::pixelcolor::Rgb565> =\n    \
+             embedded_graphics::image::ImageRaw::new(MY_ICON_DATA, 9);\n"
+        ));
+    }
+
+    #[test]
+    fn rust_source_little_endian() {
+        let source = SourceExport::new("1st")
+            .little_endian(true)
+            .to_source(&display());
+
+        assert!(source.contains("    0x00, 0x00, 0x00, 0xF8,\n];\n"));
+        assert!(source.contains(
+            "pub const _1ST: embedded_graphics::image::ImageRawLE<\
+             embedded_graphics::pixelcolor::Rgb565>"
+        ));
+    }
+
+    #[test]
+    fn c_source() {
+        let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(9, 2));
+        Pixel(Point::new(8, 1), BinaryColor::On)
+            .draw(&mut display)
+            .unwrap();
+
+        let source = display.to_c_source("icon");
+        assert_eq!(
+            source,
+            "#ifndef ICON_H\n\
+             #define ICON_H\n\
+             \n\
+             #include <stdint.h>\n\
+             \n\
+             // 9x2 BinaryColor image, 1 bpp, big endian.\n\
+             // Pixels are packed MSB first and each row starts at a new byte.\n\
+             #define ICON_WIDTH 9\n\
+             #define ICON_HEIGHT 2\n\
+             \n\
+             static const uint8_t icon_data[4] = {\n    \
+             0x00, 0x00, 0x00, 0x80,\n\
+             };\n\
+             \n\
+             #endif // ICON_H\n"
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use std::{collections::HashMap, fmt::Write as _, fs, io, path::Path};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+
+use crate::{display::SimulatorDisplay, output_settings::OutputSettings};
+
+/// Pixel shape used by [`SvgExport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PixelShape {
+    /// Square pixels, like in the output of [`OutputImage`](crate::OutputImage).
+    #[default]
+    Square,
+    /// Round pixels, which resemble the look of LED matrices or some OLED displays.
+    Round,
+}
+
+/// Exporter for display content as SVG vector images.
+///
+/// The exported image uses the same geometry and colors as an [`OutputImage`](crate::OutputImage)
+/// created with the same [`OutputSettings`], but stays sharp at any resolution. One unit in the
+/// SVG coordinate system corresponds to one pixel in the output image.
+///
+/// To keep the files small, horizontal runs of pixels with the same color are merged into a single
+/// rectangle and equal runs in consecutive rows are merged into larger rectangles. Pixels with the
+/// background color aren't included in the output.
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+/// use embedded_graphics_simulator::{
+///     BinaryColorTheme, OutputSettingsBuilder, PixelShape, SimulatorDisplay, SvgExport,
+/// };
+///
+/// let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(16, 8));
+/// Pixel(Point::new(1, 2), BinaryColor::On).draw(&mut display).unwrap();
+///
+/// let output_settings = OutputSettingsBuilder::new()
+///     .theme(BinaryColorTheme::OledBlue)
+///     .scale(4)
+///     .pixel_spacing(1)
+///     .build();
+///
+/// let svg = SvgExport::new(&output_settings)
+///     .pixel_shape(PixelShape::Round)
+///     .to_svg(&display);
+/// assert!(svg.starts_with("<svg"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvgExport {
+    output_settings: OutputSettings,
+    pixel_shape: PixelShape,
+}
+
+impl SvgExport {
+    /// Creates a new SVG exporter.
+    pub fn new(output_settings: &OutputSettings) -> Self {
+        Self {
+            output_settings: *output_settings,
+            pixel_shape: PixelShape::default(),
+        }
+    }
+
+    /// Sets the pixel shape.
+    ///
+    /// The default shape is [`PixelShape::Square`].
+    pub fn pixel_shape(mut self, pixel_shape: PixelShape) -> Self {
+        self.pixel_shape = pixel_shape;
+        self
+    }
+
+    /// Exports the display content as SVG.
+    pub fn to_svg<C>(&self, display: &SimulatorDisplay<C>) -> String
+    where
+        C: PixelColor + Into<Rgb888>,
+    {
+        let size = display.output_size(&self.output_settings);
+        let scale = self.output_settings.scale;
+        let spacing = self.output_settings.pixel_spacing;
+        let pitch = scale + spacing;
+        let background = self.output_settings.theme.convert(Rgb888::BLACK);
+
+        let rectangles = merge_pixels(display, |color| {
+            let color = self.output_settings.theme.convert(color.into());
+            (color != background).then_some(color)
+        });
+
+        // Rectangles are grouped by color. The colors are ordered by their first occurrence to make
+        // the output deterministic.
+        let mut colors = Vec::new();
+        let mut groups = HashMap::<Rgb888, Vec<Rectangle>>::new();
+        for (color, rectangle) in rectangles {
+            groups
+                .entry(color)
+                .or_insert_with(|| {
+                    colors.push(color);
+                    Vec::new()
+                })
+                .push(rectangle);
+        }
+
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+             viewBox=\"0 0 {0} {1}\"",
+            size.width, size.height
+        );
+        if self.pixel_shape == PixelShape::Square {
+            let _ = write!(svg, " shape-rendering=\"crispEdges\"");
+        }
+        let _ = writeln!(svg, ">");
+
+        let _ = writeln!(
+            svg,
+            "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+            size.width,
+            size.height,
+            hex_color(background)
+        );
+
+        if self.pixel_shape == PixelShape::Round && !colors.is_empty() {
+            // Merged rectangles are filled with a pattern that contains a single round pixel.
+            let radius = scale as f32 / 2.0;
+
+            let _ = writeln!(svg, "<defs>");
+            for (index, color) in colors.iter().enumerate() {
+                let _ = writeln!(
+                    svg,
+                    "<pattern id=\"p{index}\" width=\"{pitch}\" height=\"{pitch}\" \
+                     patternUnits=\"userSpaceOnUse\"><circle cx=\"{radius}\" cy=\"{radius}\" \
+                     r=\"{radius}\" fill=\"{}\"/></pattern>",
+                    hex_color(*color)
+                );
+            }
+            let _ = writeln!(svg, "</defs>");
+        }
+
+        for (index, color) in colors.iter().enumerate() {
+            match self.pixel_shape {
+                PixelShape::Square => {
+                    let _ = writeln!(svg, "<g fill=\"{}\">", hex_color(*color));
+                }
+                PixelShape::Round => {
+                    let _ = writeln!(svg, "<g fill=\"url(#p{index})\">");
+                }
+            }
+
+            for rectangle in &groups[color] {
+                let x = rectangle.top_left.x as u32 * pitch;
+                let y = rectangle.top_left.y as u32 * pitch;
+                let width = rectangle.size.width * pitch - spacing;
+                let height = rectangle.size.height * pitch - spacing;
+
+                let _ = writeln!(
+                    svg,
+                    "<rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{height}\"/>"
+                );
+            }
+
+            let _ = writeln!(svg, "</g>");
+        }
+
+        // Merged square pixels also cover the spacing between them, which is restored by drawing
+        // the gaps in the background color.
+        if self.pixel_shape == PixelShape::Square && spacing > 0 && !colors.is_empty() {
+            let display_size = display.size();
+
+            let mut path = String::new();
+            for x in 1..display_size.width {
+                let _ = write!(
+                    path,
+                    "M{} 0h{spacing}V{}h-{spacing}z",
+                    x * pitch - spacing,
+                    size.height
+                );
+            }
+            for y in 1..display_size.height {
+                let _ = write!(
+                    path,
+                    "M0 {}H{}v{spacing}H0z",
+                    y * pitch - spacing,
+                    size.width
+                );
+            }
+
+            if !path.is_empty() {
+                let _ = writeln!(
+                    svg,
+                    "<path fill=\"{}\" d=\"{path}\"/>",
+                    hex_color(background)
+                );
+            }
+        }
+
+        let _ = writeln!(svg, "</svg>");
+
+        svg
+    }
+
+    /// Exports the display content as SVG and saves it to a file.
+    pub fn save<C, P>(&self, display: &SimulatorDisplay<C>, path: P) -> io::Result<()>
+    where
+        C: PixelColor + Into<Rgb888>,
+        P: AsRef<Path>,
+    {
+        fs::write(path, self.to_svg(display))
+    }
+}
+
+impl<C> SimulatorDisplay<C>
+where
+    C: PixelColor + Into<Rgb888>,
+{
+    /// Exports the display content as an SVG image.
+    ///
+    /// The SVG uses the same geometry and colors as the output image. See [`SvgExport`] for more
+    /// options.
+    pub fn to_svg(&self, output_settings: &OutputSettings) -> String {
+        SvgExport::new(output_settings).to_svg(self)
+    }
+}
+
+/// Merges pixels with the same color into rectangles in display coordinates.
+///
+/// Pixels for which `color` returns `None` are skipped.
+fn merge_pixels<C, F>(display: &SimulatorDisplay<C>, mut color: F) -> Vec<(Rgb888, Rectangle)>
+where
+    C: PixelColor,
+    F: FnMut(C) -> Option<Rgb888>,
+{
+    let size = display.size();
+
+    let mut rectangles = Vec::new();
+    // Rectangles that can still be extended, indexed by their horizontal position and color.
+    let mut open = HashMap::<(i32, u32, Rgb888), usize>::new();
+
+    for y in 0..size.height as i32 {
+        let mut next_open = HashMap::new();
+
+        let mut x = 0;
+        while x < size.width as i32 {
+            let start = x;
+            let run_color = color(display.get_pixel(Point::new(x, y)));
+            x += 1;
+            while x < size.width as i32 && color(display.get_pixel(Point::new(x, y))) == run_color {
+                x += 1;
+            }
+
+            let Some(run_color) = run_color else {
+                continue;
+            };
+            let key = (start, (x - start) as u32, run_color);
+
+            let index = match open.remove(&key) {
+                Some(index) => {
+                    let (_, rectangle): &mut (Rgb888, Rectangle) = &mut rectangles[index];
+                    rectangle.size.height += 1;
+                    index
+                }
+                None => {
+                    rectangles.push((
+                        run_color,
+                        Rectangle::new(Point::new(start, y), Size::new(key.1, 1)),
+                    ));
+                    rectangles.len() - 1
+                }
+            };
+            next_open.insert(key, index);
+        }
+
+        open = next_open;
+    }
+
+    rectangles
+}
+
+fn hex_color(color: Rgb888) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::{
+        pixelcolor::BinaryColor,
+        primitives::{PrimitiveStyle, Rectangle},
+    };
+
+    use crate::{BinaryColorTheme, OutputSettingsBuilder};
+
+    fn display() -> SimulatorDisplay<BinaryColor> {
+        let mut display = SimulatorDisplay::new(Size::new(6, 4));
+        Rectangle::new(Point::new(1, 1), Size::new(3, 2))
+            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+            .draw(&mut display)
+            .unwrap();
+        Pixel(Point::new(5, 3), BinaryColor::On)
+            .draw(&mut display)
+            .unwrap();
+
+        display
+    }
+
+    #[test]
+    fn merged_rectangles() {
+        let svg = display().to_svg(&OutputSettings::default());
+
+        assert_eq!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"6\" height=\"4\" \
+             viewBox=\"0 0 6 4\" shape-rendering=\"crispEdges\">\n\
+             <rect width=\"6\" height=\"4\" fill=\"#000000\"/>\n\
+             <g fill=\"#ffffff\">\n\
+             <rect x=\"1\" y=\"1\" width=\"3\" height=\"2\"/>\n\
+             <rect x=\"5\" y=\"3\" width=\"1\" height=\"1\"/>\n\
+             </g>\n\
+             </svg>\n"
+        );
+    }
+
+    #[test]
+    fn scale_and_spacing() {
+        let output_settings = OutputSettingsBuilder::new()
+            .theme(BinaryColorTheme::LcdGreen)
+            .scale(3)
+            .pixel_spacing(1)
+            .build();
+        let svg = display().to_svg(&output_settings);
+
+        assert!(svg.contains("width=\"23\" height=\"15\" viewBox=\"0 0 23 15\""));
+        assert!(svg.contains("<rect x=\"4\" y=\"4\" width=\"11\" height=\"7\"/>"));
+        assert!(svg.contains("<rect x=\"20\" y=\"12\" width=\"3\" height=\"3\"/>"));
+
+        let background = hex_color(BinaryColorTheme::LcdGreen.convert(Rgb888::BLACK));
+        assert!(svg.contains(&format!(
+            "<rect width=\"23\" height=\"15\" fill=\"{background}\"/>"
+        )));
+        assert!(svg.contains(&format!("<path fill=\"{background}\" d=\"M3 0h1V15h-1z")));
+        assert!(svg.contains("M0 11H23v1H0z\"/>"));
+    }
+
+    #[test]
+    fn round_pixels() {
+        let output_settings = OutputSettingsBuilder::new().scale(3).build();
+        let svg = SvgExport::new(&output_settings)
+            .pixel_shape(PixelShape::Round)
+            .to_svg(&display());
+
+        assert!(!svg.contains("crispEdges"));
+        assert!(svg.contains(
+            "<pattern id=\"p0\" width=\"3\" height=\"3\" patternUnits=\"userSpaceOnUse\">\
+             <circle cx=\"1.5\" cy=\"1.5\" r=\"1.5\" fill=\"#ffffff\"/></pattern>"
+        ));
+        assert!(svg.contains("<g fill=\"url(#p0)\">\n<rect x=\"3\" y=\"3\" width=\"9\""));
+    }
+}
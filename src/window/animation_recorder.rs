@@ -0,0 +1,528 @@
+use std::{
+    env,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+use crate::output_image::OutputImage;
+
+/// Frame timing used for animation recordings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FrameTiming {
+    /// Use the real time between frames.
+    ///
+    /// The frame delays in the recording match the time between the calls to `update` or
+    /// `flush`, including the time spent by the FPS limiter.
+    #[default]
+    Real,
+    /// Use a fixed virtual duration for each frame.
+    ///
+    /// The recording is independent of the rendering speed, which makes it reproducible. This is
+    /// useful to record applications that advance their animations by a fixed step per frame.
+    Fixed(Duration),
+}
+
+/// Animation recording settings.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use embedded_graphics_simulator::{AnimationSettings, FrameTiming};
+///
+/// let settings = AnimationSettings::new()
+///     .timing(FrameTiming::Fixed(Duration::from_millis(40)))
+///     .max_duration(Duration::from_secs(5));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct AnimationSettings {
+    timing: Option<FrameTiming>,
+    max_duration: Option<Duration>,
+}
+
+impl AnimationSettings {
+    /// Creates new animation settings with the default settings.
+    ///
+    /// By default the recording isn't limited in length and uses the real frame timing, except for
+    /// headless windows. Headless windows don't limit the frame rate and use a fixed frame
+    /// duration of `1 / max_fps` instead.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates animation settings for recordings that are started by using environment variables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `EG_SIMULATOR_RECORD_MAX_DURATION` is set to an invalid value.
+    pub(crate) fn from_env() -> Self {
+        let mut settings = Self::new();
+
+        if let Ok(value) = env::var("EG_SIMULATOR_RECORD_MAX_DURATION") {
+            let seconds = value
+                .parse::<f64>()
+                .ok()
+                .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                .unwrap_or_else(|| {
+                    panic!("EG_SIMULATOR_RECORD_MAX_DURATION must be a duration in seconds")
+                });
+            settings = settings.max_duration(seconds);
+        }
+
+        settings
+    }
+
+    /// Sets the frame timing.
+    pub fn timing(mut self, timing: FrameTiming) -> Self {
+        self.timing = Some(timing);
+        self
+    }
+
+    /// Sets the frame timing if it wasn't set explicitly.
+    pub(crate) fn default_timing(mut self, timing: FrameTiming) -> Self {
+        self.timing.get_or_insert(timing);
+        self
+    }
+
+    /// Sets the maximum duration of the recording.
+    ///
+    /// The recording is finished automatically as soon as a frame would start after the maximum
+    /// duration.
+    pub fn max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+}
+
+/// Records frames into an animated GIF or APNG file.
+///
+/// Frames are held back until the next frame is added, because the delay of a frame is only known
+/// once the next frame starts. Consecutive identical frames are merged into a single frame.
+pub(crate) struct AnimationRecorder {
+    format: AnimationFormat,
+    /// Output file, which is passed to the encoder once the image size is known.
+    file: Option<File>,
+    encoder: Option<AnimationEncoder>,
+    finished: bool,
+    settings: AnimationSettings,
+    start_time: Option<Instant>,
+    frame_count: u32,
+    pending: Option<Box<[u8]>>,
+    /// End of the last written frame in units of the encoder's time resolution.
+    written: u64,
+}
+
+impl AnimationRecorder {
+    /// Creates a new recorder.
+    ///
+    /// The format is determined by the file extension: `.gif` files are saved as GIF, `.png` and
+    /// `.apng` files as APNG.
+    pub fn new<P: AsRef<Path>>(path: P, settings: &AnimationSettings) -> io::Result<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+
+        let format = match extension.as_deref() {
+            Some("gif") => AnimationFormat::Gif,
+            Some("png" | "apng") => AnimationFormat::Apng,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "animations must be saved as .gif, .png or .apng files",
+                ))
+            }
+        };
+
+        Ok(Self {
+            format,
+            file: Some(File::create(path)?),
+            encoder: None,
+            finished: false,
+            settings: *settings,
+            start_time: None,
+            frame_count: 0,
+            pending: None,
+            written: 0,
+        })
+    }
+
+    /// Returns `true` if the recording is finished.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Adds a frame to the recording.
+    ///
+    /// Frames that are added after the recording was finished are ignored.
+    pub fn add_frame(&mut self, image: &OutputImage<Rgb888>) -> io::Result<()> {
+        if self.is_finished() {
+            return Ok(());
+        }
+
+        if let Some(file) = self.file.take() {
+            let writer = BufWriter::new(file);
+            self.encoder = Some(match self.format {
+                AnimationFormat::Gif => {
+                    AnimationEncoder::Gif(GifEncoder::new(writer, image.size())?)
+                }
+                AnimationFormat::Apng => {
+                    AnimationEncoder::Apng(ApngEncoder::new(writer, image.size())?)
+                }
+            });
+        }
+
+        let timestamp = match self.settings.timing.unwrap_or_default() {
+            FrameTiming::Real => self.start_time.get_or_insert_with(Instant::now).elapsed(),
+            FrameTiming::Fixed(duration) => duration * self.frame_count,
+        };
+        self.frame_count += 1;
+
+        if let Some(max_duration) = self.settings.max_duration {
+            if timestamp >= max_duration {
+                return self.finish_at(max_duration);
+            }
+        }
+
+        if self.pending.as_deref() == Some(&image.data[..]) {
+            return Ok(());
+        }
+
+        self.write_pending(timestamp, false)?;
+        self.pending = Some(image.data.clone());
+
+        Ok(())
+    }
+
+    /// Finishes the recording.
+    pub fn finish(&mut self) -> io::Result<()> {
+        if self.is_finished() {
+            return Ok(());
+        }
+
+        let end = match self.settings.timing.unwrap_or_default() {
+            FrameTiming::Real => self
+                .start_time
+                .map(|start_time| start_time.elapsed())
+                .unwrap_or_default(),
+            FrameTiming::Fixed(duration) => duration * self.frame_count,
+        };
+
+        let end = match self.settings.max_duration {
+            Some(max_duration) => end.min(max_duration),
+            None => end,
+        };
+
+        self.finish_at(end)
+    }
+
+    fn finish_at(&mut self, end: Duration) -> io::Result<()> {
+        self.write_pending(end, true)?;
+        self.finished = true;
+
+        match self.encoder.take() {
+            Some(encoder) => encoder.finish(),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes the pending frame, which ends at the given time.
+    ///
+    /// Frames that are shorter than the minimum delay of the format are dropped, unless it's the
+    /// last frame.
+    fn write_pending(&mut self, end: Duration, last: bool) -> io::Result<()> {
+        let (Some(data), Some(encoder)) = (self.pending.take(), &mut self.encoder) else {
+            return Ok(());
+        };
+
+        let end = (end.as_micros() as u64 + encoder.time_unit() / 2) / encoder.time_unit();
+        let mut delay = end.saturating_sub(self.written);
+
+        if delay < encoder.min_delay() {
+            if !last {
+                return Ok(());
+            }
+            delay = encoder.min_delay();
+        }
+
+        encoder.write_frame(data, delay)?;
+        self.written += delay;
+
+        Ok(())
+    }
+}
+
+impl Drop for AnimationRecorder {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnimationFormat {
+    Gif,
+    Apng,
+}
+
+enum AnimationEncoder {
+    Gif(GifEncoder),
+    Apng(ApngEncoder),
+}
+
+impl AnimationEncoder {
+    /// Returns the time resolution of the frame delays in microseconds.
+    fn time_unit(&self) -> u64 {
+        match self {
+            Self::Gif(_) => 10_000,
+            Self::Apng(_) => 1_000,
+        }
+    }
+
+    /// Returns the minimum frame delay in units of the time resolution.
+    ///
+    /// Most GIF viewers replace delays shorter than 20 ms by a much longer delay, which is why
+    /// shorter frames are dropped.
+    fn min_delay(&self) -> u64 {
+        match self {
+            Self::Gif(_) => 2,
+            Self::Apng(_) => 1,
+        }
+    }
+
+    fn write_frame(&mut self, data: Box<[u8]>, delay: u64) -> io::Result<()> {
+        match self {
+            Self::Gif(encoder) => encoder.write_frame(&data, delay),
+            Self::Apng(encoder) => encoder.write_frame(data, delay),
+        }
+    }
+
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Gif(encoder) => encoder.finish(),
+            Self::Apng(encoder) => encoder.finish(),
+        }
+    }
+}
+
+struct GifEncoder {
+    encoder: gif::Encoder<BufWriter<File>>,
+    width: u16,
+    height: u16,
+}
+
+impl GifEncoder {
+    fn new(writer: BufWriter<File>, size: Size) -> io::Result<Self> {
+        let (Ok(width), Ok(height)) = (u16::try_from(size.width), u16::try_from(size.height))
+        else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "image is too large for the GIF format",
+            ));
+        };
+
+        let mut encoder =
+            gif::Encoder::new(writer, width, height, &[]).map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+
+        Ok(Self {
+            encoder,
+            width,
+            height,
+        })
+    }
+
+    fn write_frame(&mut self, data: &[u8], delay: u64) -> io::Result<()> {
+        // Frames with more than 256 colors are quantized, otherwise the exact colors are used.
+        let mut frame = gif::Frame::from_rgb_speed(self.width, self.height, data, 10);
+        frame.delay = delay.min(u64::from(u16::MAX)) as u16;
+
+        self.encoder.write_frame(&frame).map_err(io::Error::other)
+    }
+
+    fn finish(self) -> io::Result<()> {
+        self.encoder.into_inner().map_err(io::Error::other)?.flush()
+    }
+}
+
+/// APNG encoder.
+///
+/// The number of frames must be known before the header of an APNG file is written, but isn't
+/// known until the recording is finished. The frames are therefore kept in memory and are encoded
+/// when the recording is finished.
+struct ApngEncoder {
+    writer: BufWriter<File>,
+    size: Size,
+    frames: Vec<(Box<[u8]>, u64)>,
+}
+
+impl ApngEncoder {
+    fn new(writer: BufWriter<File>, size: Size) -> io::Result<Self> {
+        Ok(Self {
+            writer,
+            size,
+            frames: Vec::new(),
+        })
+    }
+
+    fn write_frame(&mut self, data: Box<[u8]>, delay: u64) -> io::Result<()> {
+        self.frames.push((data, delay));
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(&mut self.writer, self.size.width, self.size.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_compression(png::Compression::Fast);
+        encoder
+            .set_animated(self.frames.len() as u32, 0)
+            .map_err(io::Error::other)?;
+
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        for (data, delay) in &self.frames {
+            // Delays are stored as a fraction of two 16 bit values.
+            let (delay_num, delay_den) = if *delay <= u64::from(u16::MAX) {
+                (*delay as u16, 1000)
+            } else {
+                ((delay / 1000).min(u64::from(u16::MAX)) as u16, 1)
+            };
+
+            writer
+                .set_frame_delay(delay_num, delay_den)
+                .map_err(io::Error::other)?;
+            writer.write_image_data(data).map_err(io::Error::other)?;
+        }
+        writer.finish().map_err(io::Error::other)?;
+
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    use embedded_graphics::pixelcolor::Rgb888;
+
+    use crate::test_utils::TempDir;
+
+    fn frame(color: Rgb888) -> OutputImage<Rgb888> {
+        let mut image = OutputImage::new(Size::new(4, 3));
+        image.clear(color).unwrap();
+        image
+    }
+
+    fn record(path: &Path, settings: &AnimationSettings, frames: &[Rgb888]) {
+        let mut recorder = AnimationRecorder::new(path, settings).unwrap();
+        for color in frames {
+            recorder.add_frame(&frame(*color)).unwrap();
+        }
+        recorder.finish().unwrap();
+    }
+
+    #[test]
+    fn gif_fixed_timing() {
+        let dir = TempDir::new("animation-fixed");
+        let path = dir.join("fixed.gif");
+        let settings =
+            AnimationSettings::new().timing(FrameTiming::Fixed(Duration::from_millis(50)));
+        record(
+            &path,
+            &settings,
+            &[Rgb888::RED, Rgb888::RED, Rgb888::GREEN, Rgb888::BLUE],
+        );
+
+        let mut decoder = gif::DecodeOptions::new();
+        decoder.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = decoder.read_info(fs::File::open(&path).unwrap()).unwrap();
+
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            frames.push((frame.delay, frame.buffer[0..3].to_vec()));
+        }
+
+        assert_eq!(
+            frames,
+            [
+                (10, vec![255, 0, 0]),
+                (5, vec![0, 255, 0]),
+                (5, vec![0, 0, 255]),
+            ]
+        );
+    }
+
+    #[test]
+    fn max_duration() {
+        let dir = TempDir::new("animation-max-duration");
+        let path = dir.join("max-duration.gif");
+        let settings = AnimationSettings::new()
+            .timing(FrameTiming::Fixed(Duration::from_millis(100)))
+            .max_duration(Duration::from_millis(250));
+
+        let mut recorder = AnimationRecorder::new(&path, &settings).unwrap();
+        for color in [Rgb888::RED, Rgb888::GREEN, Rgb888::BLUE] {
+            recorder.add_frame(&frame(color)).unwrap();
+            assert!(!recorder.is_finished());
+        }
+        recorder.add_frame(&frame(Rgb888::WHITE)).unwrap();
+        assert!(recorder.is_finished());
+        drop(recorder);
+
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(fs::File::open(&path).unwrap())
+            .unwrap();
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+
+        assert_eq!(delays, [10, 10, 5]);
+    }
+
+    #[test]
+    fn apng() {
+        let dir = TempDir::new("animation-apng");
+        let path = dir.join("animation.png");
+        let settings =
+            AnimationSettings::new().timing(FrameTiming::Fixed(Duration::from_millis(40)));
+        record(&path, &settings, &[Rgb888::RED, Rgb888::GREEN]);
+
+        let decoder = png::Decoder::new(io::BufReader::new(fs::File::open(&path).unwrap()));
+        let mut reader = decoder.read_info().unwrap();
+        let animation = reader.info().animation_control.unwrap();
+        assert_eq!(animation.num_frames, 2);
+        assert_eq!(animation.num_plays, 0);
+
+        let mut buffer = vec![0; reader.output_buffer_size().unwrap()];
+        let mut frames = Vec::new();
+        for _ in 0..2 {
+            reader.next_frame(&mut buffer).unwrap();
+            let control = reader.info().frame_control.unwrap();
+            frames.push((control.delay_num, control.delay_den, buffer[0..3].to_vec()));
+        }
+
+        assert_eq!(
+            frames,
+            [(40, 1000, vec![255, 0, 0]), (40, 1000, vec![0, 255, 0])]
+        );
+    }
+
+    #[test]
+    fn unsupported_extension() {
+        let error = AnimationRecorder::new("out.mp4", &AnimationSettings::new())
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
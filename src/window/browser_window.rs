@@ -0,0 +1,781 @@
+use std::{
+    cell::{RefCell, RefMut},
+    collections::VecDeque,
+    io::{self, ErrorKind, Read, Write},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender, TryRecvError},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use image::ColorType;
+use serde::Deserialize;
+use tungstenite::{
+    handshake::{
+        machine::TryParse,
+        server::{create_response, write_response, Request},
+    },
+    protocol::Role,
+    Bytes, Message, Utf8Bytes, WebSocket,
+};
+
+use crate::{
+    output_image::OutputImage,
+    output_settings::OutputSettings,
+    parse_error::ParseError,
+    png_options::{PngCompression, PngOptions},
+    sdl2::{Keycode, Mod, MouseButton, MouseWheelDirection},
+    SimulatorEvent,
+};
+
+/// Encoding of the frames that are sent to the browser.
+///
+/// See [`Window::new_browser`](crate::Window::new_browser) for more details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BrowserFrameEncoding {
+    /// PNG images.
+    ///
+    /// PNG frames are compressed, which reduces the required bandwidth.
+    #[default]
+    Png,
+    /// Raw RGB data.
+    ///
+    /// Each frame starts with the width and height of the image as big endian `u32` values,
+    /// followed by the uncompressed 8 bit RGB data. Raw frames need more bandwidth, but don't
+    /// require any encoding.
+    Rgb,
+}
+
+impl FromStr for BrowserFrameEncoding {
+    type Err = ParseError;
+
+    /// Parses a frame encoding.
+    ///
+    /// Valid values are `png` and `rgb`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "png" => Self::Png,
+            "rgb" => Self::Rgb,
+            _ => return Err(ParseError::new(s, "png or rgb")),
+        })
+    }
+}
+
+/// Web page that displays the frames and sends input events back to the simulator.
+const PAGE: &str = include_str!("browser_window.html");
+
+/// Maximum time a connection waits for a message from the browser before it checks for new
+/// frames.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Window that is displayed in a web browser.
+///
+/// A small web page is served by an HTTP server, which connects to the simulator by using a
+/// WebSocket. All connected browsers receive the same frames.
+///
+/// The server runs on a background thread and each connection is handled by a separate thread.
+/// Frames are passed to the connections and messages are passed back to the window by using
+/// channels, which makes sure that slow or misbehaving clients never block the render loop.
+pub struct BrowserWindow {
+    local_addr: SocketAddr,
+    server: Arc<Server>,
+    encoding: BrowserFrameEncoding,
+    last_frame: Option<Box<[u8]>>,
+    messages: Receiver<Utf8Bytes>,
+    events: RefCell<VecDeque<SimulatorEvent>>,
+}
+
+impl BrowserWindow {
+    pub fn new<A: ToSocketAddrs>(title: &str, addr: A) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+
+        let server = Arc::new(Server {
+            title: String::from(title),
+            clients: Mutex::new(Clients::default()),
+            stopped: AtomicBool::new(false),
+        });
+        let (sender, messages) = mpsc::channel();
+
+        let accept_server = Arc::clone(&server);
+        thread::Builder::new()
+            .name(String::from("browser-window"))
+            .spawn(move || accept_server.accept(listener, sender))?;
+
+        Ok(Self {
+            local_addr,
+            server,
+            encoding: BrowserFrameEncoding::default(),
+            last_frame: None,
+            messages,
+            events: RefCell::new(VecDeque::new()),
+        })
+    }
+
+    /// Returns the address the HTTP server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn set_encoding(&mut self, encoding: BrowserFrameEncoding) {
+        if encoding != self.encoding {
+            self.encoding = encoding;
+            self.last_frame = None;
+        }
+    }
+
+    /// Sends the framebuffer to all connected browsers if it has changed.
+    pub fn update(&mut self, framebuffer: &OutputImage<Rgb888>) -> io::Result<()> {
+        if self.last_frame.as_deref() == Some(&framebuffer.data[..]) {
+            return Ok(());
+        }
+
+        let size = framebuffer.size();
+        let payload = match self.encoding {
+            BrowserFrameEncoding::Png => PngOptions::new()
+                .compression(PngCompression::Fast)
+                .encode(&framebuffer.data, size.width, size.height, ColorType::Rgb8)
+                .map_err(io::Error::other)?,
+            BrowserFrameEncoding::Rgb => {
+                let mut payload = Vec::with_capacity(8 + framebuffer.data.len());
+                payload.extend_from_slice(&size.width.to_be_bytes());
+                payload.extend_from_slice(&size.height.to_be_bytes());
+                payload.extend_from_slice(&framebuffer.data);
+                payload
+            }
+        };
+
+        self.server
+            .clients
+            .lock()
+            .unwrap()
+            .send(Bytes::from(payload));
+        self.last_frame = Some(framebuffer.data.clone());
+
+        Ok(())
+    }
+
+    /// Returns the queue of events, after parsing all pending messages from the browsers.
+    ///
+    /// Mouse and touch positions are converted from output to display coordinates by using the
+    /// given output settings.
+    pub fn events(&self, output_settings: &OutputSettings) -> RefMut<'_, VecDeque<SimulatorEvent>> {
+        let mut events = self.events.borrow_mut();
+        events.extend(
+            self.messages
+                .try_iter()
+                .filter_map(|message| parse_event(message.as_bytes(), output_settings)),
+        );
+
+        events
+    }
+}
+
+impl Drop for BrowserWindow {
+    fn drop(&mut self) {
+        self.server.stopped.store(true, Ordering::Relaxed);
+
+        // Wake up the server thread, which is blocked while it waits for new connections.
+        let mut addr = self.local_addr;
+        if addr.ip().is_unspecified() {
+            addr.set_ip(match addr {
+                SocketAddr::V4(_) => Ipv4Addr::LOCALHOST.into(),
+                SocketAddr::V6(_) => Ipv6Addr::LOCALHOST.into(),
+            });
+        }
+        let _ = TcpStream::connect(addr);
+
+        // Dropping the senders stops the connection threads.
+        *self.server.clients.lock().unwrap() = Clients::default();
+    }
+}
+
+/// State that is shared between the window and the server threads.
+struct Server {
+    title: String,
+    clients: Mutex<Clients>,
+    stopped: AtomicBool,
+}
+
+impl Server {
+    /// Accepts connections until the window is dropped.
+    fn accept(self: Arc<Self>, listener: TcpListener, messages: Sender<Utf8Bytes>) {
+        for stream in listener.incoming() {
+            if self.stopped.load(Ordering::Relaxed) {
+                break;
+            }
+
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let server = Arc::clone(&self);
+            let messages = messages.clone();
+            // Invalid requests and disconnected clients are ignored.
+            let _ = thread::Builder::new()
+                .name(String::from("browser-window-connection"))
+                .spawn(move || {
+                    if let Ok(Some((socket, frames))) = server.handle_request(stream) {
+                        // The connection must not keep the server state alive after the window
+                        // was dropped.
+                        drop(server);
+                        let _ = serve_client(socket, frames, messages);
+                    }
+                });
+        }
+    }
+
+    /// Answers an HTTP request.
+    ///
+    /// Requests for the web page are answered directly. WebSocket connections are added to the
+    /// list of clients and returned together with the channel that receives the frames.
+    fn handle_request(
+        &self,
+        mut stream: TcpStream,
+    ) -> io::Result<Option<(WebSocket<TcpStream>, Receiver<Bytes>)>> {
+        stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+        stream.set_nodelay(true)?;
+
+        let request = read_request(&mut stream)?;
+
+        match request.uri().path() {
+            "/ws" => {
+                // Only the page served by this window is allowed to connect, to prevent other
+                // web sites from receiving the frames and sending events. The host is also
+                // checked to prevent DNS rebinding attacks, in which a foreign domain resolves to
+                // the address of the server.
+                if !is_allowed_origin(&request, stream.local_addr()?) {
+                    write_status(&mut stream, "403 Forbidden")?;
+                    return Ok(None);
+                }
+
+                let Ok(response) = create_response(&request) else {
+                    write_status(&mut stream, "400 Bad Request")?;
+                    return Ok(None);
+                };
+                write_response(&mut stream, &response).map_err(io::Error::other)?;
+
+                stream.set_read_timeout(Some(POLL_INTERVAL))?;
+                let socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+                let frames = self.clients.lock().unwrap().add();
+
+                Ok(Some((socket, frames)))
+            }
+            "/" => {
+                let page = PAGE.replace("{title}", &escape_html(&self.title));
+
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\n\
+                     Content-Type: text/html; charset=utf-8\r\n\
+                     Content-Length: {}\r\n\
+                     Connection: close\r\n\r\n{page}",
+                    page.len()
+                )?;
+
+                Ok(None)
+            }
+            _ => {
+                write_status(&mut stream, "404 Not Found")?;
+
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Channels to the connected browsers.
+#[derive(Default)]
+struct Clients {
+    senders: Vec<Sender<Bytes>>,
+    /// Last frame, which is sent to newly connected browsers.
+    last_frame: Option<Bytes>,
+}
+
+impl Clients {
+    fn send(&mut self, frame: Bytes) {
+        self.senders
+            .retain(|sender| sender.send(frame.clone()).is_ok());
+        self.last_frame = Some(frame);
+    }
+
+    fn add(&mut self) -> Receiver<Bytes> {
+        let (sender, receiver) = mpsc::channel();
+        if let Some(frame) = &self.last_frame {
+            let _ = sender.send(frame.clone());
+        }
+        self.senders.push(sender);
+
+        receiver
+    }
+}
+
+/// Sends frames to a browser and passes the received messages to the window.
+///
+/// Returns when the connection was closed or the window was dropped.
+fn serve_client(
+    mut socket: WebSocket<TcpStream>,
+    frames: Receiver<Bytes>,
+    messages: Sender<Utf8Bytes>,
+) -> tungstenite::Result<()> {
+    loop {
+        // Browsers that can't keep up skip frames and only receive the latest one.
+        let mut frame = None;
+        loop {
+            match frames.try_recv() {
+                Ok(next) => frame = Some(next),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return Ok(()),
+            }
+        }
+        if let Some(frame) = frame {
+            socket.send(Message::Binary(frame))?;
+        }
+
+        match socket.read() {
+            Ok(Message::Text(message)) => {
+                if messages.send(message).is_err() {
+                    return Ok(());
+                }
+            }
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Reads and parses the request line and headers of an HTTP request.
+fn read_request(stream: &mut TcpStream) -> io::Result<Request> {
+    let mut request = Vec::new();
+    let mut buffer = [0; 1024];
+
+    loop {
+        let len = stream.read(&mut buffer)?;
+        if len == 0 || request.len() > 16 * 1024 {
+            return Err(ErrorKind::InvalidData.into());
+        }
+        request.extend_from_slice(&buffer[..len]);
+
+        match Request::try_parse(&request) {
+            Ok(Some((_, request))) => return Ok(request),
+            Ok(None) => {}
+            Err(e) => return Err(io::Error::new(ErrorKind::InvalidData, e)),
+        }
+    }
+}
+
+/// Writes a response without a body.
+fn write_status(stream: &mut TcpStream, status: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+    )
+}
+
+/// Returns `true` if the request was sent by the page served at the given address.
+///
+/// The `Origin` header must match the `Host` header and the host must either be `localhost` or
+/// the IP address of the server.
+fn is_allowed_origin(request: &Request, local_addr: SocketAddr) -> bool {
+    let header = |name| request.headers().get(name)?.to_str().ok();
+
+    match (header("origin"), header("host")) {
+        (Some(origin), Some(host)) => {
+            origin.strip_prefix("http://") == Some(host) && is_local_host(host, local_addr)
+        }
+        _ => false,
+    }
+}
+
+/// Returns `true` if the value of a `Host` header refers to the given address.
+fn is_local_host(host: &str, local_addr: SocketAddr) -> bool {
+    // Browsers omit the port if it matches the default port.
+    let (name, port) = match host.rsplit_once(':') {
+        Some((name, port)) if !port.contains(']') => (name, port.parse().ok()),
+        _ => (host, Some(80)),
+    };
+
+    let name = name.trim_start_matches('[').trim_end_matches(']');
+    let is_local_name =
+        name == "localhost" || name.parse::<IpAddr>().is_ok_and(|ip| ip == local_addr.ip());
+
+    is_local_name && port == Some(local_addr.port())
+}
+
+/// Input event sent by the browser.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum BrowserEvent {
+    MouseDown { button: u8, x: i32, y: i32 },
+    MouseUp { button: u8, x: i32, y: i32 },
+    MouseMove { x: i32, y: i32 },
+    Wheel { dx: i32, dy: i32 },
+    KeyDown(KeyEvent),
+    KeyUp(KeyEvent),
+    TouchStart(TouchEvent),
+    TouchMove(TouchEvent),
+    TouchEnd(TouchEvent),
+    TouchCancel(TouchEvent),
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyEvent {
+    key: String,
+    shift: bool,
+    ctrl: bool,
+    alt: bool,
+    meta: bool,
+    repeat: bool,
+}
+
+impl KeyEvent {
+    /// Converts the `KeyboardEvent.key` value into a keycode.
+    fn keycode(&self) -> Option<Keycode> {
+        Some(match self.key.as_str() {
+            "Enter" => Keycode::RETURN,
+            "Escape" => Keycode::ESCAPE,
+            "Backspace" => Keycode::BACKSPACE,
+            "Tab" => Keycode::TAB,
+            "ArrowUp" => Keycode::UP,
+            "ArrowDown" => Keycode::DOWN,
+            "ArrowLeft" => Keycode::LEFT,
+            "ArrowRight" => Keycode::RIGHT,
+            "Home" => Keycode::HOME,
+            "End" => Keycode::END,
+            "PageUp" => Keycode::PAGEUP,
+            "PageDown" => Keycode::PAGEDOWN,
+            "Insert" => Keycode::INSERT,
+            "Delete" => Keycode::DELETE,
+            "Shift" => Keycode::LSHIFT,
+            "Control" => Keycode::LCTRL,
+            "Alt" => Keycode::LALT,
+            "Meta" => Keycode::LGUI,
+            "F1" => Keycode::F1,
+            "F2" => Keycode::F2,
+            "F3" => Keycode::F3,
+            "F4" => Keycode::F4,
+            "F5" => Keycode::F5,
+            "F6" => Keycode::F6,
+            "F7" => Keycode::F7,
+            "F8" => Keycode::F8,
+            "F9" => Keycode::F9,
+            "F10" => Keycode::F10,
+            "F11" => Keycode::F11,
+            "F12" => Keycode::F12,
+            // The keycodes of printable ASCII characters match their lowercase character codes.
+            key => match key.as_bytes() {
+                [byte @ 0x20..=0x7E] => Keycode::from_i32(i32::from(byte.to_ascii_lowercase()))?,
+                _ => return None,
+            },
+        })
+    }
+
+    fn keymod(&self) -> Mod {
+        let mut keymod = Mod::NOMOD;
+        if self.shift {
+            keymod |= Mod::LSHIFTMOD;
+        }
+        if self.ctrl {
+            keymod |= Mod::LCTRLMOD;
+        }
+        if self.alt {
+            keymod |= Mod::LALTMOD;
+        }
+        if self.meta {
+            keymod |= Mod::LGUIMOD;
+        }
+        keymod
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TouchEvent {
+    id: i64,
+    x: i32,
+    y: i32,
+    #[serde(default)]
+    pressure: f32,
+}
+
+/// Converts a message sent by the browser into a simulator event.
+fn parse_event(message: &[u8], output_settings: &OutputSettings) -> Option<SimulatorEvent> {
+    let point = |x, y| output_settings.output_to_display(Point::new(x, y));
+    let mouse_btn = |button| match button {
+        0 => MouseButton::Left,
+        1 => MouseButton::Middle,
+        2 => MouseButton::Right,
+        3 => MouseButton::X1,
+        4 => MouseButton::X2,
+        _ => MouseButton::Unknown,
+    };
+    let pressure = |touch: &TouchEvent| (touch.pressure * 100.0) as u32;
+
+    Some(match serde_json::from_slice(message).ok()? {
+        BrowserEvent::MouseDown { button, x, y } => SimulatorEvent::MouseButtonDown {
+            mouse_btn: mouse_btn(button),
+            point: point(x, y),
+        },
+        BrowserEvent::MouseUp { button, x, y } => SimulatorEvent::MouseButtonUp {
+            mouse_btn: mouse_btn(button),
+            point: point(x, y),
+        },
+        BrowserEvent::MouseMove { x, y } => SimulatorEvent::MouseMove { point: point(x, y) },
+        BrowserEvent::Wheel { dx, dy } => SimulatorEvent::MouseWheel {
+            scroll_delta: Point::new(dx, dy),
+            direction: MouseWheelDirection::Normal,
+        },
+        BrowserEvent::KeyDown(key) => SimulatorEvent::KeyDown {
+            keycode: key.keycode()?,
+            keymod: key.keymod(),
+            repeat: key.repeat,
+        },
+        BrowserEvent::KeyUp(key) => SimulatorEvent::KeyUp {
+            keycode: key.keycode()?,
+            keymod: key.keymod(),
+            repeat: key.repeat,
+        },
+        BrowserEvent::TouchStart(touch) => SimulatorEvent::TouchStarted {
+            id: touch.id,
+            point: point(touch.x, touch.y),
+            pressure: pressure(&touch),
+        },
+        BrowserEvent::TouchMove(touch) => SimulatorEvent::TouchMoved {
+            id: touch.id,
+            point: point(touch.x, touch.y),
+            pressure: pressure(&touch),
+        },
+        BrowserEvent::TouchEnd(touch) => SimulatorEvent::TouchEnded {
+            id: touch.id,
+            point: point(touch.x, touch.y),
+            pressure: pressure(&touch),
+        },
+        BrowserEvent::TouchCancel(touch) => SimulatorEvent::TouchCancelled {
+            id: touch.id,
+            point: point(touch.x, touch.y),
+            pressure: pressure(&touch),
+        },
+    })
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tungstenite::{client::IntoClientRequest, HandshakeError};
+
+    use crate::OutputSettingsBuilder;
+
+    #[test]
+    fn parse_encoding() {
+        assert_eq!("png".parse(), Ok(BrowserFrameEncoding::Png));
+        assert_eq!("rgb".parse(), Ok(BrowserFrameEncoding::Rgb));
+        assert_eq!(
+            "jpeg".parse::<BrowserFrameEncoding>(),
+            Err(ParseError::new("jpeg", "png or rgb"))
+        );
+    }
+
+    #[test]
+    fn browser_events() {
+        let output_settings = OutputSettingsBuilder::new().scale(2).build();
+        let event = |json: &str| parse_event(json.as_bytes(), &output_settings);
+
+        assert_eq!(
+            event(r#"{"type":"mousedown","button":2,"x":9,"y":4}"#),
+            Some(SimulatorEvent::MouseButtonDown {
+                mouse_btn: MouseButton::Right,
+                point: Point::new(4, 2),
+            })
+        );
+        assert_eq!(
+            event(
+                r#"{"type":"keydown","key":"A","shift":true,"ctrl":false,"alt":false,"meta":false,"repeat":false}"#
+            ),
+            Some(SimulatorEvent::KeyDown {
+                keycode: Keycode::A,
+                keymod: Mod::LSHIFTMOD,
+                repeat: false,
+            })
+        );
+        assert_eq!(
+            event(r#"{"type":"touchend","id":3,"x":2,"y":2,"pressure":0.5}"#),
+            Some(SimulatorEvent::TouchEnded {
+                id: 3,
+                point: Point::new(1, 1),
+                pressure: 50,
+            })
+        );
+        assert_eq!(
+            event(
+                r#"{"type":"keyup","key":"ä","shift":false,"ctrl":false,"alt":false,"meta":false,"repeat":false}"#
+            ),
+            None
+        );
+        assert_eq!(event("invalid"), None);
+    }
+
+    /// Opens a WebSocket connection to the window.
+    ///
+    /// The host is only used in the `Host` header, the connection is always made to the window's
+    /// address.
+    fn connect(
+        window: &BrowserWindow,
+        host: &str,
+        origin: &str,
+    ) -> tungstenite::Result<WebSocket<TcpStream>> {
+        let addr = window.local_addr();
+        let mut request = format!("ws://{host}/ws").into_client_request()?;
+        request
+            .headers_mut()
+            .insert("Origin", origin.parse().unwrap());
+
+        let stream = TcpStream::connect(addr)?;
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        let (socket, _) = tungstenite::client(request, stream).map_err(|e| match e {
+            HandshakeError::Failure(e) => e,
+            HandshakeError::Interrupted(_) => unreachable!(),
+        })?;
+
+        Ok(socket)
+    }
+
+    #[test]
+    fn stream_frames_and_events() {
+        let mut window = BrowserWindow::new("Test", "127.0.0.1:0").unwrap();
+        window.set_encoding(BrowserFrameEncoding::Rgb);
+        let host = window.local_addr().to_string();
+        let origin = format!("http://{host}");
+
+        let mut socket = connect(&window, &host, &origin).unwrap();
+
+        let mut framebuffer = OutputImage::new(Size::new(2, 1));
+        framebuffer.clear(Rgb888::RED).unwrap();
+        window.update(&framebuffer).unwrap();
+
+        let frame = [0, 0, 0, 2, 0, 0, 0, 1, 255, 0, 0, 255, 0, 0];
+        assert_eq!(socket.read().unwrap(), Message::binary(frame.to_vec()));
+
+        // Browsers that connect later receive the last frame.
+        let mut second_socket = connect(&window, &host, &origin).unwrap();
+        assert_eq!(
+            second_socket.read().unwrap(),
+            Message::binary(frame.to_vec())
+        );
+
+        socket
+            .send(Message::text(r#"{"type":"mousemove","x":1,"y":0}"#))
+            .unwrap();
+        // Messages must also be processed if the browser disconnects right after sending them.
+        socket.close(None).unwrap();
+        drop(socket);
+
+        let mut events = Vec::new();
+        for _ in 0..100 {
+            events.extend(window.events(&OutputSettings::default()).drain(..));
+            if !events.is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(
+            events,
+            [SimulatorEvent::MouseMove {
+                point: Point::new(1, 0)
+            }]
+        );
+
+        // The connections are closed when the window is dropped.
+        drop(window);
+        assert!(matches!(
+            second_socket.read(),
+            Err(tungstenite::Error::Io(_) | tungstenite::Error::Protocol(_))
+        ));
+    }
+
+    #[test]
+    fn reject_foreign_origin() {
+        let window = BrowserWindow::new("Test", "127.0.0.1:0").unwrap();
+        let port = window.local_addr().port();
+
+        let host = format!("127.0.0.1:{port}");
+
+        for (host, origin) in [
+            (host.clone(), "http://example.com".to_string()),
+            (host.clone(), format!("https://{host}")),
+            (host.clone(), format!("http://localhost:{port}")),
+            (host.clone(), "null".to_string()),
+            // DNS rebinding: a foreign domain that resolves to the address of the server.
+            (
+                format!("evil.example:{port}"),
+                format!("http://evil.example:{port}"),
+            ),
+            (
+                format!("127.0.0.2:{port}"),
+                format!("http://127.0.0.2:{port}"),
+            ),
+        ] {
+            match connect(&window, &host, &origin) {
+                Err(tungstenite::Error::Http(response)) => {
+                    assert_eq!(response.status(), 403, "{host} {origin}")
+                }
+                result => panic!("unexpected result for {host} {origin}: {result:?}"),
+            }
+        }
+        assert!(window.server.clients.lock().unwrap().senders.is_empty());
+
+        let host = format!("localhost:{port}");
+        assert!(connect(&window, &host, &format!("http://{host}")).is_ok());
+    }
+
+    #[test]
+    fn local_host() {
+        let addr = "127.0.0.1:8080".parse().unwrap();
+        assert!(is_local_host("127.0.0.1:8080", addr));
+        assert!(is_local_host("localhost:8080", addr));
+        assert!(!is_local_host("127.0.0.1:8081", addr));
+        assert!(!is_local_host("127.0.0.1", addr));
+        assert!(!is_local_host("example.com:8080", addr));
+
+        let addr = "[::1]:80".parse().unwrap();
+        assert!(is_local_host("[::1]", addr));
+        assert!(is_local_host("[::1]:80", addr));
+        assert!(is_local_host("localhost", addr));
+        assert!(!is_local_host("[::2]", addr));
+    }
+
+    /// Sends a GET request and returns the response.
+    fn get(window: &BrowserWindow, path: &str) -> String {
+        let mut stream = TcpStream::connect(window.local_addr()).unwrap();
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serve_page() {
+        let window = BrowserWindow::new("<Demo>", "127.0.0.1:0").unwrap();
+
+        let response = get(&window, "/");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("<title>&lt;Demo&gt;</title>"));
+
+        let response = get(&window, "/missing");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
@@ -0,0 +1,100 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::Instant,
+};
+
+use crate::{window::script_file::ScriptEntry, SimulatorEvent};
+
+/// Writes simulator events to a file.
+///
+/// The events are written in the same format that is used by
+/// [`InputScript`](crate::InputScript), which allows recorded sessions to be replayed.
+pub struct EventRecorder {
+    writer: BufWriter<File>,
+    frame: usize,
+    start_time: Option<Instant>,
+}
+
+impl EventRecorder {
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            frame: 0,
+            start_time: None,
+        })
+    }
+
+    /// Sets the frame index and start time that are used for subsequently recorded events.
+    pub fn set_frame(&mut self, frame: usize, start_time: Instant) {
+        self.frame = frame;
+        self.start_time = Some(start_time);
+    }
+
+    pub fn record(&mut self, event: SimulatorEvent) -> io::Result<()> {
+        let time = self
+            .start_time
+            .map(|start_time| start_time.elapsed())
+            .unwrap_or_default();
+
+        ScriptEntry::new(Some(self.frame), Some(time), event).write(&mut self.writer)?;
+
+        // Flush after every event to make sure that no events are lost if the process is
+        // terminated without dropping the window.
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use embedded_graphics::prelude::Point;
+
+    use crate::{
+        sdl2::{Keycode, Mod},
+        test_utils::TempDir,
+        EventTrigger, InputScript,
+    };
+
+    #[test]
+    fn record_and_load() {
+        let dir = TempDir::new("recorder");
+        let path = dir.join("events.jsonl");
+
+        let events = [
+            SimulatorEvent::KeyDown {
+                keycode: Keycode::SPACE,
+                keymod: Mod::NOMOD,
+                repeat: false,
+            },
+            SimulatorEvent::TouchMoved {
+                id: 3,
+                point: Point::new(10, 11),
+                pressure: 50,
+            },
+            SimulatorEvent::Quit,
+        ];
+
+        let mut recorder = EventRecorder::new(&path).unwrap();
+        recorder.record(events[0]).unwrap();
+        recorder.set_frame(4, Instant::now() - Duration::from_secs(1));
+        recorder.record(events[1]).unwrap();
+        recorder.record(events[2]).unwrap();
+        drop(recorder);
+
+        let script = InputScript::load(&path).unwrap();
+
+        assert_eq!(
+            script,
+            InputScript::from_iter([
+                (EventTrigger::Frame(0), events[0]),
+                (EventTrigger::Frame(4), events[1]),
+                (EventTrigger::Frame(4), events[2]),
+            ])
+        );
+    }
+}
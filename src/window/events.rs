@@ -0,0 +1,168 @@
+use std::{cell::RefMut, collections::VecDeque};
+
+use embedded_graphics::prelude::Point;
+
+use crate::sdl2::{Keycode, Mod, MouseButton, MouseWheelDirection};
+#[cfg(feature = "json")]
+use crate::window::event_recorder::EventRecorder;
+
+#[cfg(feature = "with-sdl")]
+use crate::window::sdl_window::SdlEvents;
+
+/// A derivation of SDL2 events mapped to embedded-graphics coordinates
+///
+/// Events can either be captured by an SDL2 window or be played back from an [`InputScript`].
+///
+/// [`InputScript`]: crate::InputScript
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SimulatorEvent {
+    /// A keypress event, fired on keyUp
+    KeyUp {
+        /// The key being released
+        keycode: Keycode,
+        /// Any modifier being held at the time of keyup
+        keymod: Mod,
+        /// Whether the key is repeating
+        repeat: bool,
+    },
+    /// A keypress event, fired on keyDown
+    KeyDown {
+        /// The key being pressed
+        keycode: Keycode,
+        /// Any modifier being held at the time of keydown
+        keymod: Mod,
+        /// Whether the key is repeating
+        repeat: bool,
+    },
+    /// A mouse click event, fired on mouseUp
+    MouseButtonUp {
+        /// The mouse button being released
+        mouse_btn: MouseButton,
+        /// The location of the mouse in Simulator coordinates
+        point: Point,
+    },
+    /// A mouse click event, fired on mouseDown
+    MouseButtonDown {
+        /// The mouse button being pressed
+        mouse_btn: MouseButton,
+        /// The location of the mouse in Simulator coordinates
+        point: Point,
+    },
+    /// A mouse wheel event
+    MouseWheel {
+        /// The scroll wheel delta in the x and y direction
+        scroll_delta: Point,
+        /// The directionality of the scroll (normal or flipped)
+        direction: MouseWheelDirection,
+    },
+    /// Mouse move event
+    MouseMove {
+        /// The current mouse position
+        point: Point,
+    },
+    /// Touch started event
+    TouchStarted {
+        /// The ID of the finger that started the touch
+        id: i64,
+        /// The location of the touch in Simulator coordinates
+        point: Point,
+        /// The pressure of the touch.
+        pressure: u32,
+    },
+    /// Touch moved event
+    TouchMoved {
+        /// The ID of the finger that moved
+        id: i64,
+        /// The location of the touch in Simulator coordinates
+        point: Point,
+        /// The pressure of the touch.
+        pressure: u32,
+    },
+    /// Touch ended event
+    TouchEnded {
+        /// The ID of the finger that ended the touch
+        id: i64,
+        /// The location of the touch in Simulator coordinates
+        point: Point,
+        /// The pressure of the touch.
+        pressure: u32,
+    },
+    /// Touch cancelled event
+    TouchCancelled {
+        /// The ID of the finger whose touch was cancelled
+        id: i64,
+        /// The location of the touch in Simulator coordinates
+        point: Point,
+        /// The pressure of the touch.
+        pressure: u32,
+    },
+    /// An exit event
+    Quit,
+}
+
+/// Iterator over simulator events.
+///
+/// See [`Window::events`](crate::Window::events) and
+/// [`MultiWindow::events`](crate::MultiWindow::events) for more details.
+pub struct SimulatorEventsIter<'a> {
+    pub(crate) scripted_events: Option<RefMut<'a, VecDeque<SimulatorEvent>>>,
+    #[cfg(feature = "with-sdl")]
+    pub(crate) sdl_events: Option<SdlEvents<'a>>,
+    #[cfg(feature = "terminal")]
+    pub(crate) terminal_events: Option<RefMut<'a, VecDeque<SimulatorEvent>>>,
+    #[cfg(feature = "browser")]
+    pub(crate) browser_events: Option<RefMut<'a, VecDeque<SimulatorEvent>>>,
+    #[cfg(feature = "json")]
+    pub(crate) recorder: Option<RefMut<'a, EventRecorder>>,
+}
+
+impl Iterator for SimulatorEventsIter<'_> {
+    type Item = SimulatorEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.next_event()?;
+
+        #[cfg(feature = "json")]
+        if let Some(recorder) = &mut self.recorder {
+            recorder
+                .record(event)
+                .unwrap_or_else(|e| panic!("failed to record event: {e}"));
+        }
+
+        Some(event)
+    }
+}
+
+impl SimulatorEventsIter<'_> {
+    fn next_event(&mut self) -> Option<SimulatorEvent> {
+        if let Some(event) = self
+            .scripted_events
+            .as_mut()
+            .and_then(|events| events.pop_front())
+        {
+            return Some(event);
+        }
+
+        #[cfg(feature = "terminal")]
+        if let Some(terminal_events) = &mut self.terminal_events {
+            return terminal_events.pop_front();
+        }
+
+        // Browser events are mixed with SDL events if a multi window is streamed to a browser.
+        #[cfg(feature = "browser")]
+        if let Some(event) = self
+            .browser_events
+            .as_mut()
+            .and_then(|events| events.pop_front())
+        {
+            return Some(event);
+        }
+
+        #[cfg(feature = "with-sdl")]
+        if let Some(sdl_events) = &mut self.sdl_events {
+            return sdl_events.poll();
+        }
+
+        None
+    }
+}
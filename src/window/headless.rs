@@ -0,0 +1,150 @@
+use std::{collections::VecDeque, time::Duration};
+
+use embedded_graphics::pixelcolor::Rgb888;
+
+use crate::OutputImage;
+
+/// Frame captured by a headless window.
+///
+/// See [`Window::new_headless`](crate::Window::new_headless) for more details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    index: usize,
+    timestamp: Duration,
+    image: OutputImage<Rgb888>,
+}
+
+impl Frame {
+    /// Returns the index of this frame.
+    ///
+    /// The first frame passed to [`Window::update`](crate::Window::update) has the index `0`.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the time at which this frame was rendered.
+    ///
+    /// The timestamp is relative to the first frame, which always has a timestamp of zero.
+    pub fn timestamp(&self) -> Duration {
+        self.timestamp
+    }
+
+    /// Returns the rendered output image.
+    ///
+    /// The output settings of the window have already been applied to the image.
+    pub fn image(&self) -> &OutputImage<Rgb888> {
+        &self.image
+    }
+}
+
+pub struct HeadlessWindow {
+    frames: VecDeque<Frame>,
+    max_frames: usize,
+}
+
+impl HeadlessWindow {
+    /// Number of frames that are kept by default.
+    pub const DEFAULT_HISTORY_LEN: usize = 1;
+
+    pub fn new() -> Self {
+        Self {
+            frames: VecDeque::new(),
+            max_frames: Self::DEFAULT_HISTORY_LEN,
+        }
+    }
+
+    pub fn update(&mut self, framebuffer: &OutputImage<Rgb888>, index: usize, timestamp: Duration) {
+        if self.max_frames == 0 {
+            return;
+        }
+
+        while self.frames.len() >= self.max_frames {
+            self.frames.pop_front();
+        }
+
+        self.frames.push_back(Frame {
+            index,
+            timestamp,
+            image: framebuffer.clone(),
+        });
+    }
+
+    pub fn set_history_len(&mut self, len: usize) {
+        self.max_frames = len;
+
+        while self.frames.len() > self.max_frames {
+            self.frames.pop_front();
+        }
+    }
+
+    pub fn frames(&self) -> impl Iterator<Item = &Frame> {
+        self.frames.iter()
+    }
+
+    pub fn last_frame(&self) -> Option<&Frame> {
+        self.frames.back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::prelude::*;
+
+    fn framebuffer(color: Rgb888) -> OutputImage<Rgb888> {
+        let mut image = OutputImage::new(Size::new(2, 2));
+        image.clear(color).unwrap();
+
+        image
+    }
+
+    #[test]
+    fn default_history_len() {
+        let mut window = HeadlessWindow::new();
+        assert_eq!(window.last_frame(), None);
+
+        window.update(&framebuffer(Rgb888::RED), 0, Duration::ZERO);
+        window.update(&framebuffer(Rgb888::GREEN), 1, Duration::ZERO);
+
+        assert_eq!(window.frames().count(), 1);
+
+        let frame = window.last_frame().unwrap();
+        assert_eq!(frame.index(), 1);
+        assert_eq!(frame.image(), &framebuffer(Rgb888::GREEN));
+    }
+
+    #[test]
+    fn bounded_history() {
+        let mut window = HeadlessWindow::new();
+        window.set_history_len(3);
+
+        for index in 0..5 {
+            window.update(
+                &framebuffer(Rgb888::BLUE),
+                index,
+                Duration::from_millis(index as u64 * 10),
+            );
+        }
+
+        let indices = window.frames().map(Frame::index).collect::<Vec<_>>();
+        assert_eq!(indices, [2, 3, 4]);
+
+        let timestamps = window.frames().map(Frame::timestamp).collect::<Vec<_>>();
+        assert_eq!(timestamps, [20, 30, 40].map(Duration::from_millis));
+
+        window.set_history_len(1);
+        let indices = window.frames().map(Frame::index).collect::<Vec<_>>();
+        assert_eq!(indices, [4]);
+    }
+
+    #[test]
+    fn disabled_history() {
+        let mut window = HeadlessWindow::new();
+        window.set_history_len(0);
+
+        window.update(&framebuffer(Rgb888::BLUE), 0, Duration::ZERO);
+
+        assert_eq!(window.last_frame(), None);
+    }
+}
@@ -0,0 +1,259 @@
+use std::time::Duration;
+#[cfg(feature = "json")]
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+#[cfg(feature = "json")]
+use crate::window::script_file::ScriptEntry;
+use crate::SimulatorEvent;
+
+/// Condition that determines when a scripted event is played back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventTrigger {
+    /// Play back the event after the frame with the given index was rendered.
+    ///
+    /// The first frame passed to [`Window::update`](crate::Window::update) has the index `0`.
+    Frame(usize),
+
+    /// Play back the event after the given time has elapsed.
+    ///
+    /// The time is measured relative to the first call to
+    /// [`Window::update`](crate::Window::update).
+    Time(Duration),
+}
+
+impl EventTrigger {
+    fn is_due(self, frame: usize, time: Duration) -> bool {
+        match self {
+            EventTrigger::Frame(trigger_frame) => frame >= trigger_frame,
+            EventTrigger::Time(trigger_time) => time >= trigger_time,
+        }
+    }
+}
+
+/// Script of simulator events.
+///
+/// An input script can be used to feed a predefined sequence of [`SimulatorEvent`]s into a
+/// [`Window`](crate::Window) by using
+/// [`Window::set_input_script`](crate::Window::set_input_script). Each event is associated with
+/// an [`EventTrigger`] which determines after which frame, or after how much time, the event is
+/// returned by [`Window::events`](crate::Window::events). This makes it possible to test
+/// interactive applications without user interaction and works with and without SDL2 support.
+///
+/// If the `json` feature is enabled, scripts can also be loaded from a file by using `load` or by
+/// setting the `EG_SIMULATOR_REPLAY` environment variable to the path of the script file.
+///
+/// # File format
+///
+/// Script files contain one JSON object per line. Each object contains a `frame` or `time` (in
+/// seconds) field and the `event`. If both fields are set, the frame number is used.
+///
+/// ```text
+/// {"frame":0,"event":{"type":"mouse_button_down","mouse_btn":"left","point":[10,20]}}
+/// {"frame":1,"event":{"type":"mouse_button_up","mouse_btn":"left","point":[10,20]}}
+/// {"time":0.5,"event":{"type":"key_down","keycode":1073741904,"keymod":0,"repeat":false}}
+/// {"time":1.0,"event":{"type":"quit"}}
+/// ```
+///
+/// Keycodes and modifiers are stored using their numeric SDL2 values.
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+/// use embedded_graphics_simulator::{
+///     sdl2::MouseButton, InputScript, OutputSettings, SimulatorDisplay, SimulatorEvent, Window,
+/// };
+///
+/// let script = InputScript::new()
+///     .at_frame(
+///         1,
+///         SimulatorEvent::MouseButtonUp {
+///             mouse_btn: MouseButton::Left,
+///             point: Point::new(3, 4),
+///         },
+///     )
+///     .at_frame(2, SimulatorEvent::Quit);
+///
+/// let display = SimulatorDisplay::<BinaryColor>::new(Size::new(16, 16));
+/// let mut window = Window::new_headless("Scripted input", &OutputSettings::default());
+/// window.set_input_script(script);
+///
+/// let mut clicks = Vec::new();
+///
+/// 'running: loop {
+///     window.update(&display);
+///
+///     for event in window.events() {
+///         match event {
+///             SimulatorEvent::MouseButtonUp { point, .. } => clicks.push(point),
+///             SimulatorEvent::Quit => break 'running,
+///             _ => {}
+///         }
+///     }
+/// }
+///
+/// assert_eq!(clicks, [Point::new(3, 4)]);
+/// assert_eq!(window.frame_count(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct InputScript {
+    events: Vec<(EventTrigger, SimulatorEvent)>,
+}
+
+impl InputScript {
+    /// Creates an empty input script.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an event that is played back after the given frame.
+    pub fn at_frame(mut self, frame: usize, event: SimulatorEvent) -> Self {
+        self.push(EventTrigger::Frame(frame), event);
+
+        self
+    }
+
+    /// Adds an event that is played back after the given time has elapsed.
+    pub fn at_time(mut self, time: Duration, event: SimulatorEvent) -> Self {
+        self.push(EventTrigger::Time(time), event);
+
+        self
+    }
+
+    /// Adds an event to the script.
+    pub fn push(&mut self, trigger: EventTrigger, event: SimulatorEvent) {
+        self.events.push((trigger, event));
+    }
+
+    /// Returns the number of events that haven't been played back yet.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns `true` if all events have been played back.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Loads an input script from a file.
+    ///
+    /// This method requires the `json` feature.
+    #[cfg(feature = "json")]
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+
+        let mut script = Self::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entry: ScriptEntry = serde_json::from_str(&line)?;
+            let (trigger, event) = entry.into_parts()?;
+
+            script.push(trigger, event);
+        }
+
+        Ok(script)
+    }
+
+    /// Saves the input script to a file.
+    ///
+    /// This method requires the `json` feature.
+    #[cfg(feature = "json")]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        for (trigger, event) in &self.events {
+            let (frame, time) = match trigger {
+                EventTrigger::Frame(frame) => (Some(*frame), None),
+                EventTrigger::Time(time) => (None, Some(*time)),
+            };
+
+            ScriptEntry::new(frame, time, *event).write(&mut writer)?;
+        }
+
+        writer.flush()
+    }
+
+    /// Removes all events which are due and passes them to `f`.
+    pub(crate) fn poll<F>(&mut self, frame: usize, time: Duration, mut f: F)
+    where
+        F: FnMut(SimulatorEvent),
+    {
+        self.events.retain(|(trigger, event)| {
+            let due = trigger.is_due(frame, time);
+            if due {
+                f(*event);
+            }
+
+            !due
+        });
+    }
+}
+
+impl FromIterator<(EventTrigger, SimulatorEvent)> for InputScript {
+    fn from_iter<T: IntoIterator<Item = (EventTrigger, SimulatorEvent)>>(iter: T) -> Self {
+        Self {
+            events: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::prelude::Point;
+
+    fn poll_all(script: &mut InputScript, frame: usize, time: Duration) -> Vec<SimulatorEvent> {
+        let mut events = Vec::new();
+        script.poll(frame, time, |event| events.push(event));
+
+        events
+    }
+
+    fn mouse_move(x: i32) -> SimulatorEvent {
+        SimulatorEvent::MouseMove {
+            point: Point::new(x, 0),
+        }
+    }
+
+    #[test]
+    fn frame_triggers() {
+        let mut script = InputScript::new()
+            .at_frame(0, mouse_move(0))
+            .at_frame(2, mouse_move(2))
+            .at_frame(2, mouse_move(3));
+
+        assert_eq!(poll_all(&mut script, 0, Duration::ZERO), [mouse_move(0)]);
+        assert_eq!(poll_all(&mut script, 1, Duration::ZERO), []);
+        assert_eq!(
+            poll_all(&mut script, 2, Duration::ZERO),
+            [mouse_move(2), mouse_move(3)]
+        );
+        assert!(script.is_empty());
+    }
+
+    #[test]
+    fn time_triggers() {
+        let mut script = InputScript::new()
+            .at_time(Duration::from_millis(100), mouse_move(1))
+            .at_time(Duration::from_millis(50), mouse_move(0));
+
+        assert_eq!(poll_all(&mut script, 0, Duration::ZERO), []);
+        assert_eq!(
+            poll_all(&mut script, 1, Duration::from_millis(60)),
+            [mouse_move(0)]
+        );
+        assert_eq!(
+            poll_all(&mut script, 2, Duration::from_millis(120)),
+            [mouse_move(1)]
+        );
+    }
+}
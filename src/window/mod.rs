@@ -1,23 +1,50 @@
 use std::{
-    env,
-    fs::File,
-    io::BufReader,
-    ops::Deref,
+    cell::RefCell,
+    collections::VecDeque,
+    env, io,
+    path::Path,
     process, thread,
     time::{Duration, Instant},
 };
 
+#[cfg(feature = "browser")]
+use std::net::{SocketAddr, ToSocketAddrs};
+
 use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
 
 use crate::{
-    display::SimulatorDisplay, output_image::OutputImage, output_settings::OutputSettings,
+    check, display::SimulatorDisplay, output_image::OutputImage, output_settings::OutputSettings,
+    png_options::PngOptions,
 };
 
+#[cfg(feature = "animation")]
+mod animation_recorder;
+#[cfg(feature = "json")]
+mod event_recorder;
+mod events;
+mod headless;
+mod input_script;
+#[cfg(feature = "json")]
+mod script_file;
+mod video_recorder;
+
+#[cfg(feature = "animation")]
+use animation_recorder::AnimationRecorder;
+#[cfg(feature = "animation")]
+pub use animation_recorder::{AnimationSettings, FrameTiming};
+#[cfg(feature = "json")]
+use event_recorder::EventRecorder;
+pub use events::{SimulatorEvent, SimulatorEventsIter};
+pub use headless::Frame;
+use headless::HeadlessWindow;
+pub use input_script::{EventTrigger, InputScript};
+use video_recorder::VideoRecorder;
+
 #[cfg(feature = "with-sdl")]
 mod sdl_window;
 
 #[cfg(feature = "with-sdl")]
-pub use sdl_window::{SdlWindow, SimulatorEvent, SimulatorEventsIter};
+pub use sdl_window::SdlWindow;
 
 #[cfg(feature = "with-sdl")]
 mod multi_window;
@@ -25,6 +52,22 @@ mod multi_window;
 #[cfg(feature = "with-sdl")]
 pub use multi_window::MultiWindow;
 
+#[cfg(feature = "terminal")]
+mod terminal_window;
+
+#[cfg(feature = "terminal")]
+pub use terminal_window::TerminalGraphics;
+#[cfg(feature = "terminal")]
+use terminal_window::TerminalWindow;
+
+#[cfg(feature = "browser")]
+mod browser_window;
+
+#[cfg(feature = "browser")]
+pub use browser_window::BrowserFrameEncoding;
+#[cfg(feature = "browser")]
+pub(crate) use browser_window::BrowserWindow;
+
 pub(crate) struct FpsLimiter {
     max_fps: u32,
     frame_start: Instant,
@@ -51,28 +94,246 @@ impl FpsLimiter {
     }
 }
 
+enum Backend {
+    #[cfg(feature = "with-sdl")]
+    Sdl(Option<SdlWindow>),
+    Headless(HeadlessWindow),
+    #[cfg(feature = "terminal")]
+    Terminal(TerminalWindow),
+    #[cfg(feature = "browser")]
+    Browser(BrowserWindow),
+}
+
 /// Simulator window
-#[allow(dead_code)]
+///
+/// By default the window is displayed using SDL2. If the `with-sdl` feature is disabled, or if the
+/// window was created by using [`new_headless`](Self::new_headless), no window is shown and the
+/// rendered frames are kept in memory instead.
 pub struct Window {
     framebuffer: Option<OutputImage<Rgb888>>,
-    #[cfg(feature = "with-sdl")]
-    sdl_window: Option<SdlWindow>,
+    backend: Backend,
+    #[allow(dead_code)]
     title: String,
     output_settings: OutputSettings,
     fps_limiter: FpsLimiter,
+    frame_count: usize,
+    start_time: Option<Instant>,
+    input_script: Option<InputScript>,
+    scripted_events: RefCell<VecDeque<SimulatorEvent>>,
+    #[cfg(feature = "json")]
+    event_recorder: Option<RefCell<EventRecorder>>,
+    #[cfg(feature = "animation")]
+    animation_recorder: Option<AnimationRecorder>,
+    video_recorder: Option<VideoRecorder>,
 }
 
 impl Window {
     /// Creates a new simulator window.
+    ///
+    /// If the `with-sdl` feature is disabled this is equivalent to
+    /// [`new_headless`](Self::new_headless).
+    ///
+    /// If the `terminal` feature is enabled and the `EG_SIMULATOR_TERMINAL` environment variable
+    /// is set to `half-blocks`, `sixel` or `kitty`, a terminal window is created instead. See
+    /// `new_terminal` for more details.
+    ///
+    /// If the `browser` feature is enabled and the `EG_SIMULATOR_BROWSER` environment variable
+    /// is set to a socket address, e.g. `127.0.0.1:8080`, a browser window is created instead.
+    /// The frame encoding can be selected by setting `EG_SIMULATOR_BROWSER_ENCODING` to `png` or
+    /// `rgb`. See `new_browser` for more details.
     pub fn new(title: &str, output_settings: &OutputSettings) -> Self {
-        Self {
+        #[cfg(feature = "terminal")]
+        if let Ok(graphics) = env::var("EG_SIMULATOR_TERMINAL") {
+            let graphics = graphics.parse().unwrap_or_else(|_| {
+                panic!("EG_SIMULATOR_TERMINAL must be half-blocks, sixel or kitty")
+            });
+
+            return Self::new_terminal(title, output_settings, graphics);
+        }
+
+        #[cfg(feature = "browser")]
+        if let Ok(addr) = env::var("EG_SIMULATOR_BROWSER") {
+            let mut window = Self::new_browser(title, output_settings, addr.as_str())
+                .unwrap_or_else(|e| panic!("failed to start browser window on \"{addr}\": {e}"));
+
+            if let Ok(encoding) = env::var("EG_SIMULATOR_BROWSER_ENCODING") {
+                let encoding = encoding
+                    .parse()
+                    .unwrap_or_else(|_| panic!("EG_SIMULATOR_BROWSER_ENCODING must be png or rgb"));
+                window.set_browser_frame_encoding(encoding);
+            }
+
+            if let Some(addr) = window.browser_addr() {
+                eprintln!("Serving \"{title}\" at http://{addr}/");
+            }
+
+            return window;
+        }
+
+        #[cfg(feature = "with-sdl")]
+        let backend = Backend::Sdl(None);
+        #[cfg(not(feature = "with-sdl"))]
+        let backend = Backend::Headless(HeadlessWindow::new());
+
+        Self::with_backend(title, output_settings, backend)
+    }
+
+    /// Creates a new headless simulator window.
+    ///
+    /// A headless window doesn't open a window on the screen, instead the rendered frames are
+    /// stored in a bounded frame history which can be accessed by using the
+    /// [`frames`](Self::frames) and [`last_frame`](Self::last_frame) methods. By default only the
+    /// most recent frame is kept, use [`set_frame_history_len`](Self::set_frame_history_len) to
+    /// keep more frames.
+    ///
+    /// Headless windows can be used to test applications in environments without a display
+    /// server, like CI runners. The FPS limit isn't applied to headless windows, which means that
+    /// [`update`](Self::update) returns immediately.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_simulator::{OutputSettings, SimulatorDisplay, Window};
+    ///
+    /// let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(16, 8));
+    /// let mut window = Window::new_headless("Test", &OutputSettings::default());
+    /// window.set_frame_history_len(10);
+    ///
+    /// for x in 0..3 {
+    ///     Pixel(Point::new(x, 0), BinaryColor::On).draw(&mut display).unwrap();
+    ///     window.update(&display);
+    /// }
+    ///
+    /// assert_eq!(window.frame_count(), 3);
+    ///
+    /// let frame = window.last_frame().unwrap();
+    /// assert_eq!(frame.index(), 2);
+    /// assert_eq!(frame.image().size(), Size::new(16, 8));
+    /// ```
+    pub fn new_headless(title: &str, output_settings: &OutputSettings) -> Self {
+        Self::with_backend(
+            title,
+            output_settings,
+            Backend::Headless(HeadlessWindow::new()),
+        )
+    }
+
+    /// Creates a new simulator window which is displayed in the terminal.
+    ///
+    /// The themed output is drawn into the terminal using the selected [`TerminalGraphics`]
+    /// protocol and is redrawn in place each time the content changes. Unicode half blocks with
+    /// 24-bit colors are supported by most terminal emulators and also work over SSH. Each
+    /// character cell contains two vertically stacked pixels and the output is cropped to the
+    /// size of the terminal. Sixel and kitty graphics show the output at full resolution, but are
+    /// only supported by some terminal emulators.
+    ///
+    /// Keystrokes are returned by [`events`](Self::events) as [`SimulatorEvent::KeyDown`] events,
+    /// which are directly followed by a [`SimulatorEvent::KeyUp`] event, because terminals don't
+    /// report key releases. Key events are only supported on Unix-like systems. Pressing `Ctrl+C`
+    /// terminates the process after the terminal was restored.
+    ///
+    /// A terminal window can also be created without code changes by setting the
+    /// `EG_SIMULATOR_TERMINAL` environment variable, see [`new`](Self::new).
+    ///
+    /// This method requires the `terminal` feature.
+    #[cfg(feature = "terminal")]
+    pub fn new_terminal(
+        title: &str,
+        output_settings: &OutputSettings,
+        graphics: TerminalGraphics,
+    ) -> Self {
+        Self::with_backend(
+            title,
+            output_settings,
+            Backend::Terminal(TerminalWindow::new(title, graphics)),
+        )
+    }
+
+    /// Creates a new simulator window which is displayed in a web browser.
+    ///
+    /// A small web page is served over HTTP on the given address, which can be opened in any
+    /// modern browser, e.g. `http://127.0.0.1:8080/` for the address `127.0.0.1:8080`. Each frame
+    /// passed to [`update`](Self::update) is pushed to all connected browsers over a WebSocket.
+    /// Frames are only sent if the content has changed and newly connected browsers immediately
+    /// receive the last frame. Use port `0` to let the operating system select a free port, which
+    /// can be queried by using [`browser_addr`](Self::browser_addr).
+    ///
+    /// Mouse, keyboard and touch input in the browser is returned by [`events`](Self::events) as
+    /// the corresponding [`SimulatorEvent`]s. Key names are mapped to SDL keycodes, keys that
+    /// don't have a corresponding keycode are ignored. The web page doesn't generate
+    /// [`SimulatorEvent::Quit`] events.
+    ///
+    /// The server doesn't use any authentication or encryption and should only be bound to
+    /// addresses in trusted networks. WebSocket connections are only accepted from the page
+    /// served by the window, i.e. if the `Origin` header matches the requested host. The page must
+    /// be opened by using `localhost` or the IP address of the server, other host names are
+    /// rejected to prevent DNS rebinding attacks.
+    ///
+    /// A browser window can also be created without code changes by setting the
+    /// `EG_SIMULATOR_BROWSER` environment variable, see [`new`](Self::new).
+    ///
+    /// This method requires the `browser` feature.
+    #[cfg(feature = "browser")]
+    pub fn new_browser<A: ToSocketAddrs>(
+        title: &str,
+        output_settings: &OutputSettings,
+        addr: A,
+    ) -> io::Result<Self> {
+        Ok(Self::with_backend(
+            title,
+            output_settings,
+            Backend::Browser(BrowserWindow::new(title, addr)?),
+        ))
+    }
+
+    fn with_backend(title: &str, output_settings: &OutputSettings, backend: Backend) -> Self {
+        #[cfg(feature = "json")]
+        let input_script = env::var("EG_SIMULATOR_REPLAY").ok().map(|path| {
+            InputScript::load(&path)
+                .unwrap_or_else(|e| panic!("failed to load input script \"{path}\": {e}"))
+        });
+        #[cfg(not(feature = "json"))]
+        let input_script = None;
+
+        let mut window = Self {
             framebuffer: None,
-            #[cfg(feature = "with-sdl")]
-            sdl_window: None,
+            backend,
             title: String::from(title),
-            output_settings: output_settings.clone(),
+            output_settings: *output_settings,
             fps_limiter: FpsLimiter::new(),
+            frame_count: 0,
+            start_time: None,
+            input_script,
+            scripted_events: RefCell::new(VecDeque::new()),
+            #[cfg(feature = "json")]
+            event_recorder: None,
+            #[cfg(feature = "animation")]
+            animation_recorder: None,
+            video_recorder: None,
+        };
+
+        #[cfg(feature = "json")]
+        if let Ok(path) = env::var("EG_SIMULATOR_RECORD") {
+            window
+                .record_events(&path)
+                .unwrap_or_else(|e| panic!("failed to create event recording \"{path}\": {e}"));
+        }
+
+        #[cfg(feature = "animation")]
+        if let Ok(path) = env::var("EG_SIMULATOR_RECORD_GIF") {
+            window
+                .record_animation(&path, &AnimationSettings::from_env())
+                .unwrap_or_else(|e| panic!("failed to create animation \"{path}\": {e}"));
         }
+
+        if let Ok(path) = env::var("EG_SIMULATOR_RECORD_Y4M") {
+            window
+                .record_video(&path)
+                .unwrap_or_else(|e| panic!("failed to create video \"{path}\": {e}"));
+        }
+
+        window
     }
 
     /// Updates the window.
@@ -81,104 +342,171 @@ impl Window {
         C: PixelColor + Into<Rgb888> + From<Rgb888>,
     {
         if let Ok(path) = env::var("EG_SIMULATOR_CHECK") {
+            let expected = SimulatorDisplay::<Rgb888>::load_png(&path);
             let output = display.to_rgb_output_image(&self.output_settings);
 
-            let png_file = BufReader::new(File::open(path).unwrap());
-            let expected = image::load(png_file, image::ImageFormat::Png)
-                .unwrap()
-                .to_rgb8();
-
-            let png_size = Size::new(expected.width(), expected.height());
-
-            assert!(
-                output.size().eq(&png_size),
-                "display dimensions don't match PNG dimensions (display: {}x{}, PNG: {}x{})",
-                output.size().width,
-                output.size().height,
-                png_size.width,
-                png_size.height
-            );
-
-            assert!(
-                output
-                    .as_image_buffer()
-                    .as_raw()
-                    .eq(&expected.as_raw().deref()),
-                "display content doesn't match PNG file",
+            let report = check::check_reference_from_env(
+                expected,
+                &output.to_rgb_display(),
+                Path::new(&path),
             );
-
-            process::exit(0);
+            self.prepare_exit();
+            report.exit();
         }
 
         if let Ok(path) = env::var("EG_SIMULATOR_CHECK_RAW") {
-            let expected = SimulatorDisplay::load_png(path).unwrap();
-
-            assert!(
-                display.size().eq(&expected.size()),
-                "display dimensions don't match PNG dimensions (display: {}x{}, PNG: {}x{})",
-                display.size().width,
-                display.size().height,
-                expected.size().width,
-                expected.size().height
-            );
+            let expected = SimulatorDisplay::<C>::load_png(&path);
 
-            assert!(
-                display.pixels.eq(&expected.pixels),
-                "display content doesn't match PNG file",
+            let report = check::check_reference_from_env(
+                expected.map(|expected| expected.to_rgb_display()),
+                &display.to_rgb_display(),
+                Path::new(&path),
             );
+            self.prepare_exit();
+            report.exit();
+        }
+
+        let png_options = PngOptions::from_env();
+
+        let dump_frame = env::var("EG_SIMULATOR_DUMP_FRAME").ok().map(|frame| {
+            frame
+                .parse::<usize>()
+                .expect("EG_SIMULATOR_DUMP_FRAME must be a frame index")
+        });
+
+        if dump_frame.is_none_or(|frame| frame == self.frame_count) {
+            if let Ok(path) = env::var("EG_SIMULATOR_DUMP") {
+                display
+                    .to_rgb_output_image(&self.output_settings)
+                    .save_png_with_options(path, &png_options)
+                    .unwrap();
+                self.prepare_exit();
+                process::exit(0);
+            }
 
-            process::exit(0);
+            if let Ok(path) = env::var("EG_SIMULATOR_DUMP_RAW") {
+                display
+                    .to_rgb_output_image(&OutputSettings::default())
+                    .save_png_with_options(path, &png_options)
+                    .unwrap();
+                self.prepare_exit();
+                process::exit(0);
+            }
         }
 
-        if let Ok(path) = env::var("EG_SIMULATOR_DUMP") {
+        if let Ok(pattern) = env::var("EG_SIMULATOR_DUMP_FRAMES") {
             display
                 .to_rgb_output_image(&self.output_settings)
-                .save_png(path)
+                .save_png_with_options(frame_path(&pattern, self.frame_count), &png_options)
                 .unwrap();
-            process::exit(0);
         }
 
-        if let Ok(path) = env::var("EG_SIMULATOR_DUMP_RAW") {
+        if let Ok(pattern) = env::var("EG_SIMULATOR_DUMP_RAW_FRAMES") {
             display
                 .to_rgb_output_image(&OutputSettings::default())
-                .save_png(path)
+                .save_png_with_options(frame_path(&pattern, self.frame_count), &png_options)
                 .unwrap();
-            process::exit(0);
         }
 
-        #[cfg(feature = "with-sdl")]
-        {
-            let size = display.output_size(&self.output_settings);
+        let start_time = *self.start_time.get_or_insert_with(Instant::now);
+        let frame_time = start_time.elapsed();
+        let size = display.output_size(&self.output_settings);
 
-            if self.framebuffer.is_none() {
-                self.framebuffer = Some(OutputImage::new(size));
-            }
+        let framebuffer = self
+            .framebuffer
+            .get_or_insert_with(|| OutputImage::new(size));
+        framebuffer.draw_display(display, Point::zero(), &self.output_settings);
 
-            if self.sdl_window.is_none() {
-                self.sdl_window = Some(SdlWindow::new(&self.title, size));
+        match &mut self.backend {
+            #[cfg(feature = "with-sdl")]
+            Backend::Sdl(sdl_window) => sdl_window
+                .get_or_insert_with(|| SdlWindow::new(&self.title, size))
+                .update(framebuffer),
+            Backend::Headless(headless_window) => {
+                headless_window.update(framebuffer, self.frame_count, frame_time)
             }
+            #[cfg(feature = "terminal")]
+            Backend::Terminal(terminal_window) => terminal_window
+                .update(framebuffer)
+                .unwrap_or_else(|e| panic!("failed to update terminal: {e}")),
+            #[cfg(feature = "browser")]
+            Backend::Browser(browser_window) => browser_window
+                .update(framebuffer)
+                .unwrap_or_else(|e| panic!("failed to update browser window: {e}")),
+        }
+
+        #[cfg(feature = "animation")]
+        if let Some(animation_recorder) = &mut self.animation_recorder {
+            animation_recorder
+                .add_frame(framebuffer)
+                .unwrap_or_else(|e| panic!("failed to record animation: {e}"));
+        }
+
+        if let Some(video_recorder) = &mut self.video_recorder {
+            video_recorder
+                .add_frame(framebuffer, self.fps_limiter.max_fps)
+                .unwrap_or_else(|e| panic!("failed to record video: {e}"));
+        }
 
-            let framebuffer = self.framebuffer.as_mut().unwrap();
-            let sdl_window = self.sdl_window.as_mut().unwrap();
+        #[cfg(feature = "json")]
+        if let Some(event_recorder) = &mut self.event_recorder {
+            event_recorder
+                .get_mut()
+                .set_frame(self.frame_count, start_time);
+        }
 
-            framebuffer.draw_display(display, Point::zero(), &self.output_settings);
-            sdl_window.update(framebuffer);
+        if let Some(input_script) = &mut self.input_script {
+            let mut scripted_events = self.scripted_events.borrow_mut();
+            input_script.poll(self.frame_count, frame_time, |event| {
+                scripted_events.push_back(event)
+            });
         }
 
-        self.fps_limiter.sleep();
+        self.frame_count += 1;
+
+        // Headless windows aren't displayed, which makes limiting the frame rate unnecessary.
+        if !matches!(self.backend, Backend::Headless(_)) {
+            self.fps_limiter.sleep();
+        }
+    }
+
+    /// Finishes active recordings and restores the terminal before `update` terminates the
+    /// process.
+    ///
+    /// `process::exit` doesn't run destructors, which would otherwise leave incomplete files or a
+    /// terminal in raw mode.
+    fn prepare_exit(&mut self) {
+        #[cfg(feature = "animation")]
+        if let Err(e) = self.stop_animation_recording() {
+            eprintln!("failed to finish animation: {e}");
+        }
+
+        if let Err(e) = self.stop_video_recording() {
+            eprintln!("failed to finish video: {e}");
+        }
+
+        #[cfg(feature = "terminal")]
+        if let Backend::Terminal(terminal_window) = &mut self.backend {
+            terminal_window.restore();
+        }
     }
 
     /// Shows a static display.
     ///
     /// This methods updates the window once and loops until the simulator window
-    /// is closed.
+    /// is closed. Headless windows return immediately after the update.
     pub fn show_static<C>(&mut self, display: &SimulatorDisplay<C>)
     where
         C: PixelColor + Into<Rgb888> + From<Rgb888>,
     {
         self.update(display);
 
-        #[cfg(feature = "with-sdl")]
+        #[cfg(any(feature = "with-sdl", feature = "terminal", feature = "browser"))]
+        if matches!(self.backend, Backend::Headless(_)) {
+            return;
+        }
+
+        #[cfg(any(feature = "with-sdl", feature = "terminal", feature = "browser"))]
         'running: loop {
             if self.events().any(|e| e == SimulatorEvent::Quit) {
                 break 'running;
@@ -189,21 +517,373 @@ impl Window {
 
     /// Returns an iterator of all captured simulator events.
     ///
+    /// Events played back from an [`InputScript`] are returned before the events that were
+    /// captured by the window. Headless windows only return scripted events.
+    ///
     /// # Panics
     ///
     /// Panics if called before [`update`](Self::update) is called at least
     /// once. Also panics if multiple instances of the iterator are used at the
     /// same time.
-    #[cfg(feature = "with-sdl")]
     pub fn events(&self) -> SimulatorEventsIter<'_> {
-        self.sdl_window
-            .as_ref()
-            .unwrap()
-            .events(&self.output_settings)
+        SimulatorEventsIter {
+            scripted_events: Some(self.scripted_events.borrow_mut()),
+            #[cfg(feature = "with-sdl")]
+            sdl_events: match &self.backend {
+                Backend::Sdl(sdl_window) => {
+                    Some(sdl_window.as_ref().unwrap().events(&self.output_settings))
+                }
+                _ => None,
+            },
+            #[cfg(feature = "terminal")]
+            terminal_events: match &self.backend {
+                Backend::Terminal(terminal_window) => Some(terminal_window.events()),
+                _ => None,
+            },
+            #[cfg(feature = "browser")]
+            browser_events: match &self.backend {
+                Backend::Browser(browser_window) => {
+                    Some(browser_window.events(&self.output_settings))
+                }
+                _ => None,
+            },
+            #[cfg(feature = "json")]
+            recorder: self
+                .event_recorder
+                .as_ref()
+                .map(|event_recorder| event_recorder.borrow_mut()),
+        }
+    }
+
+    /// Sets the input script.
+    ///
+    /// The events in the script are played back after the corresponding frames have been passed
+    /// to [`update`](Self::update) and are returned by [`events`](Self::events) in addition to
+    /// the events captured by the window. A previously set script is replaced.
+    ///
+    /// If the `json` feature is enabled, an input script can also be set by setting the
+    /// `EG_SIMULATOR_REPLAY` environment variable to the path of a script file. See
+    /// [`InputScript`] for a description of the file format.
+    pub fn set_input_script(&mut self, input_script: InputScript) {
+        self.input_script = Some(input_script);
+    }
+
+    /// Records all events returned by [`events`](Self::events) to a file.
+    ///
+    /// Each recorded event is stored together with the index of the last rendered frame and the
+    /// time since the first [`update`](Self::update) call. The recording uses the same format as
+    /// [`InputScript`] and can be replayed by using [`InputScript::load`] or the
+    /// `EG_SIMULATOR_REPLAY` environment variable. During replay the frame index is used to
+    /// determine when an event is played back, which reproduces the recorded session exactly
+    /// as long as the application behaves deterministically.
+    ///
+    /// Recording can also be enabled by setting the `EG_SIMULATOR_RECORD` environment variable
+    /// to the path of the output file. An existing recording is replaced.
+    ///
+    /// Event recording isn't supported by `MultiWindow`.
+    ///
+    /// This method requires the `json` feature.
+    #[cfg(feature = "json")]
+    pub fn record_events<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let mut event_recorder = EventRecorder::new(path)?;
+        if let Some(start_time) = self.start_time {
+            event_recorder.set_frame(self.frame_count.saturating_sub(1), start_time);
+        }
+
+        self.event_recorder = Some(RefCell::new(event_recorder));
+
+        Ok(())
+    }
+
+    /// Records all subsequent frames into an animated GIF or APNG file.
+    ///
+    /// The format is determined by the file extension: `.gif` files are saved as GIF, `.png` and
+    /// `.apng` files as APNG. The frames are recorded using the themed output image, which means
+    /// that recording also works for headless windows. GIF files are limited to 256 colors per
+    /// frame and a time resolution of 10 ms, APNG files are lossless. Consecutive identical frames
+    /// are merged into a single frame. APNG frames are kept in memory and are only written to the
+    /// file when the recording is finished.
+    ///
+    /// Unless a frame timing is set in the settings, headless windows record each frame with a
+    /// fixed duration of `1 / max_fps`, based on the FPS limit at the start of the recording.
+    ///
+    /// The recording is finished when the maximum duration in the settings is reached, when
+    /// [`stop_animation_recording`](Self::stop_animation_recording) is called or when the window
+    /// is dropped. An active recording is finished and replaced.
+    ///
+    /// Recording can also be enabled by setting the `EG_SIMULATOR_RECORD_GIF` environment
+    /// variable to the path of the output file. The maximum duration of this recording can be
+    /// set in seconds by using the `EG_SIMULATOR_RECORD_MAX_DURATION` environment variable.
+    ///
+    /// This method requires the `animation` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_simulator::{
+    ///     AnimationSettings, FrameTiming, OutputSettings, SimulatorDisplay, Window,
+    /// };
+    ///
+    /// let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(16, 8));
+    /// let mut window = Window::new_headless("Animation", &OutputSettings::default());
+    ///
+    /// let settings = AnimationSettings::new().timing(FrameTiming::Fixed(Duration::from_millis(100)));
+    /// window.record_animation("animation.gif", &settings)?;
+    ///
+    /// for x in 0..16 {
+    ///     Pixel(Point::new(x, 0), BinaryColor::On).draw(&mut display).unwrap();
+    ///     window.update(&display);
+    /// }
+    ///
+    /// window.stop_animation_recording()?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(feature = "animation")]
+    pub fn record_animation<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        settings: &AnimationSettings,
+    ) -> io::Result<()> {
+        self.stop_animation_recording()?;
+
+        // Headless windows don't limit the frame rate, which would result in real frame delays
+        // that are too short to be recorded.
+        let mut settings = *settings;
+        if matches!(self.backend, Backend::Headless(_)) {
+            settings = settings
+                .default_timing(FrameTiming::Fixed(self.fps_limiter.desired_loop_duration()));
+        }
+
+        self.animation_recorder = Some(AnimationRecorder::new(path, &settings)?);
+
+        Ok(())
+    }
+
+    /// Stops the active animation recording.
+    ///
+    /// Does nothing if no recording is active.
+    #[cfg(feature = "animation")]
+    pub fn stop_animation_recording(&mut self) -> io::Result<()> {
+        match self.animation_recorder.take() {
+            Some(mut animation_recorder) => animation_recorder.finish(),
+            None => Ok(()),
+        }
+    }
+
+    /// Streams all subsequent frames into an uncompressed YUV4MPEG2 (Y4M) video file.
+    ///
+    /// Unlike `record_animation` no encoding is required, which makes
+    /// this method suitable for long recordings. The output can be converted into other formats by
+    /// using external tools, e.g. `ffmpeg -i video.y4m video.mp4`. If the path is `-` the video is
+    /// streamed to stdout, which allows the output to be piped directly into another tool.
+    ///
+    /// Each call to [`update`](Self::update) adds one frame to the video, using the themed output
+    /// image. The FPS limit of the window at the time of the first frame is stored as the frame
+    /// rate of the video. An active recording is replaced.
+    ///
+    /// Recording can also be enabled by setting the `EG_SIMULATOR_RECORD_Y4M` environment variable
+    /// to the path of the output file or `-`.
+    pub fn record_video<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        self.stop_video_recording()?;
+        self.video_recorder = Some(VideoRecorder::new(path)?);
+
+        Ok(())
+    }
+
+    /// Stops the active video recording.
+    ///
+    /// Does nothing if no recording is active.
+    pub fn stop_video_recording(&mut self) -> io::Result<()> {
+        match self.video_recorder.take() {
+            Some(mut video_recorder) => video_recorder.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the address the HTTP server of a browser window is listening on.
+    ///
+    /// Returns `None` if the window isn't a browser window.
+    ///
+    /// This method requires the `browser` feature.
+    #[cfg(feature = "browser")]
+    pub fn browser_addr(&self) -> Option<SocketAddr> {
+        match &self.backend {
+            Backend::Browser(browser_window) => Some(browser_window.local_addr()),
+            _ => None,
+        }
+    }
+
+    /// Sets the encoding of the frames that are sent to the browser.
+    ///
+    /// The default encoding is [`BrowserFrameEncoding::Png`]. This setting has no effect for
+    /// non browser windows.
+    ///
+    /// This method requires the `browser` feature.
+    #[cfg(feature = "browser")]
+    pub fn set_browser_frame_encoding(&mut self, encoding: BrowserFrameEncoding) {
+        if let Backend::Browser(browser_window) = &mut self.backend {
+            browser_window.set_encoding(encoding);
+        }
     }
 
     /// Sets the FPS limit of the window.
+    ///
+    /// Headless windows don't wait between frames, but the limit is still used as the frame rate
+    /// of video recordings.
     pub fn set_max_fps(&mut self, max_fps: u32) {
         self.fps_limiter.max_fps = max_fps;
     }
+
+    /// Returns the number of frames that were passed to [`update`](Self::update).
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// Sets the maximum number of frames kept in the frame history of a headless window.
+    ///
+    /// If the history is full the oldest frame is discarded when a new frame is added. Setting the
+    /// length to `0` disables the frame history. This setting has no effect for non headless
+    /// windows.
+    pub fn set_frame_history_len(&mut self, len: usize) {
+        match &mut self.backend {
+            Backend::Headless(headless_window) => headless_window.set_history_len(len),
+            #[cfg(any(feature = "with-sdl", feature = "terminal", feature = "browser"))]
+            _ => {}
+        }
+    }
+
+    /// Returns an iterator over the frames in the frame history.
+    ///
+    /// The frames are ordered from oldest to newest. Only headless windows keep a frame history,
+    /// for other windows the returned iterator is always empty.
+    pub fn frames(&self) -> impl Iterator<Item = &Frame> {
+        match &self.backend {
+            Backend::Headless(headless_window) => Some(headless_window.frames()),
+            #[cfg(any(feature = "with-sdl", feature = "terminal", feature = "browser"))]
+            _ => None,
+        }
+        .into_iter()
+        .flatten()
+    }
+
+    /// Returns the most recently rendered frame of a headless window.
+    ///
+    /// Returns `None` if no frame was rendered yet, the frame history is disabled or the window
+    /// isn't a headless window.
+    pub fn last_frame(&self) -> Option<&Frame> {
+        match &self.backend {
+            Backend::Headless(headless_window) => headless_window.last_frame(),
+            #[cfg(any(feature = "with-sdl", feature = "terminal", feature = "browser"))]
+            _ => None,
+        }
+    }
+}
+
+/// Replaces the frame number placeholder in a path pattern.
+///
+/// The pattern must contain a printf style `%d` placeholder, which can optionally include a zero
+/// padded width, e.g. `%05d`.
+///
+/// # Panics
+///
+/// Panics if the pattern doesn't contain a valid placeholder.
+fn frame_path(pattern: &str, frame: usize) -> String {
+    let invalid_pattern = || -> ! {
+        panic!("frame path pattern must contain a %d or %0<width>d placeholder: \"{pattern}\"")
+    };
+
+    let Some(start) = pattern.find('%') else {
+        invalid_pattern()
+    };
+    let Some(len) = pattern[start..].find('d') else {
+        invalid_pattern()
+    };
+
+    let width = &pattern[start + 1..start + len];
+    let width = if width.is_empty() {
+        0
+    } else if width.starts_with('0') {
+        width.parse::<usize>().unwrap_or_else(|_| invalid_pattern())
+    } else {
+        invalid_pattern()
+    };
+
+    format!(
+        "{}{:0width$}{}",
+        &pattern[..start],
+        frame,
+        &pattern[start + len + 1..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_path_placeholder() {
+        assert_eq!(frame_path("frame_%d.png", 7), "frame_7.png");
+        assert_eq!(frame_path("out/frame_%05d.png", 42), "out/frame_00042.png");
+        assert_eq!(frame_path("%03d", 1234), "1234");
+    }
+
+    #[test]
+    #[should_panic(expected = "frame path pattern must contain a %d or %0<width>d placeholder")]
+    fn frame_path_without_placeholder() {
+        frame_path("frame.png", 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "frame path pattern must contain a %d or %0<width>d placeholder")]
+    fn frame_path_invalid_width() {
+        frame_path("frame_%5d.png", 0);
+    }
+
+    #[test]
+    fn headless_ignores_fps_limit() {
+        let display = SimulatorDisplay::<Rgb888>::new(Size::new(4, 4));
+        let mut window = Window::new_headless("Test", &OutputSettings::default());
+        window.set_max_fps(1);
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            window.update(&display);
+        }
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    #[cfg(feature = "animation")]
+    fn headless_animation_recording() {
+        let dir = crate::test_utils::TempDir::new("headless-animation");
+        let path = dir.join("headless.gif");
+
+        let mut display = SimulatorDisplay::<Rgb888>::new(Size::new(10, 10));
+        let mut window = Window::new_headless("Test", &OutputSettings::default());
+        window.set_max_fps(25);
+        window
+            .record_animation(&path, &AnimationSettings::new())
+            .unwrap();
+
+        for i in 0..100 {
+            Pixel(Point::new(i % 10, i / 10), Rgb888::WHITE)
+                .draw(&mut display)
+                .unwrap();
+            window.update(&display);
+        }
+        window.stop_animation_recording().unwrap();
+
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(std::fs::File::open(&path).unwrap())
+            .unwrap();
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+
+        assert_eq!(delays, [4; 100]);
+    }
 }
@@ -1,10 +1,16 @@
-use std::collections::HashMap;
+#[cfg(feature = "browser")]
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::{collections::HashMap, env, io, path::Path};
 
 use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
 
+#[cfg(feature = "animation")]
+use crate::{window::AnimationRecorder, AnimationSettings};
+#[cfg(feature = "browser")]
+use crate::{window::BrowserWindow, BrowserFrameEncoding};
 use crate::{
-    window::{sdl_window::SimulatorEventsIter, FpsLimiter, SdlWindow},
-    OutputImage, OutputSettings, SimulatorDisplay,
+    window::{FpsLimiter, SdlWindow, VideoRecorder},
+    OutputImage, OutputSettings, SimulatorDisplay, SimulatorEventsIter,
 };
 
 /// Simulator window with support for multiple displays.
@@ -17,11 +23,24 @@ use crate::{
 /// To determine if the mouse pointer is over one of the displays the
 /// [`translate_mouse_position`](Self::translate_mouse_position) can be used to
 /// translate window coordinates into display coordinates.
+///
+/// Input scripts and event recordings aren't supported by multi windows. The
+/// `EG_SIMULATOR_REPLAY` and `EG_SIMULATOR_RECORD` environment variables are
+/// only used by [`Window`](crate::Window).
 pub struct MultiWindow {
     sdl_window: SdlWindow,
     framebuffer: OutputImage<Rgb888>,
     displays: HashMap<usize, DisplaySettings>,
     fps_limiter: FpsLimiter,
+    #[cfg(feature = "animation")]
+    animation_recorder: Option<AnimationRecorder>,
+    video_recorder: Option<VideoRecorder>,
+    #[cfg(feature = "browser")]
+    title: String,
+    #[cfg(feature = "browser")]
+    browser_window: Option<BrowserWindow>,
+    #[cfg(feature = "browser")]
+    browser_frame_encoding: BrowserFrameEncoding,
 }
 
 impl MultiWindow {
@@ -33,12 +52,52 @@ impl MultiWindow {
 
         sdl_window.update(&framebuffer);
 
-        Self {
-            sdl_window: sdl_window,
+        let mut window = Self {
+            sdl_window,
             framebuffer,
             displays: HashMap::new(),
             fps_limiter: FpsLimiter::new(),
+            #[cfg(feature = "animation")]
+            animation_recorder: None,
+            video_recorder: None,
+            #[cfg(feature = "browser")]
+            title: String::from(title),
+            #[cfg(feature = "browser")]
+            browser_window: None,
+            #[cfg(feature = "browser")]
+            browser_frame_encoding: BrowserFrameEncoding::default(),
+        };
+
+        #[cfg(feature = "animation")]
+        if let Ok(path) = env::var("EG_SIMULATOR_RECORD_GIF") {
+            window
+                .record_animation(&path, &AnimationSettings::from_env())
+                .unwrap_or_else(|e| panic!("failed to create animation \"{path}\": {e}"));
+        }
+
+        if let Ok(path) = env::var("EG_SIMULATOR_RECORD_Y4M") {
+            window
+                .record_video(&path)
+                .unwrap_or_else(|e| panic!("failed to create video \"{path}\": {e}"));
+        }
+
+        #[cfg(feature = "browser")]
+        if let Ok(addr) = env::var("EG_SIMULATOR_BROWSER") {
+            if let Ok(encoding) = env::var("EG_SIMULATOR_BROWSER_ENCODING") {
+                let encoding = encoding
+                    .parse()
+                    .unwrap_or_else(|_| panic!("EG_SIMULATOR_BROWSER_ENCODING must be png or rgb"));
+                window.set_browser_frame_encoding(encoding);
+            }
+
+            let addr = window
+                .serve_browser(addr.as_str())
+                .unwrap_or_else(|e| panic!("failed to start browser window on \"{addr}\": {e}"));
+
+            eprintln!("Serving \"{title}\" at http://{addr}/");
         }
+
+        window
     }
 
     /// Adds a display to the window.
@@ -52,7 +111,7 @@ impl MultiWindow {
             display.id,
             DisplaySettings {
                 offset,
-                output_settings: output_settings.clone(),
+                output_settings: *output_settings,
             },
         );
     }
@@ -90,9 +149,71 @@ impl MultiWindow {
     pub fn flush(&mut self) {
         self.sdl_window.update(&self.framebuffer);
 
+        #[cfg(feature = "browser")]
+        if let Some(browser_window) = &mut self.browser_window {
+            browser_window
+                .update(&self.framebuffer)
+                .unwrap_or_else(|e| panic!("failed to update browser window: {e}"));
+        }
+
+        #[cfg(feature = "animation")]
+        if let Some(animation_recorder) = &mut self.animation_recorder {
+            animation_recorder
+                .add_frame(&self.framebuffer)
+                .unwrap_or_else(|e| panic!("failed to record animation: {e}"));
+        }
+
+        if let Some(video_recorder) = &mut self.video_recorder {
+            video_recorder
+                .add_frame(&self.framebuffer, self.fps_limiter.max_fps)
+                .unwrap_or_else(|e| panic!("failed to record video: {e}"));
+        }
+
         self.fps_limiter.sleep();
     }
 
+    /// Streams the window content to web browsers.
+    ///
+    /// The window is mirrored to all browsers that open the web page served on the given address,
+    /// in addition to the SDL window. Input events from the browsers are returned by
+    /// [`events`](Self::events) together with the events of the SDL window. See
+    /// [`Window::new_browser`](crate::Window::new_browser) for more details. Returns the address the
+    /// HTTP server is listening on. An active server is replaced.
+    ///
+    /// Streaming can also be enabled by setting the `EG_SIMULATOR_BROWSER` environment variable to
+    /// a socket address. The frame encoding can be selected by setting
+    /// `EG_SIMULATOR_BROWSER_ENCODING` to `png` or `rgb`.
+    ///
+    /// This method requires the `browser` feature.
+    #[cfg(feature = "browser")]
+    pub fn serve_browser<A: ToSocketAddrs>(&mut self, addr: A) -> io::Result<SocketAddr> {
+        self.browser_window = None;
+
+        let mut browser_window = BrowserWindow::new(&self.title, addr)?;
+        browser_window.set_encoding(self.browser_frame_encoding);
+        browser_window.update(&self.framebuffer)?;
+        let addr = browser_window.local_addr();
+        self.browser_window = Some(browser_window);
+
+        Ok(addr)
+    }
+
+    /// Sets the encoding of the frames that are sent to the browser.
+    ///
+    /// The default encoding is [`BrowserFrameEncoding::Png`]. The encoding is used by the active
+    /// server and by servers that are started later by using
+    /// [`serve_browser`](Self::serve_browser).
+    ///
+    /// This method requires the `browser` feature.
+    #[cfg(feature = "browser")]
+    pub fn set_browser_frame_encoding(&mut self, encoding: BrowserFrameEncoding) {
+        self.browser_frame_encoding = encoding;
+
+        if let Some(browser_window) = &mut self.browser_window {
+            browser_window.set_encoding(encoding);
+        }
+    }
+
     /// Returns an iterator of all captured simulator events.
     ///
     /// The coordinates in mouse events are in raw window coordinates, use
@@ -103,7 +224,19 @@ impl MultiWindow {
     ///
     /// Panics if multiple instances of the iterator are used at the same time.
     pub fn events(&self) -> SimulatorEventsIter<'_> {
-        self.sdl_window.events(&crate::OutputSettings::default())
+        SimulatorEventsIter {
+            scripted_events: None,
+            sdl_events: Some(self.sdl_window.events(&OutputSettings::default())),
+            #[cfg(feature = "terminal")]
+            terminal_events: None,
+            #[cfg(feature = "browser")]
+            browser_events: self
+                .browser_window
+                .as_ref()
+                .map(|browser_window| browser_window.events(&OutputSettings::default())),
+            #[cfg(feature = "json")]
+            recorder: None,
+        }
     }
 
     /// Translate a mouse position into display coordinates.
@@ -125,6 +258,56 @@ impl MultiWindow {
         display.bounding_box().contains(p).then_some(p)
     }
 
+    /// Records all subsequent frames into an animated GIF or APNG file.
+    ///
+    /// A frame is recorded each time [`flush`](Self::flush) is called. See
+    /// [`Window::record_animation`](crate::Window::record_animation) for more information.
+    ///
+    /// This method requires the `animation` feature.
+    #[cfg(feature = "animation")]
+    pub fn record_animation<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        settings: &AnimationSettings,
+    ) -> io::Result<()> {
+        self.stop_animation_recording()?;
+        self.animation_recorder = Some(AnimationRecorder::new(path, settings)?);
+
+        Ok(())
+    }
+
+    /// Stops the active animation recording.
+    ///
+    /// Does nothing if no recording is active.
+    #[cfg(feature = "animation")]
+    pub fn stop_animation_recording(&mut self) -> io::Result<()> {
+        match self.animation_recorder.take() {
+            Some(mut animation_recorder) => animation_recorder.finish(),
+            None => Ok(()),
+        }
+    }
+
+    /// Streams all subsequent frames into an uncompressed YUV4MPEG2 (Y4M) video file.
+    ///
+    /// A frame is added each time [`flush`](Self::flush) is called. See
+    /// [`Window::record_video`](crate::Window::record_video) for more information.
+    pub fn record_video<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        self.stop_video_recording()?;
+        self.video_recorder = Some(VideoRecorder::new(path)?);
+
+        Ok(())
+    }
+
+    /// Stops the active video recording.
+    ///
+    /// Does nothing if no recording is active.
+    pub fn stop_video_recording(&mut self) -> io::Result<()> {
+        match self.video_recorder.take() {
+            Some(mut video_recorder) => video_recorder.flush(),
+            None => Ok(()),
+        }
+    }
+
     /// Sets the FPS limit of the window.
     pub fn set_max_fps(&mut self, max_fps: u32) {
         self.fps_limiter.max_fps = max_fps;
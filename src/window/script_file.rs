@@ -0,0 +1,415 @@
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+use embedded_graphics::prelude::Point;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    sdl2::{Keycode, Mod, MouseButton, MouseWheelDirection},
+    EventTrigger, SimulatorEvent,
+};
+
+/// Single line in a script file.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ScriptEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    frame: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    time: Option<f64>,
+    event: EventRecord,
+}
+
+impl ScriptEntry {
+    pub(crate) fn new(frame: Option<usize>, time: Option<Duration>, event: SimulatorEvent) -> Self {
+        Self {
+            frame,
+            time: time.map(|time| time.as_secs_f64()),
+            event: event.into(),
+        }
+    }
+
+    /// Writes the entry as a single line.
+    pub(crate) fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        serde_json::to_writer(&mut writer, self)?;
+        writeln!(writer)
+    }
+
+    /// Returns the trigger and event of this entry.
+    pub(crate) fn into_parts(self) -> io::Result<(EventTrigger, SimulatorEvent)> {
+        Ok((self.trigger()?, self.event.try_into()?))
+    }
+
+    fn trigger(&self) -> io::Result<EventTrigger> {
+        match (self.frame, self.time) {
+            (Some(frame), _) => Ok(EventTrigger::Frame(frame)),
+            (None, Some(time)) => Duration::try_from_secs_f64(time)
+                .map(EventTrigger::Time)
+                .map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "invalid time in script entry: {time} \
+                             (expected a non-negative number of seconds)"
+                        ),
+                    )
+                }),
+            (None, None) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "script entry must contain a frame or time field",
+            )),
+        }
+    }
+}
+
+/// Serializable representation of a [`SimulatorEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum EventRecord {
+    KeyUp {
+        keycode: i32,
+        keymod: u16,
+        repeat: bool,
+    },
+    KeyDown {
+        keycode: i32,
+        keymod: u16,
+        repeat: bool,
+    },
+    MouseButtonUp {
+        mouse_btn: MouseButtonRecord,
+        point: (i32, i32),
+    },
+    MouseButtonDown {
+        mouse_btn: MouseButtonRecord,
+        point: (i32, i32),
+    },
+    MouseWheel {
+        scroll_delta: (i32, i32),
+        direction: u32,
+    },
+    MouseMove {
+        point: (i32, i32),
+    },
+    TouchStarted {
+        id: i64,
+        point: (i32, i32),
+        pressure: u32,
+    },
+    TouchMoved {
+        id: i64,
+        point: (i32, i32),
+        pressure: u32,
+    },
+    TouchEnded {
+        id: i64,
+        point: (i32, i32),
+        pressure: u32,
+    },
+    TouchCancelled {
+        id: i64,
+        point: (i32, i32),
+        pressure: u32,
+    },
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MouseButtonRecord {
+    Unknown,
+    Left,
+    Middle,
+    Right,
+    X1,
+    X2,
+}
+
+impl From<MouseButton> for MouseButtonRecord {
+    fn from(button: MouseButton) -> Self {
+        match button {
+            MouseButton::Unknown => Self::Unknown,
+            MouseButton::Left => Self::Left,
+            MouseButton::Middle => Self::Middle,
+            MouseButton::Right => Self::Right,
+            MouseButton::X1 => Self::X1,
+            MouseButton::X2 => Self::X2,
+        }
+    }
+}
+
+impl From<MouseButtonRecord> for MouseButton {
+    fn from(button: MouseButtonRecord) -> Self {
+        match button {
+            MouseButtonRecord::Unknown => Self::Unknown,
+            MouseButtonRecord::Left => Self::Left,
+            MouseButtonRecord::Middle => Self::Middle,
+            MouseButtonRecord::Right => Self::Right,
+            MouseButtonRecord::X1 => Self::X1,
+            MouseButtonRecord::X2 => Self::X2,
+        }
+    }
+}
+
+fn point_to_tuple(point: Point) -> (i32, i32) {
+    (point.x, point.y)
+}
+
+fn tuple_to_point((x, y): (i32, i32)) -> Point {
+    Point::new(x, y)
+}
+
+fn keycode_from_i32(keycode: i32) -> io::Result<Keycode> {
+    Keycode::from_i32(keycode).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid keycode: {keycode}"),
+        )
+    })
+}
+
+impl From<SimulatorEvent> for EventRecord {
+    fn from(event: SimulatorEvent) -> Self {
+        match event {
+            SimulatorEvent::KeyUp {
+                keycode,
+                keymod,
+                repeat,
+            } => Self::KeyUp {
+                keycode: keycode.into_i32(),
+                keymod: keymod.bits(),
+                repeat,
+            },
+            SimulatorEvent::KeyDown {
+                keycode,
+                keymod,
+                repeat,
+            } => Self::KeyDown {
+                keycode: keycode.into_i32(),
+                keymod: keymod.bits(),
+                repeat,
+            },
+            SimulatorEvent::MouseButtonUp { mouse_btn, point } => Self::MouseButtonUp {
+                mouse_btn: mouse_btn.into(),
+                point: point_to_tuple(point),
+            },
+            SimulatorEvent::MouseButtonDown { mouse_btn, point } => Self::MouseButtonDown {
+                mouse_btn: mouse_btn.into(),
+                point: point_to_tuple(point),
+            },
+            SimulatorEvent::MouseWheel {
+                scroll_delta,
+                direction,
+            } => Self::MouseWheel {
+                scroll_delta: point_to_tuple(scroll_delta),
+                direction: direction.to_ll(),
+            },
+            SimulatorEvent::MouseMove { point } => Self::MouseMove {
+                point: point_to_tuple(point),
+            },
+            SimulatorEvent::TouchStarted {
+                id,
+                point,
+                pressure,
+            } => Self::TouchStarted {
+                id,
+                point: point_to_tuple(point),
+                pressure,
+            },
+            SimulatorEvent::TouchMoved {
+                id,
+                point,
+                pressure,
+            } => Self::TouchMoved {
+                id,
+                point: point_to_tuple(point),
+                pressure,
+            },
+            SimulatorEvent::TouchEnded {
+                id,
+                point,
+                pressure,
+            } => Self::TouchEnded {
+                id,
+                point: point_to_tuple(point),
+                pressure,
+            },
+            SimulatorEvent::TouchCancelled {
+                id,
+                point,
+                pressure,
+            } => Self::TouchCancelled {
+                id,
+                point: point_to_tuple(point),
+                pressure,
+            },
+            SimulatorEvent::Quit => Self::Quit,
+        }
+    }
+}
+
+impl TryFrom<EventRecord> for SimulatorEvent {
+    type Error = io::Error;
+
+    fn try_from(record: EventRecord) -> io::Result<Self> {
+        Ok(match record {
+            EventRecord::KeyUp {
+                keycode,
+                keymod,
+                repeat,
+            } => Self::KeyUp {
+                keycode: keycode_from_i32(keycode)?,
+                keymod: Mod::from_bits_truncate(keymod),
+                repeat,
+            },
+            EventRecord::KeyDown {
+                keycode,
+                keymod,
+                repeat,
+            } => Self::KeyDown {
+                keycode: keycode_from_i32(keycode)?,
+                keymod: Mod::from_bits_truncate(keymod),
+                repeat,
+            },
+            EventRecord::MouseButtonUp { mouse_btn, point } => Self::MouseButtonUp {
+                mouse_btn: mouse_btn.into(),
+                point: tuple_to_point(point),
+            },
+            EventRecord::MouseButtonDown { mouse_btn, point } => Self::MouseButtonDown {
+                mouse_btn: mouse_btn.into(),
+                point: tuple_to_point(point),
+            },
+            EventRecord::MouseWheel {
+                scroll_delta,
+                direction,
+            } => Self::MouseWheel {
+                scroll_delta: tuple_to_point(scroll_delta),
+                direction: MouseWheelDirection::from_ll(direction),
+            },
+            EventRecord::MouseMove { point } => Self::MouseMove {
+                point: tuple_to_point(point),
+            },
+            EventRecord::TouchStarted {
+                id,
+                point,
+                pressure,
+            } => Self::TouchStarted {
+                id,
+                point: tuple_to_point(point),
+                pressure,
+            },
+            EventRecord::TouchMoved {
+                id,
+                point,
+                pressure,
+            } => Self::TouchMoved {
+                id,
+                point: tuple_to_point(point),
+                pressure,
+            },
+            EventRecord::TouchEnded {
+                id,
+                point,
+                pressure,
+            } => Self::TouchEnded {
+                id,
+                point: tuple_to_point(point),
+                pressure,
+            },
+            EventRecord::TouchCancelled {
+                id,
+                point,
+                pressure,
+            } => Self::TouchCancelled {
+                id,
+                point: tuple_to_point(point),
+                pressure,
+            },
+            EventRecord::Quit => Self::Quit,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_record_round_trip() {
+        let events = [
+            SimulatorEvent::KeyDown {
+                keycode: Keycode::LEFT,
+                keymod: Mod::LSHIFTMOD | Mod::RCTRLMOD,
+                repeat: true,
+            },
+            SimulatorEvent::KeyUp {
+                keycode: Keycode::A,
+                keymod: Mod::NOMOD,
+                repeat: false,
+            },
+            SimulatorEvent::MouseButtonDown {
+                mouse_btn: MouseButton::X2,
+                point: Point::new(-1, 2),
+            },
+            SimulatorEvent::MouseWheel {
+                scroll_delta: Point::new(0, -3),
+                direction: MouseWheelDirection::Flipped,
+            },
+            SimulatorEvent::TouchCancelled {
+                id: -7,
+                point: Point::new(5, 6),
+                pressure: 42,
+            },
+            SimulatorEvent::Quit,
+        ];
+
+        for event in events {
+            let line = serde_json::to_string(&ScriptEntry::new(Some(1), None, event)).unwrap();
+            let entry: ScriptEntry = serde_json::from_str(&line).unwrap();
+
+            assert_eq!(entry.trigger().unwrap(), EventTrigger::Frame(1));
+            assert_eq!(SimulatorEvent::try_from(entry.event).unwrap(), event);
+        }
+    }
+
+    #[test]
+    fn parse_entry() {
+        let entry: ScriptEntry = serde_json::from_str(
+            r#"{"time":0.25,"event":{"type":"mouse_button_up","mouse_btn":"left","point":[1,2]}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            entry.trigger().unwrap(),
+            EventTrigger::Time(Duration::from_millis(250))
+        );
+        assert_eq!(
+            SimulatorEvent::try_from(entry.event).unwrap(),
+            SimulatorEvent::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                point: Point::new(1, 2)
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_trigger() {
+        let error = |json: &str| {
+            serde_json::from_str::<ScriptEntry>(json)
+                .unwrap()
+                .trigger()
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            error(r#"{"time":-1.5,"event":{"type":"quit"}}"#),
+            "invalid time in script entry: -1.5 (expected a non-negative number of seconds)"
+        );
+        assert_eq!(
+            error(r#"{"event":{"type":"quit"}}"#),
+            "script entry must contain a frame or time field"
+        );
+    }
+}
@@ -6,102 +6,14 @@ use embedded_graphics::{
 };
 use sdl2::{
     event::Event,
-    keyboard::{Keycode, Mod},
-    mouse::{MouseButton, MouseWheelDirection},
+    keyboard::Keycode,
     pixels::PixelFormatEnum,
     render::{Canvas, Texture, TextureCreator},
     video::WindowContext,
     EventPump,
 };
 
-use crate::{OutputImage, OutputSettings};
-
-/// A derivation of [`sdl2::event::Event`] mapped to embedded-graphics coordinates
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum SimulatorEvent {
-    /// A keypress event, fired on keyUp
-    KeyUp {
-        /// The key being released
-        keycode: Keycode,
-        /// Any modifier being held at the time of keyup
-        keymod: Mod,
-        /// Whether the key is repeating
-        repeat: bool,
-    },
-    /// A keypress event, fired on keyDown
-    KeyDown {
-        /// The key being pressed
-        keycode: Keycode,
-        /// Any modifier being held at the time of keydown
-        keymod: Mod,
-        /// Whether the key is repeating
-        repeat: bool,
-    },
-    /// A mouse click event, fired on mouseUp
-    MouseButtonUp {
-        /// The mouse button being released
-        mouse_btn: MouseButton,
-        /// The location of the mouse in Simulator coordinates
-        point: Point,
-    },
-    /// A mouse click event, fired on mouseDown
-    MouseButtonDown {
-        /// The mouse button being pressed
-        mouse_btn: MouseButton,
-        /// The location of the mouse in Simulator coordinates
-        point: Point,
-    },
-    /// A mouse wheel event
-    MouseWheel {
-        /// The scroll wheel delta in the x and y direction
-        scroll_delta: Point,
-        /// The directionality of the scroll (normal or flipped)
-        direction: MouseWheelDirection,
-    },
-    /// Mouse move event
-    MouseMove {
-        /// The current mouse position
-        point: Point,
-    },
-    /// Touch started event
-    TouchStarted {
-        /// The ID of the finger that started the touch
-        id: i64,
-        /// The location of the touch in Simulator coordinates
-        point: Point,
-        /// The pressure of the touch.
-        pressure: u32,
-    },
-    /// Touch moved event
-    TouchMoved {
-        /// The ID of the finger that moved
-        id: i64,
-        /// The location of the touch in Simulator coordinates
-        point: Point,
-        /// The pressure of the touch.
-        pressure: u32,
-    },
-    /// Touch ended event
-    TouchEnded {
-        /// The ID of the finger that ended the touch
-        id: i64,
-        /// The location of the touch in Simulator coordinates
-        point: Point,
-        /// The pressure of the touch.
-        pressure: u32,
-    },
-    /// Touch cancelled event
-    TouchCancelled {
-        /// The ID of the finger whose touch was cancelled
-        id: i64,
-        /// The location of the touch in Simulator coordinates
-        point: Point,
-        /// The pressure of the touch.
-        pressure: u32,
-    },
-    /// An exit event
-    Quit,
-}
+use crate::{OutputImage, OutputSettings, SimulatorEvent};
 
 fn scale_touch_pos(x: f32, y: f32, size: Size) -> Point {
     Point::new(
@@ -110,20 +22,14 @@ fn scale_touch_pos(x: f32, y: f32, size: Size) -> Point {
     )
 }
 
-/// Iterator over simulator events.
-///
-/// See [`Window::events`](crate::Window::events) and
-/// [`MultiWindow::events`](crate::MultiWindow::events) for more details.
-pub struct SimulatorEventsIter<'a> {
+pub struct SdlEvents<'a> {
     event_pump: RefMut<'a, EventPump>,
     output_settings: OutputSettings,
     size: Size,
 }
 
-impl Iterator for SimulatorEventsIter<'_> {
-    type Item = SimulatorEvent;
-
-    fn next(&mut self) -> Option<Self::Item> {
+impl SdlEvents<'_> {
+    pub fn poll(&mut self) -> Option<SimulatorEvent> {
         while let Some(event) = self.event_pump.poll_event() {
             match event {
                 Event::Quit { .. }
@@ -295,11 +201,11 @@ impl SdlWindow {
     }
 
     /// Handle events
-    /// Return an iterator of all captured SimulatorEvent
-    pub fn events(&self, output_settings: &OutputSettings) -> SimulatorEventsIter<'_> {
-        SimulatorEventsIter {
+    /// Return the captured SDL events
+    pub fn events(&self, output_settings: &OutputSettings) -> SdlEvents<'_> {
+        SdlEvents {
             event_pump: self.event_pump.borrow_mut(),
-            output_settings: output_settings.clone(),
+            output_settings: *output_settings,
             size: self.size,
         }
     }
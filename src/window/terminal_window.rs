@@ -0,0 +1,763 @@
+#[cfg(unix)]
+use std::sync::OnceLock;
+use std::{
+    cell::{RefCell, RefMut},
+    collections::{HashMap, VecDeque},
+    io::{self, Write},
+    str::FromStr,
+};
+
+use base64::Engine;
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+use crate::{
+    output_image::OutputImage,
+    parse_error::ParseError,
+    sdl2::{Keycode, Mod},
+    SimulatorEvent,
+};
+
+/// Graphics protocol used by terminal windows.
+///
+/// See [`Window::new_terminal`](crate::Window::new_terminal) for more details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TerminalGraphics {
+    /// Unicode half block characters with 24-bit colors.
+    ///
+    /// Each character cell displays two vertically stacked pixels. This mode is supported by most
+    /// terminal emulators.
+    #[default]
+    HalfBlocks,
+    /// Sixel graphics.
+    ///
+    /// Images are limited to 256 colors. If an image contains more colors, the colors are reduced
+    /// to a fixed palette.
+    Sixel,
+    /// Kitty graphics protocol.
+    Kitty,
+}
+
+impl FromStr for TerminalGraphics {
+    type Err = ParseError;
+
+    /// Parses a graphics protocol.
+    ///
+    /// Valid values are `half-blocks`, `sixel` and `kitty`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "half-blocks" => Self::HalfBlocks,
+            "sixel" => Self::Sixel,
+            "kitty" => Self::Kitty,
+            _ => return Err(ParseError::new(s, "half-blocks, sixel or kitty")),
+        })
+    }
+}
+
+/// Escape sequence that resets the colors, shows the cursor and leaves the alternate screen.
+const RESET_TERMINAL: &[u8] = b"\x1b[0m\x1b[?25h\x1b[?1049l";
+
+/// Window that renders into the terminal.
+pub struct TerminalWindow {
+    graphics: TerminalGraphics,
+    title: String,
+    initialized: bool,
+    #[cfg(unix)]
+    raw_mode: Option<RawMode>,
+    last_frame: Option<Box<[u8]>>,
+    last_terminal_size: Option<(u16, u16)>,
+    events: RefCell<VecDeque<SimulatorEvent>>,
+}
+
+impl TerminalWindow {
+    pub fn new(title: &str, graphics: TerminalGraphics) -> Self {
+        Self {
+            graphics,
+            title: String::from(title),
+            initialized: false,
+            #[cfg(unix)]
+            raw_mode: None,
+            last_frame: None,
+            last_terminal_size: None,
+            events: RefCell::new(VecDeque::new()),
+        }
+    }
+
+    /// Redraws the terminal if the framebuffer has changed.
+    pub fn update(&mut self, framebuffer: &OutputImage<Rgb888>) -> io::Result<()> {
+        let mut output = Vec::new();
+
+        if !self.initialized {
+            // Stdin isn't necessarily a terminal, in which case no key events are generated.
+            #[cfg(unix)]
+            {
+                self.raw_mode = RawMode::enable().ok();
+            }
+
+            // Switch to the alternate screen, hide the cursor and set the window title.
+            let _ = write!(output, "\x1b[?1049h\x1b[?25l\x1b]0;{}\x07", self.title);
+            self.initialized = true;
+        }
+
+        let terminal_size = terminal_size();
+        if self.last_terminal_size != terminal_size {
+            output.extend_from_slice(b"\x1b[2J");
+        } else if self.last_frame.as_deref() == Some(&framebuffer.data[..]) {
+            return Ok(());
+        }
+        output.extend_from_slice(b"\x1b[H");
+
+        match self.graphics {
+            TerminalGraphics::HalfBlocks => {
+                render_half_blocks(framebuffer, terminal_size, &mut output)
+            }
+            TerminalGraphics::Sixel => render_sixel(framebuffer, &mut output),
+            TerminalGraphics::Kitty => render_kitty(framebuffer, &mut output),
+        }
+
+        self.last_frame = Some(framebuffer.data.clone());
+        self.last_terminal_size = terminal_size;
+
+        // The frame is written at once to prevent tearing.
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(&output)?;
+        stdout.flush()
+    }
+
+    /// Returns the queue of events, after reading all pending input from the terminal.
+    pub fn events(&self) -> RefMut<'_, VecDeque<SimulatorEvent>> {
+        let mut events = self.events.borrow_mut();
+
+        #[cfg(unix)]
+        if self.raw_mode.is_some() {
+            parse_input(&read_input(), &mut events);
+        }
+
+        events
+    }
+
+    /// Restores the original terminal state.
+    ///
+    /// The terminal is initialized again by the next call to `update`.
+    pub fn restore(&mut self) {
+        if !self.initialized {
+            return;
+        }
+        self.initialized = false;
+        self.last_frame = None;
+
+        #[cfg(unix)]
+        {
+            self.raw_mode = None;
+        }
+
+        let mut stdout = io::stdout().lock();
+        if self.graphics == TerminalGraphics::Kitty {
+            let _ = stdout.write_all(b"\x1b_Ga=d,q=2\x1b\\");
+        }
+        let _ = stdout.write_all(RESET_TERMINAL);
+        let _ = stdout.flush();
+    }
+}
+
+impl Drop for TerminalWindow {
+    fn drop(&mut self) {
+        self.restore();
+    }
+}
+
+/// Terminal attributes before raw mode was enabled, which are restored by the signal handler.
+#[cfg(unix)]
+static ORIGINAL_TERMIOS: OnceLock<libc::termios> = OnceLock::new();
+
+/// Signals that terminate the process after the terminal was restored.
+#[cfg(unix)]
+const RESTORE_SIGNALS: [libc::c_int; 2] = [libc::SIGINT, libc::SIGTERM];
+
+/// Puts the terminal into raw mode and restores the original mode when dropped.
+///
+/// Only canonical mode and echo are disabled. Signal generation stays enabled, which means that
+/// `Ctrl+C` still terminates the process. The terminal is restored by a signal handler before the
+/// process is terminated.
+#[cfg(unix)]
+struct RawMode {
+    original: libc::termios,
+    previous_handlers: [libc::sighandler_t; 2],
+}
+
+#[cfg(unix)]
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        // SAFETY: `termios` is a plain C struct, which is initialized by `tcgetattr`.
+        let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+        // SAFETY: The pointer is valid for the duration of the call.
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let original = termios;
+
+        termios.c_lflag &= !(libc::ICANON | libc::ECHO);
+        // Reads return immediately, even if no input is available.
+        termios.c_cc[libc::VMIN] = 0;
+        termios.c_cc[libc::VTIME] = 0;
+
+        // SAFETY: The pointer is valid for the duration of the call.
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // The first saved state is kept if raw mode is enabled more than once, because it's the
+        // state before any terminal window was created.
+        let _ = ORIGINAL_TERMIOS.set(original);
+        let previous_handlers = RESTORE_SIGNALS.map(|signal| {
+            // SAFETY: The handler only calls async-signal-safe functions.
+            unsafe {
+                libc::signal(
+                    signal,
+                    restore_and_terminate as *const () as libc::sighandler_t,
+                )
+            }
+        });
+
+        Ok(Self {
+            original,
+            previous_handlers,
+        })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        for (signal, handler) in RESTORE_SIGNALS.into_iter().zip(self.previous_handlers) {
+            // SAFETY: The previous handler was returned by `signal`.
+            unsafe { libc::signal(signal, handler) };
+        }
+
+        // SAFETY: The pointer is valid for the duration of the call.
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original) };
+    }
+}
+
+/// Signal handler that restores the terminal and terminates the process.
+#[cfg(unix)]
+extern "C" fn restore_and_terminate(signal: libc::c_int) {
+    if let Some(original) = ORIGINAL_TERMIOS.get() {
+        // SAFETY: The pointer is valid for the duration of the call.
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, original) };
+    }
+
+    // SAFETY: `write`, `signal` and `raise` are async-signal-safe and the buffer is valid for
+    // reads of `RESET_TERMINAL.len()` bytes.
+    unsafe {
+        libc::write(
+            libc::STDOUT_FILENO,
+            RESET_TERMINAL.as_ptr().cast(),
+            RESET_TERMINAL.len(),
+        );
+        libc::signal(signal, libc::SIG_DFL);
+        libc::raise(signal);
+    }
+}
+
+/// Reads all pending input from stdin without blocking.
+#[cfg(unix)]
+fn read_input() -> Vec<u8> {
+    let mut input = Vec::new();
+    let mut buffer = [0u8; 256];
+
+    loop {
+        // SAFETY: The buffer is valid for writes of `buffer.len()` bytes.
+        let len =
+            unsafe { libc::read(libc::STDIN_FILENO, buffer.as_mut_ptr().cast(), buffer.len()) };
+        if len <= 0 {
+            break;
+        }
+        input.extend_from_slice(&buffer[..len as usize]);
+    }
+
+    input
+}
+
+/// Returns the terminal size in columns and rows.
+#[cfg(unix)]
+fn terminal_size() -> Option<(u16, u16)> {
+    // SAFETY: `winsize` is a plain C struct, which is initialized by the `ioctl` call.
+    let mut size = unsafe { std::mem::zeroed::<libc::winsize>() };
+    // SAFETY: The pointer is valid for the duration of the call.
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+
+    (result == 0 && size.ws_col > 0 && size.ws_row > 0).then_some((size.ws_col, size.ws_row))
+}
+
+#[cfg(not(unix))]
+fn terminal_size() -> Option<(u16, u16)> {
+    None
+}
+
+fn pixel(image: &OutputImage<Rgb888>, x: u32, y: u32) -> Rgb888 {
+    let index = (y * image.size().width + x) as usize * 3;
+    let rgb = &image.data[index..index + 3];
+
+    Rgb888::new(rgb[0], rgb[1], rgb[2])
+}
+
+/// Renders an image using half block characters.
+///
+/// The output is cropped to the terminal size and doesn't end with a line break to prevent the
+/// terminal from scrolling.
+fn render_half_blocks(
+    image: &OutputImage<Rgb888>,
+    terminal_size: Option<(u16, u16)>,
+    output: &mut Vec<u8>,
+) {
+    let size = image.size();
+    let (columns, rows) = terminal_size
+        .map(|(columns, rows)| (u32::from(columns), u32::from(rows)))
+        .unwrap_or((u32::MAX, u32::MAX));
+
+    for row in 0..size.height.div_ceil(2).min(rows) {
+        if row > 0 {
+            output.extend_from_slice(b"\r\n");
+        }
+
+        let mut foreground = None;
+        let mut background = None;
+
+        for x in 0..size.width.min(columns) {
+            let top = pixel(image, x, row * 2);
+            let bottom = (row * 2 + 1 < size.height).then(|| pixel(image, x, row * 2 + 1));
+
+            if bottom != background {
+                match bottom {
+                    Some(color) => {
+                        let _ = write!(
+                            output,
+                            "\x1b[48;2;{};{};{}m",
+                            color.r(),
+                            color.g(),
+                            color.b()
+                        );
+                    }
+                    None => output.extend_from_slice(b"\x1b[49m"),
+                }
+                background = bottom;
+            }
+
+            if bottom == Some(top) {
+                output.push(b' ');
+                continue;
+            }
+
+            if foreground != Some(top) {
+                let _ = write!(output, "\x1b[38;2;{};{};{}m", top.r(), top.g(), top.b());
+                foreground = Some(top);
+            }
+            output.extend_from_slice("▀".as_bytes());
+        }
+
+        output.extend_from_slice(b"\x1b[0m");
+    }
+}
+
+/// Renders an image using sixel graphics.
+fn render_sixel(image: &OutputImage<Rgb888>, output: &mut Vec<u8>) {
+    let size = image.size();
+
+    // Use the exact colors if possible, otherwise reduce the colors to a 3-3-2 bit palette.
+    let mut palette = Vec::new();
+    let mut palette_indices = HashMap::new();
+    for y in 0..size.height {
+        for x in 0..size.width {
+            let color = pixel(image, x, y);
+            palette_indices.entry(color).or_insert_with(|| {
+                palette.push(color);
+                palette.len() - 1
+            });
+        }
+    }
+
+    let reduce = palette.len() > 256;
+    let color_index = |color: Rgb888| {
+        if reduce {
+            usize::from((color.r() & 0xE0) | ((color.g() & 0xE0) >> 3) | (color.b() >> 6))
+        } else {
+            palette_indices[&color]
+        }
+    };
+    if reduce {
+        let scale = |value: u8, max: u16| (u16::from(value) * 255 / max) as u8;
+        palette = (0..=255u8)
+            .map(|i| Rgb888::new(scale(i >> 5, 7), scale((i >> 2) & 7, 7), scale(i & 3, 3)))
+            .collect();
+    }
+
+    let _ = write!(output, "\x1bP0;1q\"1;1;{};{}", size.width, size.height);
+    for (index, color) in palette.iter().enumerate() {
+        let percent = |value: u8| u32::from(value) * 100 / 255;
+        let _ = write!(
+            output,
+            "#{index};2;{};{};{}",
+            percent(color.r()),
+            percent(color.g()),
+            percent(color.b())
+        );
+    }
+
+    let mut band = Vec::with_capacity(size.width as usize);
+    for band_y in (0..size.height).step_by(6) {
+        let band_height = (size.height - band_y).min(6);
+
+        let mut used = vec![false; palette.len()];
+        for y in band_y..band_y + band_height {
+            for x in 0..size.width {
+                used[color_index(pixel(image, x, y))] = true;
+            }
+        }
+
+        for index in (0..palette.len()).filter(|index| used[*index]) {
+            band.clear();
+            for x in 0..size.width {
+                let mut bits = 0;
+                for dy in 0..band_height {
+                    if color_index(pixel(image, x, band_y + dy)) == index {
+                        bits |= 1 << dy;
+                    }
+                }
+                band.push(b'?' + bits);
+            }
+
+            while band.last() == Some(&b'?') {
+                band.pop();
+            }
+
+            let _ = write!(output, "#{index}");
+            write_sixel_runs(&band, output);
+            output.push(b'$');
+        }
+
+        output.push(b'-');
+    }
+
+    output.extend_from_slice(b"\x1b\\");
+}
+
+/// Writes sixel characters using run length encoding.
+fn write_sixel_runs(sixels: &[u8], output: &mut Vec<u8>) {
+    for run in sixels.chunk_by(|a, b| a == b) {
+        if run.len() > 3 {
+            let _ = write!(output, "!{}", run.len());
+            output.push(run[0]);
+        } else {
+            output.extend_from_slice(run);
+        }
+    }
+}
+
+/// Renders an image using the kitty graphics protocol.
+///
+/// The image always uses the same image and placement ID to replace the previous frame.
+fn render_kitty(image: &OutputImage<Rgb888>, output: &mut Vec<u8>) {
+    let size = image.size();
+    let data = base64::engine::general_purpose::STANDARD.encode(&image.data);
+
+    let mut chunks = data.as_bytes().chunks(4096).peekable();
+    let mut first = true;
+    while let Some(chunk) = chunks.next() {
+        let more = u8::from(chunks.peek().is_some());
+
+        if first {
+            let _ = write!(
+                output,
+                "\x1b_Ga=T,f=24,s={},v={},i=1,p=1,q=2,C=1,m={more};",
+                size.width, size.height
+            );
+            first = false;
+        } else {
+            let _ = write!(output, "\x1b_Gm={more};");
+        }
+        output.extend_from_slice(chunk);
+        output.extend_from_slice(b"\x1b\\");
+    }
+}
+
+/// Converts terminal input into simulator events.
+///
+/// Terminals only report key presses, which is why a `KeyDown` event is always directly followed
+/// by a `KeyUp` event.
+fn parse_input(mut input: &[u8], events: &mut VecDeque<SimulatorEvent>) {
+    while !input.is_empty() {
+        let (len, key) = parse_key(input);
+        input = &input[len..];
+
+        if let Some((keycode, keymod)) = key {
+            events.push_back(SimulatorEvent::KeyDown {
+                keycode,
+                keymod,
+                repeat: false,
+            });
+            events.push_back(SimulatorEvent::KeyUp {
+                keycode,
+                keymod,
+                repeat: false,
+            });
+        }
+    }
+}
+
+/// Parses a single key from terminal input.
+///
+/// Returns the number of consumed bytes and the key, if the input was recognized.
+fn parse_key(input: &[u8]) -> (usize, Option<(Keycode, Mod)>) {
+    let key = |keycode: Keycode| Some((keycode, Mod::NOMOD));
+
+    match input[0] {
+        0x1B => match input.get(1) {
+            None => (1, key(Keycode::ESCAPE)),
+            Some(b'[' | b'O') => parse_escape_sequence(input),
+            Some(_) => {
+                // Alt is reported by prefixing the key with an escape character.
+                let (len, key) = parse_key(&input[1..]);
+                (
+                    len + 1,
+                    key.map(|(keycode, keymod)| (keycode, keymod | Mod::LALTMOD)),
+                )
+            }
+        },
+        b'\r' | b'\n' => (1, key(Keycode::RETURN)),
+        b'\t' => (1, key(Keycode::TAB)),
+        0x08 | 0x7F => (1, key(Keycode::BACKSPACE)),
+        byte @ 0x01..=0x1A => (
+            1,
+            Keycode::from_i32(i32::from(b'a' + byte - 1)).map(|keycode| (keycode, Mod::LCTRLMOD)),
+        ),
+        byte @ b'A'..=b'Z' => (
+            1,
+            Keycode::from_i32(i32::from(byte.to_ascii_lowercase()))
+                .map(|keycode| (keycode, Mod::LSHIFTMOD)),
+        ),
+        // The keycodes of printable ASCII characters match their character codes.
+        byte @ 0x20..=0x7E => (1, Keycode::from_i32(i32::from(byte)).and_then(key)),
+        byte => {
+            // Skip other characters, including multi byte UTF-8 characters.
+            let len = match byte {
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                _ => 1,
+            };
+            (len.min(input.len()), None)
+        }
+    }
+}
+
+/// Parses a CSI or SS3 escape sequence, which is used for special keys.
+fn parse_escape_sequence(input: &[u8]) -> (usize, Option<(Keycode, Mod)>) {
+    let Some(end) = input[2..]
+        .iter()
+        .position(|byte| (0x40..=0x7E).contains(byte))
+        .map(|position| position + 2)
+    else {
+        return (input.len(), None);
+    };
+
+    let mut parameters = input[2..end].split(|byte| *byte == b';').map(|parameter| {
+        std::str::from_utf8(parameter)
+            .ok()
+            .and_then(|parameter| parameter.parse::<u32>().ok())
+    });
+    let number = parameters.next().flatten();
+    let modifiers = parameters.next().flatten().unwrap_or(1).saturating_sub(1);
+
+    let keycode = match (input[end], number) {
+        (b'A', _) => Keycode::UP,
+        (b'B', _) => Keycode::DOWN,
+        (b'C', _) => Keycode::RIGHT,
+        (b'D', _) => Keycode::LEFT,
+        (b'H', _) | (b'~', Some(1 | 7)) => Keycode::HOME,
+        (b'F', _) | (b'~', Some(4 | 8)) => Keycode::END,
+        (b'P', _) => Keycode::F1,
+        (b'Q', _) => Keycode::F2,
+        (b'R', _) => Keycode::F3,
+        (b'S', _) => Keycode::F4,
+        (b'~', Some(2)) => Keycode::INSERT,
+        (b'~', Some(3)) => Keycode::DELETE,
+        (b'~', Some(5)) => Keycode::PAGEUP,
+        (b'~', Some(6)) => Keycode::PAGEDOWN,
+        (b'~', Some(15)) => Keycode::F5,
+        (b'~', Some(17)) => Keycode::F6,
+        (b'~', Some(18)) => Keycode::F7,
+        (b'~', Some(19)) => Keycode::F8,
+        (b'~', Some(20)) => Keycode::F9,
+        (b'~', Some(21)) => Keycode::F10,
+        (b'~', Some(23)) => Keycode::F11,
+        (b'~', Some(24)) => Keycode::F12,
+        _ => return (end + 1, None),
+    };
+
+    let mut keymod = Mod::NOMOD;
+    if modifiers & 1 != 0 {
+        keymod |= Mod::LSHIFTMOD;
+    }
+    if modifiers & 2 != 0 {
+        keymod |= Mod::LALTMOD;
+    }
+    if modifiers & 4 != 0 {
+        keymod |= Mod::LCTRLMOD;
+    }
+
+    (end + 1, Some((keycode, keymod)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(input: &[u8]) -> Vec<(Keycode, Mod)> {
+        let mut events = VecDeque::new();
+        parse_input(input, &mut events);
+
+        events
+            .into_iter()
+            .filter_map(|event| match event {
+                SimulatorEvent::KeyDown {
+                    keycode, keymod, ..
+                } => Some((keycode, keymod)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_keys() {
+        assert_eq!(
+            keys(b"aZ1 \r"),
+            [
+                (Keycode::A, Mod::NOMOD),
+                (Keycode::Z, Mod::LSHIFTMOD),
+                (Keycode::NUM_1, Mod::NOMOD),
+                (Keycode::SPACE, Mod::NOMOD),
+                (Keycode::RETURN, Mod::NOMOD),
+            ]
+        );
+        assert_eq!(
+            keys(b"\x1b[A\x1b[1;5C\x1bOP\x1b[5~\x1bx\x01"),
+            [
+                (Keycode::UP, Mod::NOMOD),
+                (Keycode::RIGHT, Mod::LCTRLMOD),
+                (Keycode::F1, Mod::NOMOD),
+                (Keycode::PAGEUP, Mod::NOMOD),
+                (Keycode::X, Mod::LALTMOD),
+                (Keycode::A, Mod::LCTRLMOD),
+            ]
+        );
+        assert_eq!(keys("ä\x1b[99~".as_bytes()), []);
+    }
+
+    #[test]
+    fn key_up() {
+        let mut events = VecDeque::new();
+        parse_input(b"q", &mut events);
+
+        assert_eq!(
+            events,
+            [
+                SimulatorEvent::KeyDown {
+                    keycode: Keycode::Q,
+                    keymod: Mod::NOMOD,
+                    repeat: false
+                },
+                SimulatorEvent::KeyUp {
+                    keycode: Keycode::Q,
+                    keymod: Mod::NOMOD,
+                    repeat: false
+                },
+            ]
+        );
+    }
+
+    fn image() -> OutputImage<Rgb888> {
+        let mut image = OutputImage::new(Size::new(3, 3));
+        Pixel(Point::new(0, 0), Rgb888::RED)
+            .draw(&mut image)
+            .unwrap();
+        Pixel(Point::new(2, 2), Rgb888::WHITE)
+            .draw(&mut image)
+            .unwrap();
+        image
+    }
+
+    #[test]
+    fn half_blocks() {
+        let mut output = Vec::new();
+        render_half_blocks(&image(), None, &mut output);
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\x1b[48;2;0;0;0m\x1b[38;2;255;0;0m▀  \x1b[0m\r\n\
+             \x1b[38;2;0;0;0m▀▀\x1b[38;2;255;255;255m▀\x1b[0m"
+        );
+
+        let mut output = Vec::new();
+        render_half_blocks(&image(), Some((2, 1)), &mut output);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\x1b[48;2;0;0;0m\x1b[38;2;255;0;0m▀ \x1b[0m"
+        );
+    }
+
+    #[test]
+    fn sixel() {
+        let mut output = Vec::new();
+        render_sixel(&image(), &mut output);
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\x1bP0;1q\"1;1;3;3#0;2;100;0;0#1;2;0;0;0#2;2;100;100;100\
+             #0@$#1EFB$#2??C$-\x1b\\"
+        );
+
+        let mut runs = Vec::new();
+        write_sixel_runs(b"~~~~~?@@", &mut runs);
+        assert_eq!(runs, b"!5~?@@");
+    }
+
+    #[test]
+    fn sixel_reduced_palette() {
+        let mut image = OutputImage::new(Size::new(32, 32));
+        for y in 0..32 {
+            for x in 0..32 {
+                let color = Rgb888::new(x as u8 * 8, y as u8 * 8, 0);
+                Pixel(Point::new(x, y), color).draw(&mut image).unwrap();
+            }
+        }
+
+        let mut output = Vec::new();
+        render_sixel(&image, &mut output);
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("#0;2;0;0;0#1;2;0;0;33#2;2;0;0;66#3;2;0;0;100"));
+        assert!(output.contains("#73;2;28;28;33"));
+        assert!(output.contains("#255;2;100;100;100"));
+        assert!(output.ends_with("-\x1b\\"));
+    }
+
+    #[test]
+    fn kitty() {
+        let mut output = Vec::new();
+        render_kitty(&image(), &mut output);
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("\x1b_Ga=T,f=24,s=3,v=3,i=1,p=1,q=2,C=1,m=0;/wAA"));
+        assert!(output.ends_with("\x1b\\"));
+    }
+
+    #[test]
+    fn parse_graphics() {
+        assert_eq!("sixel".parse(), Ok(TerminalGraphics::Sixel));
+        assert_eq!("half-blocks".parse(), Ok(TerminalGraphics::HalfBlocks));
+        assert_eq!(
+            "png".parse::<TerminalGraphics>(),
+            Err(ParseError::new("png", "half-blocks, sixel or kitty"))
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+use crate::output_image::OutputImage;
+
+/// Streams frames into a YUV4MPEG2 (Y4M) file.
+///
+/// Y4M is an uncompressed video format which consists of a short text header followed by the raw
+/// frame data. Frames are stored with full chroma resolution (4:4:4) using the BT.601 color
+/// space with limited range, which is what most video tools expect by default.
+pub(crate) struct VideoRecorder {
+    writer: Box<dyn Write>,
+    size: Option<Size>,
+    buffer: Vec<u8>,
+}
+
+impl VideoRecorder {
+    /// Creates a new video recorder.
+    ///
+    /// If the path is `-` the video is written to stdout.
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+
+        let writer: Box<dyn Write> = if path == Path::new("-") {
+            Box::new(BufWriter::new(io::stdout()))
+        } else {
+            Box::new(BufWriter::new(File::create(path)?))
+        };
+
+        Ok(Self::from_writer(writer))
+    }
+
+    fn from_writer(writer: Box<dyn Write>) -> Self {
+        Self {
+            writer,
+            size: None,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Adds a frame to the video.
+    ///
+    /// The stream header is written before the first frame, which means that the image size and
+    /// frame rate of the first frame are used for the whole video.
+    pub fn add_frame(&mut self, image: &OutputImage<Rgb888>, frame_rate: u32) -> io::Result<()> {
+        let size = image.size();
+
+        match self.size {
+            None => {
+                writeln!(
+                    self.writer,
+                    "YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C444",
+                    size.width, size.height, frame_rate
+                )?;
+                self.size = Some(size);
+            }
+            Some(video_size) if video_size != size => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "the image size must not change during a video recording",
+                ))
+            }
+            Some(_) => {}
+        }
+
+        let pixel_count = image.data.len() / 3;
+        self.buffer.clear();
+        self.buffer.resize(pixel_count * 3, 0);
+
+        let (y_plane, chroma_planes) = self.buffer.split_at_mut(pixel_count);
+        let (u_plane, v_plane) = chroma_planes.split_at_mut(pixel_count);
+
+        for (i, rgb) in image.data.chunks_exact(3).enumerate() {
+            let [y, u, v] = rgb_to_yuv(rgb[0], rgb[1], rgb[2]);
+            y_plane[i] = y;
+            u_plane[i] = u;
+            v_plane[i] = v;
+        }
+
+        self.writer.write_all(b"FRAME\n")?;
+        self.writer.write_all(&self.buffer)
+    }
+
+    /// Flushes all buffered data.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl Drop for VideoRecorder {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Converts an RGB color to BT.601 limited range YUV.
+fn rgb_to_yuv(r: u8, g: u8, b: u8) -> [u8; 3] {
+    let (r, g, b) = (i32::from(r), i32::from(g), i32::from(b));
+
+    let y = ((66 * r + 129 * g + 25 * b + 128) >> 8) + 16;
+    let u = ((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128;
+    let v = ((112 * r - 94 * g - 18 * b + 128) >> 8) + 128;
+
+    [y as u8, u as u8, v as u8]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{cell::RefCell, rc::Rc};
+
+    /// Writer that keeps the written data accessible after the recorder was dropped.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn y4m_stream() {
+        let buffer = SharedBuffer::default();
+        let mut recorder = VideoRecorder::from_writer(Box::new(buffer.clone()));
+
+        let mut image = OutputImage::<Rgb888>::new(Size::new(3, 2));
+        image.clear(Rgb888::BLACK).unwrap();
+        Pixel(Point::new(1, 0), Rgb888::WHITE)
+            .draw(&mut image)
+            .unwrap();
+        recorder.add_frame(&image, 30).unwrap();
+
+        image.clear(Rgb888::RED).unwrap();
+        recorder.add_frame(&image, 60).unwrap();
+        drop(recorder);
+
+        let header = b"YUV4MPEG2 W3 H2 F30:1 Ip A1:1 C444\n";
+        let data = buffer.0.borrow();
+        assert!(data.starts_with(header));
+
+        let frames = data[header.len()..].chunks(6 + 3 * 6).collect::<Vec<_>>();
+        assert_eq!(frames.len(), 2);
+        assert!(frames.iter().all(|frame| frame.starts_with(b"FRAME\n")));
+
+        assert_eq!(
+            &frames[0][6..],
+            &[
+                16, 235, 16, 16, 16, 16, //
+                128, 128, 128, 128, 128, 128, //
+                128, 128, 128, 128, 128, 128, //
+            ]
+        );
+        assert_eq!(&frames[1][6..12], &[82; 6]);
+        assert_eq!(&frames[1][12..18], &[90; 6]);
+        assert_eq!(&frames[1][18..24], &[240; 6]);
+    }
+
+    #[test]
+    fn size_change() {
+        let mut recorder = VideoRecorder::from_writer(Box::new(io::sink()));
+
+        recorder
+            .add_frame(&OutputImage::new(Size::new(3, 2)), 60)
+            .unwrap();
+        let error = recorder
+            .add_frame(&OutputImage::new(Size::new(2, 3)), 60)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}